@@ -0,0 +1,371 @@
+//! Cache of loaded crates shared between requests.
+//!
+//! Generating rustdoc JSON dominates the cost of every request, so long-running callers such as
+//! the MCP server keep recently loaded crates, and the search indexes built from them, in a
+//! least-recently-used cache. Concurrent requests for the same load wait for the first one
+//! instead of repeating it.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError},
+};
+
+use rustdoc_types::Crate;
+
+use crate::{
+    cargoutils::{BinaryTarget, CrateInfo, CrateReadOptions, ResolvedTarget},
+    error::Result,
+    search::SearchIndex,
+};
+
+/// Rustdoc data loaded for a target, shared between the requests that use it.
+#[derive(Debug)]
+pub struct CrateLoad {
+    /// Resolved package location and intra-crate filter path.
+    pub(crate) resolved_target: ResolvedTarget,
+    /// Parsed rustdoc JSON for the selected target.
+    pub(crate) crate_data: Crate,
+    /// Binary target metadata for bin rendering and frontmatter output.
+    pub(crate) bin_target: Option<BinaryTarget>,
+    /// Cargo features enabled for the build.
+    features: Vec<String>,
+    /// Search indexes over public items and over all items, built on first use.
+    indexes: [OnceLock<SearchIndex>; 2],
+}
+
+impl CrateLoad {
+    /// Wrap freshly loaded rustdoc data.
+    pub(crate) fn new(
+        resolved_target: ResolvedTarget,
+        crate_data: Crate,
+        bin_target: Option<BinaryTarget>,
+        features: Vec<String>,
+    ) -> Self {
+        Self {
+            resolved_target,
+            crate_data,
+            bin_target,
+            features,
+            indexes: [OnceLock::new(), OnceLock::new()],
+        }
+    }
+
+    /// Name, version and enabled features of the loaded crate.
+    pub(crate) fn info(&self) -> CrateInfo {
+        let name = self
+            .crate_data
+            .index
+            .get(&self.crate_data.root)
+            .and_then(|root| root.name.clone())
+            .unwrap_or_default();
+        CrateInfo {
+            name,
+            version: self.crate_data.crate_version.clone(),
+            features: self.features.clone(),
+        }
+    }
+
+    /// The search index over the crate's items, including private items when requested.
+    pub(crate) fn index(&self, private_items: bool) -> &SearchIndex {
+        self.indexes[usize::from(private_items)].get_or_init(|| {
+            SearchIndex::build(
+                &self.crate_data,
+                private_items,
+                self.resolved_target.package_root(),
+            )
+        })
+    }
+}
+
+/// Identifies a crate load: the target and every option that changes the rustdoc output.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    /// Target specification as given by the caller.
+    pub(crate) target: String,
+    /// Whether default features are disabled.
+    no_default_features: bool,
+    /// Whether all features are enabled.
+    all_features: bool,
+    /// Enabled features, sorted and deduplicated.
+    features: Vec<String>,
+    /// Whether rustdoc documents private items.
+    private_items: bool,
+    /// Binary target override.
+    bin_override: Option<String>,
+    /// Whether cargo runs offline.
+    offline: bool,
+    /// Directory the target resolves against, if not the current directory.
+    root: Option<PathBuf>,
+}
+
+impl CacheKey {
+    /// The same key with rustdoc documenting private items or not.
+    pub(crate) fn with_private_items(&self, private_items: bool) -> Self {
+        Self {
+            private_items,
+            ..self.clone()
+        }
+    }
+
+    /// Build the key for loading `target`, resolved against `root`, with `options`.
+    pub(crate) fn new(target: &str, root: Option<&Path>, options: &CrateReadOptions) -> Self {
+        let mut features = options.features.clone();
+        features.sort();
+        features.dedup();
+        Self {
+            target: target.to_string(),
+            no_default_features: options.no_default_features,
+            all_features: options.all_features,
+            features,
+            private_items: options.private_items,
+            bin_override: options.bin_override.clone(),
+            offline: options.offline,
+            root: root.map(Path::to_path_buf),
+        }
+    }
+}
+
+/// A cache entry; the inner lock is held while the crate loads so identical requests wait.
+type LoadSlot = Arc<Mutex<Option<Arc<CrateLoad>>>>;
+
+/// Bookkeeping for the cached entries.
+#[derive(Default)]
+struct CacheState {
+    /// Entries by key, with the tick they were last used at.
+    slots: HashMap<CacheKey, (LoadSlot, u64)>,
+    /// Monotonic counter ordering uses of the entries.
+    clock: u64,
+}
+
+/// Least-recently-used cache of loaded crates, keyed by target, features and privacy.
+pub struct CrateCache {
+    /// Maximum number of crates kept loaded.
+    capacity: usize,
+    /// Cached entries.
+    state: Mutex<CacheState>,
+}
+
+impl fmt::Debug for CrateCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CrateCache")
+            .field("capacity", &self.capacity)
+            .field("entries", &self.lock_state().slots.len())
+            .finish()
+    }
+}
+
+impl CrateCache {
+    /// Create a cache keeping at most `capacity` crates loaded (at least one).
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// Lock the bookkeeping; a panic while it was held cannot leave it inconsistent.
+    fn lock_state(&self) -> MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Return the cached load for `key`, running `load` when it is missing.
+    ///
+    /// Callers asking for a key that is already loading wait for that load and share its result.
+    /// Failed loads are not cached.
+    pub(crate) fn get_or_load(
+        &self,
+        key: &CacheKey,
+        load: impl FnOnce() -> Result<CrateLoad>,
+    ) -> Result<Arc<CrateLoad>> {
+        let slot = {
+            let mut state = self.lock_state();
+            state.clock += 1;
+            let tick = state.clock;
+            let (slot, last_used) = state.slots.entry(key.clone()).or_default();
+            *last_used = tick;
+            Arc::clone(slot)
+        };
+
+        let mut loaded = slot.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(existing) = loaded.as_ref() {
+            return Ok(Arc::clone(existing));
+        }
+        match load() {
+            Ok(fresh) => {
+                let fresh = Arc::new(fresh);
+                *loaded = Some(Arc::clone(&fresh));
+                drop(loaded);
+                self.evict(key);
+                Ok(fresh)
+            }
+            Err(error) => {
+                drop(loaded);
+                let mut state = self.lock_state();
+                if state
+                    .slots
+                    .get(key)
+                    .is_some_and(|(current, _)| Arc::ptr_eq(current, &slot))
+                {
+                    state.slots.remove(key);
+                }
+                Err(error)
+            }
+        }
+    }
+
+    /// Return the load cached for `key` without loading it or marking it as used.
+    ///
+    /// Entries still loading count as missing.
+    pub(crate) fn peek(&self, key: &CacheKey) -> Option<Arc<CrateLoad>> {
+        let slot = Arc::clone(&self.lock_state().slots.get(key)?.0);
+        let loaded = slot.try_lock().ok()?;
+        loaded.as_ref().map(Arc::clone)
+    }
+
+    /// Drop the least recently used entries other than `keep` until the cache fits.
+    fn evict(&self, keep: &CacheKey) {
+        let mut state = self.lock_state();
+        while state.slots.len() > self.capacity {
+            let Some(oldest) = state
+                .slots
+                .iter()
+                .filter(|(key, _)| *key != keep)
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            state.slots.remove(&oldest);
+        }
+    }
+
+    /// Drop every loaded entry for which `matches` holds, returning how many were dropped.
+    ///
+    /// Entries still loading are left alone.
+    pub(crate) fn invalidate(&self, matches: impl Fn(&CacheKey, &CrateLoad) -> bool) -> usize {
+        let mut state = self.lock_state();
+        let before = state.slots.len();
+        state.slots.retain(|key, (slot, _)| match slot.try_lock() {
+            Ok(loaded) => !loaded.as_ref().is_some_and(|load| matches(key, load)),
+            Err(_) => true,
+        });
+        before - state.slots.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{
+            Arc, Barrier,
+            atomic::{AtomicUsize, Ordering},
+        },
+        thread,
+        time::Duration,
+    };
+
+    use rustdoc_types::{Crate, FORMAT_VERSION, Id, Target};
+
+    use super::{CacheKey, CrateCache, CrateLoad};
+    use crate::{
+        cargoutils::{CrateReadOptions, resolve_target},
+        error::{Result, RuskelError},
+        progress::Progress,
+    };
+
+    fn key(target: &str) -> CacheKey {
+        let options = CrateReadOptions {
+            no_default_features: false,
+            all_features: false,
+            features: Vec::new(),
+            private_items: false,
+            silent: true,
+            offline: true,
+            bin_override: None,
+            progress: Progress::default(),
+        };
+        CacheKey::new(target, None, &options)
+    }
+
+    fn load(target: &str) -> Result<CrateLoad> {
+        let crate_data = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            target: Target {
+                triple: String::new(),
+                target_features: Vec::new(),
+            },
+            format_version: FORMAT_VERSION,
+        };
+        Ok(CrateLoad::new(
+            resolve_target(target, None, true)?,
+            crate_data,
+            None,
+            Vec::new(),
+        ))
+    }
+
+    #[test]
+    fn cache_reuses_loads_and_evicts_least_recently_used() -> Result<()> {
+        let cache = CrateCache::new(2);
+        let loads = AtomicUsize::new(0);
+        let get = |target: &str| {
+            cache.get_or_load(&key(target), || {
+                loads.fetch_add(1, Ordering::SeqCst);
+                load("std")
+            })
+        };
+
+        let first = get("core")?;
+        assert!(Arc::ptr_eq(&first, &get("core")?));
+        get("alloc")?;
+        get("core")?;
+        get("std")?;
+        assert_eq!(loads.load(Ordering::SeqCst), 3);
+
+        // `alloc` was least recently used when `std` arrived.
+        get("core")?;
+        assert_eq!(loads.load(Ordering::SeqCst), 3);
+        get("alloc")?;
+        assert_eq!(loads.load(Ordering::SeqCst), 4);
+
+        let error = cache
+            .get_or_load(&key("broken"), || {
+                Err(RuskelError::Generate("boom".to_string()))
+            })
+            .unwrap_err();
+        assert!(error.to_string().contains("boom"));
+        assert_eq!(cache.invalidate(|key, _| key.target == "broken"), 0);
+        assert_eq!(cache.invalidate(|_, _| true), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn cache_coalesces_concurrent_loads() {
+        let cache = CrateCache::new(4);
+        let loads = AtomicUsize::new(0);
+        let barrier = Barrier::new(4);
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    barrier.wait();
+                    cache
+                        .get_or_load(&key("std"), || {
+                            loads.fetch_add(1, Ordering::SeqCst);
+                            thread::sleep(Duration::from_millis(50));
+                            load("std")
+                        })
+                        .expect("load");
+                });
+            }
+        });
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+    }
+}
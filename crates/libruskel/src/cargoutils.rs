@@ -1,11 +1,22 @@
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
 use std::{
-    collections::HashMap,
+    collections::{BTreeSet, HashMap, HashSet},
     env, fs,
-    io::{self, Write},
+    io::{BufRead, BufReader, Write},
     path::{Component, Path, PathBuf, absolute},
+    process::{Child, Command, Stdio},
+    thread,
+    time::Duration,
 };
 
-use cargo::{core::Workspace, ops, util::context::GlobalContext};
+use cargo::{
+    core::{
+        FeatureMap, FeatureValue, Package, PackageId, PackageSet, Resolve, SourceId, Workspace,
+    },
+    ops,
+    util::context::GlobalContext,
+};
 use once_cell::sync::Lazy;
 use rustdoc_json::PackageTarget;
 use rustdoc_types::Crate;
@@ -15,7 +26,7 @@ use tempfile::TempDir;
 use super::target::{Entrypoint, Target};
 use crate::{
     error::{Result, RuskelError, convert_cargo_error},
-    toolchain::nightly_sysroot,
+    progress::{LoadStage, Progress},
 };
 
 /// Check if a crate name is a standard library crate
@@ -142,40 +153,6 @@ fn resolve_std_reexport(target_str: &str) -> Option<String> {
     }
 }
 
-/// Load pre-built JSON documentation for a standard library crate
-fn load_std_library_json(crate_name: &str, display_name: Option<&str>) -> Result<Crate> {
-    let sysroot = nightly_sysroot()?;
-    let json_path = sysroot
-        .join("share")
-        .join("doc")
-        .join("rust")
-        .join("json")
-        .join(format!("{crate_name}.json"));
-
-    if !json_path.exists() {
-        return Err(RuskelError::Generate(
-            "Standard library documentation not available (missing rust-docs-json component)"
-                .to_string(),
-        ));
-    }
-
-    let json_content = fs::read_to_string(&json_path)?;
-    let mut crate_data: Crate = serde_json::from_str(&json_content).map_err(|e| {
-        RuskelError::Generate(format!(
-            "Failed to parse standard library JSON documentation: {e}"
-        ))
-    })?;
-
-    // If a display name is provided, update the root module name
-    if let Some(display) = display_name
-        && let Some(root_item) = crate_data.index.get_mut(&crate_data.root)
-    {
-        root_item.name = Some(display.to_string());
-    }
-
-    Ok(crate_data)
-}
-
 /// A path to a crate. This can be a directory on the filesystem or the virtual std library.
 #[derive(Debug)]
 struct CargoPath {
@@ -290,60 +267,22 @@ impl CargoPath {
             })
     }
 
-    /// Load rustdoc JSON for the crate represented by this cargo path.
-    /// Read the crate data for this resolved target using rustdoc JSON generation.
-    pub fn read_crate(&self, options: &CrateReadOptions) -> Result<CrateRead> {
-        // Handle standard library crates specially
-        if let Some((actual_crate, display_crate)) = self.std_names() {
-            let display_name = if actual_crate != display_crate {
-                Some(display_crate)
-            } else {
-                None
-            };
-            return Ok(CrateRead {
-                crate_data: load_std_library_json(actual_crate, display_name)?,
-                bin_target: None,
-            });
-        }
-
+    /// Generate and read rustdoc JSON for the package at this path.
+    fn read_crate(&self, options: &CrateReadOptions) -> Result<CrateRead> {
         let manifest_path = self.manifest_path()?;
         let PackageTargetSelection {
             package_target,
             bin_target,
-        } = select_package_target(
-            &manifest_path,
-            options.offline,
-            options.bin_override.as_deref(),
-        )?;
+            json_path,
+            features,
+        } = select_package_target(&manifest_path, options)?;
         let include_private =
             options.private_items || bin_target.as_ref().is_some_and(|target| target.is_bin_only);
 
-        let mut captured_stdout = Vec::new();
-        let mut captured_stderr = Vec::new();
-
-        let build_result = rustdoc_json::Builder::default()
-            .toolchain("nightly")
-            .manifest_path(manifest_path)
-            .package_target(package_target)
-            .document_private_items(include_private)
-            .no_default_features(options.no_default_features)
-            .all_features(options.all_features)
-            .features(&options.features)
-            .quiet(options.silent)
-            .silent(false)
-            .build_with_captured_output(&mut captured_stdout, &mut captured_stderr);
-
-        if !options.silent {
-            if !captured_stdout.is_empty() && io::stdout().write_all(&captured_stdout).is_err() {
-                // Best-effort output mirroring; ignore write failures.
-            }
-            if !captured_stderr.is_empty() && io::stderr().write_all(&captured_stderr).is_err() {
-                // Best-effort output mirroring; ignore write failures.
-            }
-        }
+        let mut command =
+            rustdoc_command(&manifest_path, &package_target, include_private, options);
+        run_rustdoc(&mut command, options)?;
 
-        let json_path = build_result
-            .map_err(|err| map_rustdoc_build_error(&err, &captured_stderr, options.silent))?;
         let json_content = fs::read_to_string(&json_path)?;
         let crate_data: Crate = serde_json::from_str(&json_content).map_err(|e| {
             RuskelError::Generate(format!(
@@ -353,6 +292,7 @@ impl CargoPath {
         Ok(CrateRead {
             crate_data,
             bin_target,
+            features,
         })
     }
 
@@ -443,6 +383,143 @@ impl CargoPath {
         Ok(None)
     }
 
+    /// Open and resolve the workspace of this project for listing its crates.
+    fn project_graph<'gctx>(&self, config: &'gctx GlobalContext) -> Result<ProjectGraph<'gctx>> {
+        // Cargo rejects members whose manifest path differs from the one the workspace was
+        // opened with, so `..` components must be resolved first.
+        let manifest_path = self.canonical_path()?.join("Cargo.toml");
+        let workspace =
+            Workspace::new(&manifest_path, config).map_err(|err| convert_cargo_error(&err))?;
+        // Resolving without a fetch avoids downloading packages for every platform.
+        let (packages, resolve) =
+            ops::resolve_ws(&workspace, true).map_err(|err| convert_cargo_error(&err))?;
+
+        let member_ids: HashSet<PackageId> = workspace
+            .members()
+            .map(|package| package.package_id())
+            .collect();
+        let dependency_ids = member_ids
+            .iter()
+            .flat_map(|member| resolve.deps(*member))
+            .map(|(id, _)| id)
+            .filter(|id| !member_ids.contains(id))
+            .collect();
+        Ok(ProjectGraph {
+            workspace,
+            packages,
+            resolve,
+            dependency_ids,
+        })
+    }
+
+    /// List the workspace members followed by the packages they depend on directly, with the
+    /// versions cargo resolved for them.
+    ///
+    /// Dependencies on other workspace members are only listed as members.
+    pub fn project_crates(&self, offline: bool) -> Result<Vec<ProjectCrate>> {
+        if self.is_std_library() {
+            return Ok(Vec::new());
+        }
+
+        let config = create_quiet_cargo_config(offline)?;
+        let graph = self.project_graph(&config)?;
+        let members = graph.members().into_iter().map(|package| ProjectCrate {
+            name: package.name().to_string(),
+            version: package.version().to_string(),
+            member: true,
+        });
+        let dependencies = graph.dependency_ids.iter().map(|id| ProjectCrate {
+            name: id.name().to_string(),
+            version: id.version().to_string(),
+            member: false,
+        });
+        Ok(members.chain(dependencies).collect())
+    }
+
+    /// List the workspace members followed by the packages they depend on directly, like
+    /// [`Self::project_crates`], with their features, source, docs and a target naming each.
+    ///
+    /// Unlike [`Self::project_crates`], this loads the manifest of every listed dependency,
+    /// downloading those cargo has not cached yet.
+    pub fn project_packages(&self, offline: bool) -> Result<Vec<PackageInfo>> {
+        if self.is_std_library() {
+            return Ok(Vec::new());
+        }
+
+        let config = create_quiet_cargo_config(offline)?;
+        let graph = self.project_graph(&config)?;
+        let resolve = &graph.resolve;
+        let members = graph.members();
+        let member_names: HashSet<String> = members
+            .iter()
+            .map(|package| to_import_name(package.name().as_str()))
+            .collect();
+        let mut dependencies = graph
+            .packages
+            .get_many(graph.dependency_ids.iter().copied())
+            .map_err(|err| convert_cargo_error(&err))?;
+        dependencies.sort_by_key(|package| package.package_id());
+
+        // A dependency's name only resolves to it when no member or other package in the graph
+        // shares the name; the rest are addressed by directory.
+        let mut name_counts: HashMap<String, usize> = HashMap::new();
+        for id in resolve.iter() {
+            *name_counts
+                .entry(to_import_name(id.name().as_str()))
+                .or_default() += 1;
+        }
+
+        let workspace_target_dir = graph.workspace.target_dir().into_path_unlocked();
+        let configured_target_dir = config
+            .target_dir()
+            .map_err(|err| convert_cargo_error(&err))?
+            .map(|dir| dir.into_path_unlocked());
+        let describe = |package: &Package, member: bool| -> Result<PackageInfo> {
+            let id = package.package_id();
+            let import_name = to_import_name(package.name().as_str());
+            let target = if member
+                || (!member_names.contains(&import_name) && name_counts[&import_name] == 1)
+            {
+                package.name().to_string()
+            } else {
+                Self::manifest_dir_from_path(package.manifest_path(), package.name().as_str())?
+                    .display()
+                    .to_string()
+            };
+            let mut features: Vec<String> = resolve
+                .features(id)
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            features.sort();
+            // Dependencies are documented in place, each as the root of its own workspace.
+            let target_dir = match (&configured_target_dir, member) {
+                (_, true) => workspace_target_dir.clone(),
+                (Some(dir), false) => dir.clone(),
+                (None, false) => package.root().join("target"),
+            };
+            Ok(PackageInfo {
+                name: package.name().to_string(),
+                version: package.version().to_string(),
+                member,
+                features,
+                source: PackageSource::of(id.source_id()),
+                docs_cached: rustdoc_json_path(&target_dir, &lib_json_name(package)).exists(),
+                target,
+            })
+        };
+
+        members
+            .into_iter()
+            .map(|package| describe(package, true))
+            .chain(
+                dependencies
+                    .into_iter()
+                    .map(|package| describe(package, false)),
+            )
+            .collect()
+    }
+
     /// Walk upwards from `start_dir` to locate the closest `Cargo.toml`.
     pub fn nearest_manifest(start_dir: &Path) -> Option<Self> {
         let mut current_dir = start_dir.to_path_buf();
@@ -512,16 +589,87 @@ fn create_quiet_cargo_config(offline: bool) -> Result<GlobalContext> {
 /// Determine which Cargo target should be used for rustdoc JSON generation.
 fn select_package_target(
     manifest_path: &Path,
-    offline: bool,
-    bin_override: Option<&str>,
+    options: &CrateReadOptions,
 ) -> Result<PackageTargetSelection> {
-    let config = create_quiet_cargo_config(offline)?;
+    let config = create_quiet_cargo_config(options.offline)?;
     let workspace =
         Workspace::new(manifest_path, &config).map_err(|err| convert_cargo_error(&err))?;
     let package = workspace
         .current()
         .map_err(|err| convert_cargo_error(&err))?;
 
+    let (package_target, bin_target) =
+        choose_package_target(package, options.bin_override.as_deref())?;
+    let json_name = match &package_target {
+        PackageTarget::Bin(name) => to_import_name(name),
+        _ => lib_json_name(package),
+    };
+    Ok(PackageTargetSelection {
+        package_target,
+        bin_target,
+        json_path: rustdoc_json_path(&workspace.target_dir().into_path_unlocked(), &json_name),
+        features: enabled_features(package.summary().features(), options),
+    })
+}
+
+/// Name of the rustdoc JSON file documenting the library of `package`, without extension.
+fn lib_json_name(package: &Package) -> String {
+    package
+        .targets()
+        .iter()
+        .find(|target| target.is_lib())
+        .map_or_else(
+            || to_import_name(package.name().as_str()),
+            |lib| lib.crate_name(),
+        )
+}
+
+/// Where `cargo rustdoc` writes the JSON named `json_name` under `target_dir`.
+fn rustdoc_json_path(target_dir: &Path, json_name: &str) -> PathBuf {
+    target_dir.join("doc").join(format!("{json_name}.json"))
+}
+
+/// The features of a package that a build with `options` enables, sorted by name.
+///
+/// Expands `default` and every feature enabled by another feature, including the implicit
+/// features of optional dependencies enabled through `dep/feature` values.
+fn enabled_features(feature_map: &FeatureMap, options: &CrateReadOptions) -> Vec<String> {
+    if options.all_features {
+        return feature_map.keys().map(ToString::to_string).collect();
+    }
+
+    let mut pending: Vec<&str> = options.features.iter().map(String::as_str).collect();
+    if !options.no_default_features {
+        pending.push("default");
+    }
+    let mut enabled = BTreeSet::new();
+    while let Some(feature) = pending.pop() {
+        let Some((name, values)) = feature_map.get_key_value(feature) else {
+            continue;
+        };
+        if !enabled.insert(name.to_string()) {
+            continue;
+        }
+        for value in values {
+            match value {
+                FeatureValue::Feature(name) => pending.push(name.as_str()),
+                FeatureValue::DepFeature {
+                    dep_name,
+                    weak: false,
+                    ..
+                } => pending.push(dep_name.as_str()),
+                _ => {}
+            }
+        }
+    }
+    enabled.into_iter().collect()
+}
+
+/// Pick the library target, or the binary target to document when there is no library.
+fn choose_package_target(
+    package: &Package,
+    bin_override: Option<&str>,
+) -> Result<(PackageTarget, Option<BinaryTarget>)> {
     let has_lib = package.targets().iter().any(|target| target.is_lib());
     let bin_targets: Vec<_> = package
         .targets()
@@ -532,13 +680,13 @@ fn select_package_target(
 
     if let Some(bin_name) = bin_override {
         if bin_names.contains(&bin_name) {
-            return Ok(PackageTargetSelection {
-                package_target: PackageTarget::Bin(bin_name.to_string()),
-                bin_target: Some(BinaryTarget {
+            return Ok((
+                PackageTarget::Bin(bin_name.to_string()),
+                Some(BinaryTarget {
                     name: bin_name.to_string(),
                     is_bin_only: !has_lib,
                 }),
-            });
+            ));
         }
 
         let available = if bin_names.is_empty() {
@@ -553,10 +701,7 @@ fn select_package_target(
     }
 
     if has_lib {
-        return Ok(PackageTargetSelection {
-            package_target: PackageTarget::Lib,
-            bin_target: None,
-        });
+        return Ok((PackageTarget::Lib, None));
     }
 
     if bin_names.is_empty() {
@@ -568,24 +713,24 @@ fn select_package_target(
     if let Some(default_run) = package.manifest().default_run()
         && bin_names.contains(&default_run)
     {
-        return Ok(PackageTargetSelection {
-            package_target: PackageTarget::Bin(default_run.to_string()),
-            bin_target: Some(BinaryTarget {
+        return Ok((
+            PackageTarget::Bin(default_run.to_string()),
+            Some(BinaryTarget {
                 name: default_run.to_string(),
                 is_bin_only: true,
             }),
-        });
+        ));
     }
 
     if bin_names.len() == 1 {
         let name = bin_names[0];
-        return Ok(PackageTargetSelection {
-            package_target: PackageTarget::Bin(name.to_string()),
-            bin_target: Some(BinaryTarget {
+        return Ok((
+            PackageTarget::Bin(name.to_string()),
+            Some(BinaryTarget {
                 name: name.to_string(),
                 is_bin_only: true,
             }),
-        });
+        ));
     }
 
     Err(RuskelError::Generate(format!(
@@ -646,6 +791,105 @@ fn create_dummy_crate(
     Ok(CargoPath::from_temp_dir(temp_dir))
 }
 
+/// A crate the current project builds: a workspace member or one of their direct dependencies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectCrate {
+    /// Package name.
+    pub name: String,
+    /// Package version; for dependencies, the version cargo resolved.
+    pub version: String,
+    /// Whether the crate is a workspace member rather than a dependency.
+    pub member: bool,
+}
+
+/// A project's workspace resolved without fetching, as listed by [`CargoPath::project_crates`]
+/// and [`CargoPath::project_packages`].
+struct ProjectGraph<'gctx> {
+    /// The project's workspace.
+    workspace: Workspace<'gctx>,
+    /// Packages of the resolved graph, downloaded on demand.
+    packages: PackageSet<'gctx>,
+    /// The resolved dependency graph.
+    resolve: Resolve,
+    /// Packages the members depend on directly, other than members, in id order.
+    dependency_ids: BTreeSet<PackageId>,
+}
+
+impl ProjectGraph<'_> {
+    /// The workspace members, sorted by name.
+    fn members(&self) -> Vec<&Package> {
+        let mut members: Vec<&Package> = self.workspace.members().collect();
+        members.sort_by_key(|package| package.name());
+        members
+    }
+}
+
+/// Where cargo gets a package from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageSource {
+    /// A registry such as crates.io, including local and vendored mirrors of one.
+    Registry,
+    /// A directory on disk, as for workspace members and `path` dependencies.
+    Path,
+    /// A git repository.
+    Git,
+}
+
+impl PackageSource {
+    /// Classify the cargo source `source_id`.
+    fn of(source_id: SourceId) -> Self {
+        if source_id.is_path() {
+            Self::Path
+        } else if source_id.is_git() {
+            Self::Git
+        } else {
+            Self::Registry
+        }
+    }
+
+    /// Lowercase name of the source kind, e.g. `registry`.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Registry => "registry",
+            Self::Path => "path",
+            Self::Git => "git",
+        }
+    }
+}
+
+/// A crate the current project builds, described in enough detail to pick and load it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageInfo {
+    /// Package name.
+    pub name: String,
+    /// Package version; for dependencies, the version cargo resolved.
+    pub version: String,
+    /// Whether the crate is a workspace member rather than a dependency.
+    pub member: bool,
+    /// Cargo features the project enables for the package, sorted by name.
+    pub features: Vec<String>,
+    /// Where cargo gets the package from.
+    pub source: PackageSource,
+    /// Whether rustdoc JSON from an earlier build is on disk, so loading the crate only needs
+    /// cargo to confirm it is fresh.
+    pub docs_cached: bool,
+    /// Target specification that loads the package from within the project: its name, or its
+    /// directory when another package in the dependency graph has the same name.
+    pub target: String,
+}
+
+/// The crate a target loaded, and the features it was documented with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrateInfo {
+    /// Crate name, as used in paths.
+    pub name: String,
+    /// Crate version, when rustdoc recorded one.
+    pub version: Option<String>,
+    /// Cargo features enabled for the build, with `default` and implied features expanded.
+    /// Always empty for standard library crates.
+    pub features: Vec<String>,
+}
+
 /// Metadata describing a selected binary target.
 #[derive(Debug, Clone)]
 pub struct BinaryTarget {
@@ -662,6 +906,25 @@ pub struct CrateRead {
     pub(crate) crate_data: Crate,
     /// Binary target metadata when a bin target was selected.
     pub(crate) bin_target: Option<BinaryTarget>,
+    /// Cargo features enabled for the build.
+    pub(crate) features: Vec<String>,
+}
+
+impl CrateRead {
+    /// Wrap rustdoc data read for a library target with no features enabled.
+    pub fn new(crate_data: Crate) -> Self {
+        Self {
+            crate_data,
+            bin_target: None,
+            features: Vec::new(),
+        }
+    }
+
+    /// Records the cargo features the crate was documented with.
+    pub fn with_features(mut self, features: Vec<String>) -> Self {
+        self.features = features;
+        self
+    }
 }
 
 /// Options controlling how rustdoc JSON is generated.
@@ -681,6 +944,35 @@ pub struct CrateReadOptions {
     pub(crate) offline: bool,
     /// Optional override of the binary target name.
     pub(crate) bin_override: Option<String>,
+    /// Progress hook notified of build stages and polled for cancellation.
+    pub(crate) progress: Progress,
+}
+
+impl CrateReadOptions {
+    /// Whether the build disables default features.
+    pub fn no_default_features(&self) -> bool {
+        self.no_default_features
+    }
+
+    /// Whether the build enables all features.
+    pub fn all_features(&self) -> bool {
+        self.all_features
+    }
+
+    /// Features the build enables explicitly.
+    pub fn features(&self) -> &[String] {
+        &self.features
+    }
+
+    /// Whether the rustdoc data should include private items.
+    pub fn private_items(&self) -> bool {
+        self.private_items
+    }
+
+    /// Whether cargo must stay offline.
+    pub fn offline(&self) -> bool {
+        self.offline
+    }
 }
 
 /// Internal package target selection details for rustdoc JSON.
@@ -690,6 +982,10 @@ struct PackageTargetSelection {
     package_target: PackageTarget,
     /// Binary target metadata for frontmatter output.
     bin_target: Option<BinaryTarget>,
+    /// Where rustdoc writes the JSON for the target.
+    json_path: PathBuf,
+    /// Cargo features the build enables.
+    features: Vec<String>,
 }
 
 /// A resolved Rust package or module target.
@@ -722,10 +1018,49 @@ impl ResolvedTarget {
     }
 
     /// Read the crate data for this resolved target using rustdoc JSON generation.
-    pub fn read_crate(&self, options: &CrateReadOptions) -> Result<CrateRead> {
+    pub(crate) fn read_package(&self, options: &CrateReadOptions) -> Result<CrateRead> {
         self.package_path.read_crate(options)
     }
 
+    /// Root directory of the resolved package, or `None` for standard library crates.
+    pub fn package_root(&self) -> Option<&Path> {
+        self.package_path.as_path().ok()
+    }
+
+    /// Absolute path of the package's `Cargo.toml`; fails for standard library crates.
+    pub fn manifest_path(&self) -> Result<PathBuf> {
+        self.package_path.manifest_path()
+    }
+
+    /// The standard library crate to read and the name to show it under, e.g. `("alloc", "std")`
+    /// for `std::vec`, or `None` for packages.
+    pub(crate) fn std_names(&self) -> Option<(&str, &str)> {
+        self.package_path.std_names()
+    }
+
+    /// Name of the crate the target loads, as used in paths: the library name of a package, or
+    /// the standard library crate that provides the target.
+    pub fn crate_name(&self) -> Result<String> {
+        if let Some((actual, _)) = self.std_names() {
+            return Ok(actual.to_string());
+        }
+        let manifest_path = self.manifest_path()?;
+        let manifest = cargo_toml::Manifest::from_path(&manifest_path)
+            .map_err(|err| RuskelError::ManifestParse(err.to_string()))?;
+        if let Some(name) = manifest.lib.and_then(|lib| lib.name) {
+            return Ok(name);
+        }
+        manifest
+            .package
+            .map(|package| to_import_name(&package.name))
+            .ok_or_else(|| {
+                RuskelError::Generate(format!(
+                    "'{}' is a workspace manifest without a package",
+                    manifest_path.display()
+                ))
+            })
+    }
+
     /// Resolve a standard library crate name, optionally overriding the display name.
     fn resolve_std_crate(name: &str, display_name: Option<&str>, path: &[String]) -> Option<Self> {
         is_std_library_crate(name).then(|| {
@@ -746,11 +1081,20 @@ impl ResolvedTarget {
     }
 
     /// Resolve a `Target` into a fully-qualified location and filter path.
-    pub fn from_target(target: Target, offline: bool) -> Result<Self> {
+    ///
+    /// Relative paths and workspace names resolve against `root`, or the current directory when
+    /// `root` is `None`.
+    pub(crate) fn from_target(target: Target, root: Option<&Path>, offline: bool) -> Result<Self> {
         match target.entrypoint {
-            Entrypoint::Path(path) => Self::from_path_entry(path, &target.path),
+            Entrypoint::Path(path) => {
+                let path = match root {
+                    Some(root) if path.is_relative() => root.join(path),
+                    _ => path,
+                };
+                Self::from_path_entry(path, &target.path)
+            }
             Entrypoint::Name { name, version } => {
-                Self::from_named_entry(&name, version, &target.path, offline)
+                Self::from_named_entry(&name, version, &target.path, root, offline)
             }
         }
     }
@@ -798,6 +1142,7 @@ impl ResolvedTarget {
         name: &str,
         version: Option<Version>,
         target_path: &[String],
+        root: Option<&Path>,
         offline: bool,
     ) -> Result<Self> {
         if let Some(std_target) = Self::resolve_std_crate(name, None, target_path) {
@@ -805,8 +1150,11 @@ impl ResolvedTarget {
         }
         Self::reject_std_module_name(name)?;
 
-        let current_dir = env::current_dir()?;
-        match CargoPath::nearest_manifest(&current_dir) {
+        let base = match root {
+            Some(root) => root.to_path_buf(),
+            None => env::current_dir()?,
+        };
+        match CargoPath::nearest_manifest(&base) {
             Some(root) => Self::from_manifest_root(&root, name, version, target_path, offline),
             None => Self::from_dummy_crate(name, version, target_path, offline),
         }
@@ -942,10 +1290,46 @@ impl ResolvedTarget {
     }
 }
 
+/// The nearest manifest above `root`, or above the current directory when `root` is `None`.
+fn project_manifest(root: Option<&Path>) -> Result<Option<CargoPath>> {
+    let base = match root {
+        Some(root) => root.to_path_buf(),
+        None => env::current_dir()?,
+    };
+    Ok(CargoPath::nearest_manifest(&base))
+}
+
+/// List the crates of the project containing `root`, or the current directory when `root` is
+/// `None`: its workspace members and their direct dependencies. Returns an empty list outside of
+/// a Cargo project.
+pub fn project_crates(root: Option<&Path>, offline: bool) -> Result<Vec<ProjectCrate>> {
+    match project_manifest(root)? {
+        Some(cargo_path) => cargo_path.project_crates(offline),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// List the crates of the project containing `root`, or the current directory when `root` is
+/// `None`, as [`CargoPath::project_packages`] describes them. Returns an empty list outside of
+/// a Cargo project.
+pub fn project_packages(root: Option<&Path>, offline: bool) -> Result<Vec<PackageInfo>> {
+    match project_manifest(root)? {
+        Some(cargo_path) => cargo_path.project_packages(offline),
+        None => Ok(Vec::new()),
+    }
+}
+
 /// Resovles a target specification and returns a ResolvedTarget, pointing to the package
 /// directory. If necessary, construct temporary dummy crate to download packages from cargo.io.
 /// Parse a textual target specification into a `ResolvedTarget`.
-pub fn resolve_target(target_str: &str, offline: bool) -> Result<ResolvedTarget> {
+///
+/// Relative paths and workspace names resolve against `root`, or the current directory when
+/// `root` is `None`.
+pub fn resolve_target(
+    target_str: &str,
+    root: Option<&Path>,
+    offline: bool,
+) -> Result<ResolvedTarget> {
     let (resolved_target_str, original_crate) =
         if let Some(mapped) = resolve_std_reexport(target_str) {
             let original = target_str.split("::").next().unwrap_or("std");
@@ -957,7 +1341,7 @@ pub fn resolve_target(target_str: &str, offline: bool) -> Result<ResolvedTarget>
     let target = Target::parse(&resolved_target_str)?;
 
     match &target.entrypoint {
-        Entrypoint::Path(_) => ResolvedTarget::from_target(target, offline),
+        Entrypoint::Path(_) => ResolvedTarget::from_target(target, root, offline),
         Entrypoint::Name { name, version } => {
             if version.is_some() {
                 return ResolvedTarget::from_dummy_crate(
@@ -975,7 +1359,7 @@ pub fn resolve_target(target_str: &str, offline: bool) -> Result<ResolvedTarget>
             }
             ResolvedTarget::reject_std_module_name(name)?;
 
-            ResolvedTarget::from_target(target.clone(), offline)?
+            ResolvedTarget::from_target(target.clone(), root, offline)?
                 .retarget_dependency(&target.path, offline)
         }
     }
@@ -989,39 +1373,134 @@ fn to_import_name(package_name: &str) -> String {
 /// Maximum number of characters from rustdoc stderr included in failure reports.
 const MAX_STDERR_CHARS: usize = 8_192;
 
-/// Translate a `rustdoc_json` build failure into a user-facing [`RuskelError`].
-fn map_rustdoc_build_error(
-    err: &rustdoc_json::BuildError,
-    captured_stderr: &[u8],
-    silent: bool,
-) -> RuskelError {
-    match err {
-        rustdoc_json::BuildError::BuildRustdocJsonError => {
-            format_rustdoc_failure(captured_stderr, silent)
-        }
-        other => {
-            let err_msg = other.to_string();
+/// How often a running rustdoc build checks whether it was cancelled.
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(100);
 
-            if err_msg.contains("no library targets found in package") {
-                return RuskelError::Generate(
-                    "error: no library targets found in package".to_string(),
-                );
-            }
+/// Build the `cargo +nightly rustdoc` invocation that writes JSON docs for `package_target`.
+fn rustdoc_command(
+    manifest_path: &Path,
+    package_target: &PackageTarget,
+    include_private: bool,
+    options: &CrateReadOptions,
+) -> Command {
+    let mut command = Command::new("rustup");
+    command.args(["run", "nightly", "cargo", "rustdoc", "--color", "never"]);
+    match package_target {
+        PackageTarget::Bin(name) => command.args(["--bin", name]),
+        _ => command.arg("--lib"),
+    };
+    command.arg("--manifest-path").arg(manifest_path);
+    if options.no_default_features {
+        command.arg("--no-default-features");
+    }
+    if options.all_features {
+        command.arg("--all-features");
+    }
+    for feature in &options.features {
+        command.args(["--features", feature]);
+    }
+    command.args(["--", "-Z", "unstable-options", "--output-format", "json"]);
+    if include_private {
+        command.arg("--document-private-items");
+    }
+    command.args(["--cap-lints", "warn"]);
+    command
+        .stdin(Stdio::null())
+        .stdout(if options.silent {
+            Stdio::null()
+        } else {
+            Stdio::inherit()
+        })
+        .stderr(Stdio::piped());
+    // Cancellable builds run in their own process group so cancelling also stops rustdoc and
+    // build scripts spawned by cargo. Others stay in ours to receive terminal interrupts.
+    #[cfg(unix)]
+    if options.progress.is_cancellable() {
+        command.process_group(0);
+    }
+    command
+}
 
-            if err_msg.contains("toolchain") && err_msg.contains("is not installed") {
-                return RuskelError::Generate(
-                    "ruskel requires the nightly toolchain to be installed - run 'rustup toolchain install nightly'"
-                        .to_string(),
-                );
+/// Run a rustdoc build, reporting cargo's status lines as progress.
+///
+/// Cargo's output is mirrored to stdout and stderr unless `options.silent` is set, and its
+/// diagnostics are kept for the error when the build fails. The build is killed as soon as the
+/// progress hook reports cancellation.
+fn run_rustdoc(command: &mut Command, options: &CrateReadOptions) -> Result<()> {
+    options.progress.check()?;
+    let mut child = command.spawn().map_err(|err| {
+        RuskelError::Generate(format!(
+            "ruskel requires rustup with the nightly toolchain - failed to run rustup: {err}"
+        ))
+    })?;
+    let stderr = child.stderr.take();
+    let progress = options.progress.clone();
+    let silent = options.silent;
+    let reader = thread::spawn(move || {
+        let mut diagnostics = String::new();
+        for line in stderr
+            .into_iter()
+            .flat_map(|pipe| BufReader::new(pipe).lines())
+        {
+            let Ok(line) = line else {
+                break;
+            };
+            if !silent {
+                eprintln!("{line}");
             }
-
-            if err_msg.contains("Failed to build rustdoc JSON") {
-                return format_rustdoc_failure(captured_stderr, silent);
+            match LoadStage::from_cargo_status(&line) {
+                Some(stage) if line.starts_with(' ') => progress.stage(stage),
+                _ => {
+                    diagnostics.push_str(&line);
+                    diagnostics.push('\n');
+                }
             }
+        }
+        diagnostics
+    });
 
-            RuskelError::Generate(format!("Failed to build rustdoc JSON: {err_msg}"))
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if options.progress.check().is_err() {
+            kill_build(&mut child);
+            child.wait()?;
+            return Err(RuskelError::Cancelled);
         }
+        thread::sleep(CANCEL_POLL_INTERVAL);
+    };
+    let diagnostics = reader.join().unwrap_or_default();
+    if status.success() {
+        Ok(())
+    } else {
+        Err(map_rustdoc_failure(&diagnostics, options.silent))
+    }
+}
+
+/// Kill a cancelled build along with the processes cargo spawned for it.
+///
+/// Kill errors mean the build already exited; the caller reaps it either way.
+fn kill_build(child: &mut Child) {
+    #[cfg(unix)]
+    if let Ok(group) = libc::pid_t::try_from(child.id()) {
+        // SAFETY: `kill` has no memory-safety preconditions. `rustdoc_command` starts
+        // cancellable builds as process group leaders, so the group is the build's own.
+        unsafe { libc::kill(-group, libc::SIGKILL) };
+        return;
+    }
+    let _killed = child.kill();
+}
+
+/// Translate a failed rustdoc build into a user-facing [`RuskelError`].
+fn map_rustdoc_failure(captured_stderr: &str, silent: bool) -> RuskelError {
+    if captured_stderr.contains("toolchain") && captured_stderr.contains("is not installed") {
+        return RuskelError::Generate(
+            "ruskel requires the nightly toolchain to be installed - run 'rustup toolchain install nightly'"
+                .to_string(),
+        );
     }
+    format_rustdoc_failure(captured_stderr.as_bytes(), silent)
 }
 
 /// Format a detailed error for rustdoc build failures, optionally embedding diagnostics.
@@ -1422,6 +1901,180 @@ version = "0.1.0"
         Ok(())
     }
 
+    #[test]
+    fn test_project_crates_lists_members_and_direct_dependencies() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let workspace_root = temp_dir.path().join("workspace");
+        let write_package = |dir: &Path, manifest: &str| -> Result<()> {
+            fs::create_dir_all(dir.join("src"))?;
+            fs::write(dir.join("Cargo.toml"), manifest)?;
+            fs::write(dir.join("src/lib.rs"), "")?;
+            Ok(())
+        };
+        fs::create_dir_all(&workspace_root)?;
+        fs::write(
+            workspace_root.join("Cargo.toml"),
+            r#"
+            [workspace]
+            members = ["app", "util"]
+            resolver = "2"
+            "#,
+        )?;
+        write_package(
+            &workspace_root.join("app"),
+            r#"
+            [package]
+            name = "app"
+            version = "0.1.0"
+            edition = "2021"
+
+            [dependencies]
+            util = { path = "../util" }
+            external = { path = "../../external" }
+            "#,
+        )?;
+        write_package(
+            &workspace_root.join("util"),
+            r#"
+            [package]
+            name = "util"
+            version = "0.2.0"
+            edition = "2021"
+            "#,
+        )?;
+        write_package(
+            &temp_dir.path().join("external"),
+            r#"
+            [package]
+            name = "external"
+            version = "1.0.0"
+            edition = "2021"
+            "#,
+        )?;
+
+        let crates = CargoPath::from_path(workspace_root).project_crates(true)?;
+        let summary: Vec<(&str, &str, bool)> = crates
+            .iter()
+            .map(|krate| (krate.name.as_str(), krate.version.as_str(), krate.member))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("app", "0.1.0", true),
+                ("util", "0.2.0", true),
+                ("external", "1.0.0", false),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_project_packages_describe_features_sources_and_targets() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let workspace_root = temp_dir.path().join("workspace");
+        let write_package = |dir: &Path, manifest: &str| -> Result<()> {
+            fs::create_dir_all(dir.join("src"))?;
+            fs::write(dir.join("Cargo.toml"), manifest)?;
+            fs::write(dir.join("src/lib.rs"), "")?;
+            Ok(())
+        };
+        fs::create_dir_all(&workspace_root)?;
+        fs::write(
+            workspace_root.join("Cargo.toml"),
+            r#"
+            [workspace]
+            members = ["app", "util"]
+            resolver = "2"
+            "#,
+        )?;
+        write_package(
+            &workspace_root.join("app"),
+            r#"
+            [package]
+            name = "app"
+            version = "0.1.0"
+            edition = "2021"
+
+            [dependencies]
+            util = { path = "../util" }
+            shared = { path = "../../shared", features = ["fast"] }
+            old-util = { package = "util", path = "../../util" }
+            "#,
+        )?;
+        write_package(
+            &workspace_root.join("util"),
+            r#"
+            [package]
+            name = "util"
+            version = "0.2.0"
+            edition = "2021"
+            "#,
+        )?;
+        write_package(
+            &temp_dir.path().join("shared"),
+            r#"
+            [package]
+            name = "shared"
+            version = "1.0.0"
+            edition = "2021"
+
+            [features]
+            default = ["std"]
+            std = []
+            fast = []
+            "#,
+        )?;
+        write_package(
+            &temp_dir.path().join("util"),
+            r#"
+            [package]
+            name = "util"
+            version = "0.1.0"
+            edition = "2021"
+            "#,
+        )?;
+
+        let config = create_quiet_cargo_config(true)?;
+        let workspace = Workspace::new(&workspace_root.join("Cargo.toml"), &config)
+            .map_err(|err| convert_cargo_error(&err))?;
+        let json_path = rustdoc_json_path(&workspace.target_dir().into_path_unlocked(), "app");
+        fs::create_dir_all(json_path.parent().expect("doc directory"))?;
+        fs::write(&json_path, "{}")?;
+
+        let packages = CargoPath::from_path(workspace_root).project_packages(true)?;
+        let summary: Vec<(&str, &str, bool, bool, &str)> = packages
+            .iter()
+            .map(|package| {
+                (
+                    package.name.as_str(),
+                    package.version.as_str(),
+                    package.member,
+                    package.docs_cached,
+                    package.target.as_str(),
+                )
+            })
+            .collect();
+        let old_util = temp_dir.path().join("util").display().to_string();
+        assert_eq!(
+            summary,
+            vec![
+                ("app", "0.1.0", true, true, "app"),
+                ("util", "0.2.0", true, false, "util"),
+                ("shared", "1.0.0", false, false, "shared"),
+                ("util", "0.1.0", false, false, old_util.as_str()),
+            ]
+        );
+        assert!(
+            packages
+                .iter()
+                .all(|package| package.source == PackageSource::Path)
+        );
+        assert_eq!(packages[2].features, vec!["default", "fast", "std"]);
+
+        Ok(())
+    }
+
     #[test]
     fn test_resolve_name_prefers_workspace_members() -> Result<()> {
         let temp_dir = tempdir()?;
@@ -1447,7 +2100,7 @@ version = "0.1.0"
         fs::write(localcrate_dir.join("src/lib.rs"), "// localcrate lib")?;
 
         let _guard = DirGuard::change_to(&workspace_root)?;
-        let resolved = resolve_target("localcrate", true)?;
+        let resolved = resolve_target("localcrate", None, true)?;
 
         let ResolvedTarget {
             package_path,
@@ -1462,6 +2115,90 @@ version = "0.1.0"
         Ok(())
     }
 
+    #[test]
+    fn test_resolve_target_against_root() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let workspace_root = temp_dir.path().join("workspace");
+        let member_dir = workspace_root.join("member");
+
+        fs::create_dir_all(member_dir.join("src"))?;
+        fs::write(
+            workspace_root.join("Cargo.toml"),
+            r#"
+            [workspace]
+            members = ["member"]
+            "#,
+        )?;
+        fs::write(
+            member_dir.join("Cargo.toml"),
+            r#"
+            [package]
+            name = "member"
+            version = "0.1.0"
+            "#,
+        )?;
+        fs::write(member_dir.join("src/lib.rs"), "pub mod inner {}")?;
+
+        let expected = fs::canonicalize(&member_dir)?;
+        let by_name = resolve_target("member::inner", Some(&workspace_root), true)?;
+        assert_eq!(by_name.package_path.canonical_path()?, expected);
+        assert_eq!(by_name.filter, "inner");
+
+        let by_path = resolve_target("./member", Some(&workspace_root), true)?;
+        assert_eq!(by_path.package_path.canonical_path()?, expected);
+
+        let crates = project_crates(Some(&member_dir), true)?;
+        assert_eq!(crates[0].name, "member");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_select_package_target_expands_enabled_features() -> Result<()> {
+        let temp_dir = tempdir()?;
+        fs::create_dir_all(temp_dir.path().join("src"))?;
+        fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            r#"
+            [package]
+            name = "featured"
+            version = "0.1.0"
+
+            [features]
+            default = ["std"]
+            std = ["alloc"]
+            alloc = []
+            extra = []
+            "#,
+        )?;
+        fs::write(temp_dir.path().join("src/lib.rs"), "")?;
+
+        let manifest = temp_dir.path().join("Cargo.toml");
+        let mut options = CrateReadOptions {
+            no_default_features: false,
+            all_features: false,
+            features: Vec::new(),
+            private_items: false,
+            silent: true,
+            offline: true,
+            bin_override: None,
+            progress: Progress::default(),
+        };
+        assert_eq!(
+            select_package_target(&manifest, &options)?.features,
+            ["alloc", "default", "std"]
+        );
+
+        options.no_default_features = true;
+        options.features = vec!["extra".to_string()];
+        assert_eq!(
+            select_package_target(&manifest, &options)?.features,
+            ["extra"]
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_offline_dummy_crate_error_message() -> Result<()> {
         let temp_dir = tempdir()?;
@@ -1629,7 +2366,7 @@ version = "0.1.0"
         expected_display: &str,
         expected_filter: &str,
     ) {
-        let result = resolve_target(target, true).unwrap();
+        let result = resolve_target(target, None, true).unwrap();
         match result.package_path.std_names() {
             Some((actual, display)) => {
                 assert_eq!(actual, expected_actual);
@@ -1642,7 +2379,7 @@ version = "0.1.0"
 
     /// Assert that resolving a bare module fails with the expected error message.
     fn assert_std_module_error(module: &str, suggestion: &str) {
-        match resolve_target(module, true) {
+        match resolve_target(module, None, true) {
             Err(err) => {
                 let message = err.to_string();
                 assert!(message.contains("appears to be a standard library module"));
@@ -1714,7 +2451,7 @@ version = "0.1.0"
         ];
 
         for (i, (target, expected_result, expected_filter)) in test_cases.into_iter().enumerate() {
-            let result = ResolvedTarget::from_target(target, true);
+            let result = ResolvedTarget::from_target(target, None, true);
 
             match (result, expected_result) {
                 (Ok(resolved), ExpectedResult::Path(expected)) => {
@@ -0,0 +1,180 @@
+//! Transitive type closure of an item: the item plus every type of the same crate it mentions.
+//!
+//! Starting from the targeted items, the closure follows the resolved paths named by signatures,
+//! fields, variants, bounds and associated types. Types reached this way are rendered with their
+//! members, but only their fields, variants, bounds and associated types are followed further, so
+//! the closure stays focused on the shape of the data the item works with.
+
+use std::collections::{HashSet, VecDeque};
+
+use rustdoc_types::{Crate, Id, Item, ItemEnum, Path, StructKind, VariantKind};
+
+use crate::{
+    crateutils::{TypeVisitor, walk_bounds, walk_generics, walk_signature, walk_type},
+    render::RenderSelection,
+    search::{SearchIndex, SearchItemKind},
+};
+
+/// Collects the ids named by resolved paths.
+struct PathCollector<'a> {
+    /// Rustdoc data used to look up member items.
+    crate_data: &'a Crate,
+    /// Ids in the order they were first mentioned.
+    ids: Vec<Id>,
+}
+
+impl<'a> TypeVisitor<'a> for PathCollector<'a> {
+    fn visit_path(&mut self, path: &'a Path) {
+        if !self.ids.contains(&path.id) {
+            self.ids.push(path.id);
+        }
+    }
+}
+
+impl<'a> PathCollector<'a> {
+    /// Walk the parts of `item` that the closure follows.
+    fn visit_item(&mut self, item: &'a Item) {
+        match &item.inner {
+            ItemEnum::Function(function) => {
+                walk_generics(&function.generics, self);
+                walk_signature(&function.sig, self);
+            }
+            ItemEnum::Struct(struct_) => {
+                walk_generics(&struct_.generics, self);
+                match &struct_.kind {
+                    StructKind::Unit => {}
+                    StructKind::Tuple(fields) => self.visit_members(fields.iter().flatten()),
+                    StructKind::Plain { fields, .. } => self.visit_members(fields),
+                }
+            }
+            ItemEnum::Union(union_) => {
+                walk_generics(&union_.generics, self);
+                self.visit_members(&union_.fields);
+            }
+            ItemEnum::Enum(enum_) => {
+                walk_generics(&enum_.generics, self);
+                self.visit_members(&enum_.variants);
+            }
+            ItemEnum::Variant(variant) => match &variant.kind {
+                VariantKind::Plain => {}
+                VariantKind::Tuple(fields) => self.visit_members(fields.iter().flatten()),
+                VariantKind::Struct { fields, .. } => self.visit_members(fields),
+            },
+            ItemEnum::StructField(ty) => walk_type(ty, self),
+            ItemEnum::Trait(trait_) => {
+                walk_generics(&trait_.generics, self);
+                walk_bounds(&trait_.bounds, self);
+                self.visit_members(&trait_.items);
+            }
+            ItemEnum::TraitAlias(alias) => {
+                walk_generics(&alias.generics, self);
+                walk_bounds(&alias.params, self);
+            }
+            ItemEnum::TypeAlias(alias) => {
+                walk_generics(&alias.generics, self);
+                walk_type(&alias.type_, self);
+            }
+            ItemEnum::AssocType {
+                generics,
+                bounds,
+                type_,
+            } => {
+                walk_generics(generics, self);
+                walk_bounds(bounds, self);
+                if let Some(ty) = type_ {
+                    walk_type(ty, self);
+                }
+            }
+            ItemEnum::AssocConst { type_, .. }
+            | ItemEnum::Constant { type_, .. }
+            | ItemEnum::Static(rustdoc_types::Static { type_, .. }) => walk_type(type_, self),
+            _ => {}
+        }
+    }
+
+    /// Walk the member items with the given ids.
+    fn visit_members(&mut self, ids: impl IntoIterator<Item = &'a Id>) {
+        for id in ids {
+            if let Some(item) = self.crate_data.index.get(id) {
+                self.visit_item(item);
+            }
+        }
+    }
+}
+
+/// Collect the closure of `roots`: the roots followed by every indexed item they reach, in
+/// breadth-first order.
+///
+/// `max_depth` limits how many hops are followed from the roots; `None` follows every reachable
+/// type. Items missing from `index`, such as foreign or hidden private types, end the walk.
+pub fn collect_closure(
+    crate_data: &Crate,
+    index: &SearchIndex,
+    roots: &[Id],
+    max_depth: Option<usize>,
+) -> Vec<Id> {
+    let mut closure = Vec::new();
+    let mut seen: HashSet<Id> = roots.iter().copied().collect();
+    let mut queue: VecDeque<(Id, usize)> = roots.iter().map(|id| (*id, 0)).collect();
+
+    while let Some((id, depth)) = queue.pop_front() {
+        closure.push(id);
+        if max_depth.is_some_and(|max| depth >= max) {
+            continue;
+        }
+        let Some(item) = crate_data.index.get(&id) else {
+            continue;
+        };
+        let mut collector = PathCollector {
+            crate_data,
+            ids: Vec::new(),
+        };
+        collector.visit_item(item);
+        for mentioned in collector.ids {
+            if seen.insert(mentioned) && index.entry(&mentioned).is_some() {
+                queue.push_back((mentioned, depth + 1));
+            }
+        }
+    }
+
+    closure
+}
+
+/// Select the closure for rendering.
+///
+/// Types in the closure are expanded so their fields, variants and impls render; other items,
+/// such as the root function, render alone within their ancestors.
+pub fn build_closure_selection(index: &SearchIndex, closure: &[Id]) -> RenderSelection {
+    let matches: HashSet<Id> = closure.iter().copied().collect();
+    let mut context = HashSet::new();
+    let mut expanded = HashSet::new();
+    for id in closure {
+        let Some(entry) = index.entry(id) else {
+            continue;
+        };
+        context.extend(entry.ancestors.iter().copied());
+        if matches!(
+            entry.kind,
+            SearchItemKind::Struct
+                | SearchItemKind::Enum
+                | SearchItemKind::Union
+                | SearchItemKind::Trait
+        ) {
+            expanded.insert(*id);
+        }
+    }
+
+    let all_kinds = HashSet::new();
+    for entry in index.entries_of_kind(&all_kinds) {
+        if let Some(pos) = entry
+            .ancestors
+            .iter()
+            .position(|ancestor| expanded.contains(ancestor))
+        {
+            context.insert(entry.item_id);
+            context.extend(entry.ancestors.iter().skip(pos + 1).copied());
+        }
+    }
+
+    RenderSelection::new(matches, context, expanded)
+}
@@ -663,6 +663,156 @@ pub fn render_associated_type(item: &Item) -> String {
     format!("type {}{bounds_str}{default_str};\n", render_name(item))
 }
 
+/// Visitor over the types and paths reachable from a type, used by the `walk_*` functions.
+pub trait TypeVisitor<'a> {
+    /// Called for every type, outermost first.
+    fn visit_type(&mut self, _ty: &'a Type) {}
+
+    /// Called for every type after the types nested within it have been walked.
+    fn leave_type(&mut self, _ty: &'a Type) {}
+
+    /// Called for every resolved path, including the traits named by bounds.
+    fn visit_path(&mut self, _path: &'a Path) {}
+
+    /// Called for every resolved path after its generic arguments have been walked.
+    fn leave_path(&mut self, _path: &'a Path) {}
+}
+
+/// Walk `ty` and every type nested within it: generic arguments, associated type bindings,
+/// trait bounds, function pointer signatures and qualified paths.
+pub fn walk_type<'a>(ty: &'a Type, visitor: &mut impl TypeVisitor<'a>) {
+    visitor.visit_type(ty);
+    match ty {
+        Type::ResolvedPath(path) => walk_path(path, visitor),
+        Type::DynTrait(dyn_trait) => {
+            for poly_trait in &dyn_trait.traits {
+                walk_path(&poly_trait.trait_, visitor);
+            }
+        }
+        Type::FunctionPointer(pointer) => walk_signature(&pointer.sig, visitor),
+        Type::Tuple(types) => {
+            for ty in types {
+                walk_type(ty, visitor);
+            }
+        }
+        Type::Slice(inner) => walk_type(inner, visitor),
+        Type::Array { type_, .. }
+        | Type::Pat { type_, .. }
+        | Type::RawPointer { type_, .. }
+        | Type::BorrowedRef { type_, .. } => walk_type(type_, visitor),
+        Type::ImplTrait(bounds) => walk_bounds(bounds, visitor),
+        Type::QualifiedPath {
+            args,
+            self_type,
+            trait_,
+            ..
+        } => {
+            walk_type(self_type, visitor);
+            if let Some(trait_) = trait_ {
+                walk_path(trait_, visitor);
+            }
+            if let Some(args) = args {
+                walk_generic_args(args, visitor);
+            }
+        }
+        Type::Generic(_) | Type::Primitive(_) | Type::Infer => {}
+    }
+    visitor.leave_type(ty);
+}
+
+/// Walk a path and its generic arguments.
+pub fn walk_path<'a>(path: &'a Path, visitor: &mut impl TypeVisitor<'a>) {
+    visitor.visit_path(path);
+    if let Some(args) = &path.args {
+        walk_generic_args(args, visitor);
+    }
+    visitor.leave_path(path);
+}
+
+/// Walk the inputs and output of a function signature.
+pub fn walk_signature<'a>(sig: &'a FunctionSignature, visitor: &mut impl TypeVisitor<'a>) {
+    for (_, ty) in &sig.inputs {
+        walk_type(ty, visitor);
+    }
+    if let Some(output) = &sig.output {
+        walk_type(output, visitor);
+    }
+}
+
+/// Walk the traits and arguments named by trait bounds.
+pub fn walk_bounds<'a>(bounds: &'a [GenericBound], visitor: &mut impl TypeVisitor<'a>) {
+    for bound in bounds {
+        if let GenericBound::TraitBound { trait_, .. } = bound {
+            walk_path(trait_, visitor);
+        }
+    }
+}
+
+/// Walk the bounds and defaults of generic parameters, and the predicates of the where clause.
+pub fn walk_generics<'a>(generics: &'a Generics, visitor: &mut impl TypeVisitor<'a>) {
+    for param in &generics.params {
+        match &param.kind {
+            GenericParamDefKind::Type {
+                bounds, default, ..
+            } => {
+                walk_bounds(bounds, visitor);
+                if let Some(default) = default {
+                    walk_type(default, visitor);
+                }
+            }
+            GenericParamDefKind::Const { type_, .. } => walk_type(type_, visitor),
+            GenericParamDefKind::Lifetime { .. } => {}
+        }
+    }
+    for predicate in &generics.where_predicates {
+        match predicate {
+            WherePredicate::BoundPredicate { type_, bounds, .. } => {
+                walk_type(type_, visitor);
+                walk_bounds(bounds, visitor);
+            }
+            WherePredicate::EqPredicate { lhs, rhs } => {
+                walk_type(lhs, visitor);
+                if let Term::Type(ty) = rhs {
+                    walk_type(ty, visitor);
+                }
+            }
+            WherePredicate::LifetimePredicate { .. } => {}
+        }
+    }
+}
+
+/// Walk the types and bounds named by generic arguments.
+fn walk_generic_args<'a>(args: &'a GenericArgs, visitor: &mut impl TypeVisitor<'a>) {
+    match args {
+        GenericArgs::AngleBracketed { args, constraints } => {
+            for arg in args {
+                if let GenericArg::Type(ty) = arg {
+                    walk_type(ty, visitor);
+                }
+            }
+            for constraint in constraints {
+                if let Some(args) = &constraint.args {
+                    walk_generic_args(args, visitor);
+                }
+                match &constraint.binding {
+                    AssocItemConstraintKind::Equality(Term::Type(ty)) => walk_type(ty, visitor),
+                    AssocItemConstraintKind::Equality(Term::Constant(_)) => {}
+                    AssocItemConstraintKind::Constraint(bounds) => walk_bounds(bounds, visitor),
+                }
+            }
+        }
+        GenericArgs::Parenthesized { inputs, output } => {
+            for ty in inputs {
+                walk_type(ty, visitor);
+            }
+            if let Some(output) = output {
+                walk_type(output, visitor);
+            }
+        }
+        GenericArgs::ReturnTypeNotation => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use rustdoc_types::{GenericBound, Id, Path, TraitBoundModifier};
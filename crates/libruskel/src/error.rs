@@ -32,10 +32,18 @@ pub enum RuskelError {
     #[error("Formatting error: {0}")]
     Format(String),
 
+    /// Indicates a failure writing rendered output to its destination.
+    #[error("Failed to write output: {0}")]
+    Write(#[source] io::Error),
+
     /// Indicates an error occurred during syntax highlighting.
     #[error("Highlighting error: {0}")]
     Highlight(String),
 
+    /// Indicates a search query could not be compiled.
+    #[error("Invalid search query: {0}")]
+    InvalidSearch(String),
+
     /// The specified filter did not match any items.
     #[error("Filter '{0}' did not match any items")]
     FilterNotMatched(String),
@@ -59,6 +67,10 @@ pub enum RuskelError {
     /// Indicates a dependency was not found in the registry.
     #[error("No matching package")]
     DependencyNotFound,
+
+    /// Indicates the caller cancelled the operation through its progress hook.
+    #[error("Operation cancelled")]
+    Cancelled,
 }
 
 impl From<syntect::Error> for RuskelError {
@@ -0,0 +1,279 @@
+//! Formatting backends applied to rendered skeleton source.
+
+use std::{fmt, result, str::FromStr};
+
+use rust_format::{Config, Formatter, RustFmt};
+
+use crate::error::{Result, RuskelError};
+
+/// Backend used to format rendered skeleton source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FormatBackend {
+    /// Shell out to the `rustfmt` binary.
+    #[default]
+    RustFmt,
+    /// Format in-process by parsing with `syn` and printing with `prettyplease`.
+    PrettyPlease,
+    /// Emit the skeleton exactly as rendered, without formatting.
+    Unformatted,
+}
+
+impl FormatBackend {
+    /// Short identifier used for CLI flags and diagnostics.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::RustFmt => "rustfmt",
+            Self::PrettyPlease => "prettyplease",
+            Self::Unformatted => "none",
+        }
+    }
+}
+
+impl fmt::Display for FormatBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl FromStr for FormatBackend {
+    type Err = String;
+
+    fn from_str(token: &str) -> result::Result<Self, Self::Err> {
+        match token.to_ascii_lowercase().as_str() {
+            "rustfmt" => Ok(Self::RustFmt),
+            "prettyplease" | "syn" | "builtin" => Ok(Self::PrettyPlease),
+            "none" | "off" | "raw" => Ok(Self::Unformatted),
+            other => Err(format!(
+                "invalid formatter '{other}'. Expected one of: rustfmt, prettyplease, none."
+            )),
+        }
+    }
+}
+
+/// Line placed between items handed to [`SkeletonFormatter::format_items`]. Every backend keeps
+/// it intact, so it marks where each item starts in the formatted output.
+const ITEM_MARKER: &str = "const __RUSKEL_ITEM_BREAK: () = ();";
+
+/// Items formatted together by [`SkeletonFormatter::format_items`], split apart again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormattedItems {
+    /// Formatted headers of the enclosing modules.
+    pub opening: String,
+    /// Each item, formatted and indented for its module, starting with the blank line that
+    /// separates it from the previous item where the backend keeps one.
+    pub items: Vec<String>,
+    /// Closing braces of the enclosing modules.
+    pub closing: String,
+}
+
+/// Formats rendered source with a configurable backend and failure policy.
+pub struct SkeletonFormatter {
+    /// Backend applied to rendered output.
+    backend: FormatBackend,
+    /// Whether formatting failures fall back to unformatted output.
+    best_effort: bool,
+    /// Configured rustfmt driver used by [`FormatBackend::RustFmt`].
+    rustfmt: RustFmt,
+}
+
+impl Default for SkeletonFormatter {
+    fn default() -> Self {
+        Self::new(FormatBackend::default())
+    }
+}
+
+impl SkeletonFormatter {
+    /// Create a formatter for `backend` that reports formatting failures as errors.
+    pub fn new(backend: FormatBackend) -> Self {
+        let config = Config::new_str().option("brace_style", "PreferSameLine");
+        Self {
+            backend,
+            best_effort: false,
+            rustfmt: RustFmt::from_config(config),
+        }
+    }
+
+    /// Change the backend used for subsequent formatting.
+    pub fn set_backend(&mut self, backend: FormatBackend) {
+        self.backend = backend;
+    }
+
+    /// Set whether formatting failures return the unformatted output with a warning comment
+    /// instead of an error.
+    pub fn set_best_effort(&mut self, best_effort: bool) {
+        self.best_effort = best_effort;
+    }
+
+    /// Format `source`, applying the best-effort fallback when enabled.
+    pub fn format(&self, source: &str) -> Result<String> {
+        match self.format_strict(source) {
+            Ok(formatted) => Ok(formatted),
+            Err(err) if self.best_effort => {
+                Ok(unformatted_with_warning(self.backend, &err, source))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Format `items` as consecutive contents of the modules that `opening` opens, `depth`
+    /// levels deep, with a single backend call.
+    ///
+    /// Items are formatted in place, so they are indented and wrapped exactly as in the whole
+    /// module. The whitespace leading each item separates it from the previous one. Without a
+    /// formatting backend, the opening and items are returned unchanged.
+    pub fn format_items(
+        &self,
+        opening: &str,
+        depth: usize,
+        items: &[String],
+    ) -> Result<FormattedItems> {
+        if self.backend == FormatBackend::Unformatted {
+            return Ok(FormattedItems {
+                opening: opening.to_string(),
+                items: items.to_vec(),
+                closing: "}\n".repeat(depth),
+            });
+        }
+
+        let mut source = opening.to_string();
+        for item in items {
+            let item = item.trim_end();
+            source.push_str(ITEM_MARKER);
+            source.push('\n');
+            source.push_str(item.strip_prefix('\n').unwrap_or(item));
+            source.push('\n');
+        }
+        source.push_str(ITEM_MARKER);
+        source.push('\n');
+        source.push_str(&"}\n".repeat(depth));
+
+        let formatted = match self.format_strict(&source) {
+            Ok(formatted) => formatted,
+            Err(err) if self.best_effort => {
+                return Ok(FormattedItems {
+                    opening: unformatted_with_warning(self.backend, &err, opening),
+                    items: items.to_vec(),
+                    closing: "}\n".repeat(depth),
+                });
+            }
+            Err(err) => return Err(err),
+        };
+
+        let mut sections = vec![String::new()];
+        for line in formatted.lines() {
+            if line.trim() == ITEM_MARKER {
+                sections.push(String::new());
+            } else if let Some(section) = sections.last_mut() {
+                section.push_str(line);
+                section.push('\n');
+            }
+        }
+        if sections.len() != items.len() + 2 {
+            return Err(RuskelError::Format(format!(
+                "{} moved item boundaries while formatting",
+                self.backend
+            )));
+        }
+        let closing = sections.pop().unwrap_or_default();
+        let opening = sections.remove(0);
+        Ok(FormattedItems {
+            opening,
+            items: sections,
+            closing,
+        })
+    }
+
+    /// Format `source` with the configured backend, surfacing any failure.
+    fn format_strict(&self, source: &str) -> Result<String> {
+        match self.backend {
+            FormatBackend::RustFmt => Ok(self.rustfmt.format_str(source)?),
+            FormatBackend::PrettyPlease => {
+                let file = syn::parse_file(source)
+                    .map_err(|err| RuskelError::Format(format!("prettyplease: {err}")))?;
+                Ok(prettyplease::unparse(&file))
+            }
+            FormatBackend::Unformatted => Ok(source.to_string()),
+        }
+    }
+}
+
+/// Prefix unformatted output with a comment describing why formatting was skipped.
+fn unformatted_with_warning(backend: FormatBackend, err: &RuskelError, source: &str) -> String {
+    let reason = err.to_string();
+    let reason = reason.lines().next().unwrap_or_default();
+    format!("// Warning: {backend} formatting failed ({reason}); output is unformatted.\n{source}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prettyplease_formats_without_rustfmt() -> Result<()> {
+        let formatter = SkeletonFormatter::new(FormatBackend::PrettyPlease);
+        let output =
+            formatter.format("pub mod demo { /// Docs\npub fn run ( x : u32 ) -> u32 {} }")?;
+
+        assert!(output.contains("pub mod demo {"));
+        assert!(output.contains("    /// Docs\n"));
+        assert!(output.contains("    pub fn run(x: u32) -> u32 {}"));
+        Ok(())
+    }
+
+    #[test]
+    fn strict_mode_surfaces_format_errors() {
+        let formatter = SkeletonFormatter::new(FormatBackend::PrettyPlease);
+        let err = formatter
+            .format("pub fn broken(")
+            .expect_err("invalid source should fail to format");
+
+        assert!(matches!(err, RuskelError::Format(_)));
+    }
+
+    #[test]
+    fn best_effort_returns_unformatted_source_with_warning() -> Result<()> {
+        let mut formatter = SkeletonFormatter::new(FormatBackend::PrettyPlease);
+        formatter.set_best_effort(true);
+        let output = formatter.format("pub fn broken(")?;
+
+        assert!(output.starts_with("// Warning: prettyplease formatting failed"));
+        assert!(output.ends_with("pub fn broken("));
+        Ok(())
+    }
+
+    #[test]
+    fn unformatted_backend_passes_source_through() -> Result<()> {
+        let formatter = SkeletonFormatter::new(FormatBackend::Unformatted);
+        assert_eq!(formatter.format("pub  fn  a ( ) {}")?, "pub  fn  a ( ) {}");
+        Ok(())
+    }
+
+    #[test]
+    fn format_items_splits_items_formatted_in_place() -> Result<()> {
+        let formatter = SkeletonFormatter::new(FormatBackend::PrettyPlease);
+        let items = vec![
+            "pub fn run ( x : u32 ) -> u32 {}".to_string(),
+            "\n\npub struct Unit ;\n".to_string(),
+        ];
+        let formatted = formatter.format_items("pub mod outer {\npub mod inner {\n", 2, &items)?;
+
+        assert_eq!(formatted.opening, "pub mod outer {\n    pub mod inner {\n");
+        assert_eq!(
+            formatted.items,
+            vec![
+                "        pub fn run(x: u32) -> u32 {}\n",
+                "        pub struct Unit;\n"
+            ]
+        );
+        assert_eq!(formatted.closing, "    }\n}\n");
+        Ok(())
+    }
+
+    #[test]
+    fn backend_tokens_parse_case_insensitively() {
+        assert_eq!("RustFmt".parse(), Ok(FormatBackend::RustFmt));
+        assert_eq!("prettyplease".parse(), Ok(FormatBackend::PrettyPlease));
+        assert_eq!("none".parse(), Ok(FormatBackend::Unformatted));
+        assert!("bogus".parse::<FormatBackend>().is_err());
+    }
+}
@@ -1,15 +1,30 @@
 //! Syntax highlighting functionality for Rust code.
 
+use std::{
+    env, fmt,
+    io::{self, Write},
+    mem,
+    path::Path,
+    result,
+    str::FromStr,
+};
+
 use once_cell::sync::Lazy;
 use syntect::{
     easy::HighlightLines,
-    highlighting::{Style, Theme, ThemeSet},
+    highlighting::{Color, Style, Theme, ThemeSet},
+    html::{
+        IncludeBackground, append_highlighted_html_for_styled_line, start_highlighted_html_snippet,
+    },
     parsing::{SyntaxReference, SyntaxSet},
-    util::{LinesWithEndings, as_24_bit_terminal_escaped},
+    util::as_24_bit_terminal_escaped,
 };
 
 use crate::{Result, RuskelError};
 
+/// Name of the built-in theme used when no theme is configured.
+pub const DEFAULT_THEME: &str = "Solarized (dark)";
+
 /// Lazily loaded syntect syntax definitions including newline handling.
 static SYNTAX_SET: Lazy<SyntaxSet> = Lazy::new(SyntaxSet::load_defaults_newlines);
 /// Shared theme catalog for syntax highlighting.
@@ -17,13 +32,171 @@ static THEME_SET: Lazy<ThemeSet> = Lazy::new(ThemeSet::load_defaults);
 /// Cached lookup for the Rust syntax definition.
 static RUST_SYNTAX: Lazy<Option<&'static SyntaxReference>> =
     Lazy::new(|| SYNTAX_SET.find_syntax_by_extension("rs"));
-/// Reference to the Solarized (dark) theme used for highlighting output.
-static SOLARIZED_THEME: Lazy<&'static Theme> = Lazy::new(|| {
-    THEME_SET
-        .themes
-        .get("Solarized (dark)")
-        .expect("Solarized (dark) theme must exist")
-});
+
+/// The xterm palette for the 16 basic ANSI colors, used to approximate theme colors.
+const ANSI_16_PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Number of colors a terminal can display, used to pick the escape sequences emitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorDepth {
+    /// 24-bit RGB escapes.
+    TrueColor,
+    /// The xterm 256-color palette.
+    Ansi256,
+    /// The 16 basic ANSI colors.
+    Ansi16,
+}
+
+impl ColorDepth {
+    /// Guess the terminal's color depth from `COLORTERM` and `TERM`.
+    pub fn detect() -> Self {
+        Self::from_env(
+            env::var("COLORTERM").ok().as_deref(),
+            env::var("TERM").ok().as_deref(),
+        )
+    }
+
+    /// Guess the color depth from the values of `COLORTERM` and `TERM`.
+    fn from_env(colorterm: Option<&str>, term: Option<&str>) -> Self {
+        let colorterm = colorterm.unwrap_or_default().to_ascii_lowercase();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return Self::TrueColor;
+        }
+        if term.is_some_and(|term| term.contains("256color")) {
+            return Self::Ansi256;
+        }
+        Self::Ansi16
+    }
+
+    /// Short identifier used for CLI flags and configuration.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::TrueColor => "truecolor",
+            Self::Ansi256 => "256",
+            Self::Ansi16 => "16",
+        }
+    }
+}
+
+impl fmt::Display for ColorDepth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl FromStr for ColorDepth {
+    type Err = String;
+
+    fn from_str(token: &str) -> result::Result<Self, Self::Err> {
+        match token.to_ascii_lowercase().as_str() {
+            "truecolor" | "24bit" => Ok(Self::TrueColor),
+            "256" => Ok(Self::Ansi256),
+            "16" => Ok(Self::Ansi16),
+            other => Err(format!(
+                "invalid color depth '{other}'. Expected one of: truecolor, 256, 16."
+            )),
+        }
+    }
+}
+
+/// Output produced by a [`Highlighter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HighlightFormat {
+    /// Terminal escape sequences at the given color depth.
+    Ansi(ColorDepth),
+    /// An HTML `<pre>` block with inline styles.
+    Html,
+}
+
+/// Highlights Rust code with a configurable theme and output format.
+#[derive(Debug, Clone)]
+pub struct Highlighter {
+    /// Theme supplying colors for each scope.
+    theme: Theme,
+    /// Output format for highlighted code.
+    format: HighlightFormat,
+}
+
+impl Default for Highlighter {
+    fn default() -> Self {
+        Self {
+            theme: THEME_SET.themes[DEFAULT_THEME].clone(),
+            format: HighlightFormat::Ansi(ColorDepth::TrueColor),
+        }
+    }
+}
+
+impl Highlighter {
+    /// Create a highlighter using `theme`, which is either the name of a built-in theme or a path
+    /// to a `.tmTheme` file.
+    pub fn new(theme: &str, format: HighlightFormat) -> Result<Self> {
+        Ok(Self {
+            theme: load_theme(theme)?,
+            format,
+        })
+    }
+
+    /// Highlight `code` in full.
+    pub fn highlight(&self, code: &str) -> Result<String> {
+        let mut writer = self.writer(Vec::new())?;
+        writer.write_all(code.as_bytes())?;
+        let output = writer.finish()?;
+        String::from_utf8(output).map_err(|err| RuskelError::Highlight(err.to_string()))
+    }
+
+    /// Wrap `inner` in a writer that highlights everything written to it.
+    pub fn writer<W: Write>(&self, inner: W) -> Result<HighlightWriter<'_, W>> {
+        let syntax = *RUST_SYNTAX
+            .as_ref()
+            .ok_or_else(|| RuskelError::Highlight("Rust syntax not found".to_string()))?;
+        Ok(HighlightWriter {
+            inner,
+            highlighter: HighlightLines::new(syntax, &self.theme),
+            format: self.format,
+            theme: &self.theme,
+            started: false,
+            pending: Vec::new(),
+        })
+    }
+}
+
+/// Names of the built-in themes accepted by [`Highlighter::new`].
+pub fn theme_names() -> Vec<&'static str> {
+    THEME_SET.themes.keys().map(String::as_str).collect()
+}
+
+/// Resolve a built-in theme by name, or load a `.tmTheme` file from disk.
+fn load_theme(theme: &str) -> Result<Theme> {
+    if let Some(builtin) = THEME_SET.themes.get(theme) {
+        return Ok(builtin.clone());
+    }
+    let path = Path::new(theme);
+    if path.extension().is_some_and(|ext| ext == "tmTheme") || path.exists() {
+        return ThemeSet::get_theme(path)
+            .map_err(|err| RuskelError::Highlight(format!("failed to load theme {theme}: {err}")));
+    }
+    Err(RuskelError::Highlight(format!(
+        "unknown theme '{theme}'. Expected a .tmTheme file or one of: {}",
+        theme_names().join(", ")
+    )))
+}
 
 /// Applies syntax highlighting to Rust code using the Solarized (dark) theme.
 ///
@@ -33,17 +206,242 @@ static SOLARIZED_THEME: Lazy<&'static Theme> = Lazy::new(|| {
 /// # Returns
 /// A string with ANSI escape codes for terminal color output
 pub fn highlight_code(code: &str) -> Result<String> {
-    let syntax = *RUST_SYNTAX
-        .as_ref()
-        .ok_or_else(|| RuskelError::Highlight("Rust syntax not found".to_string()))?;
-    let mut h = HighlightLines::new(syntax, *SOLARIZED_THEME);
+    Highlighter::default().highlight(code)
+}
+
+/// A writer that highlights Rust code line by line before forwarding it to an inner writer.
+///
+/// Highlighter state carries across lines, so output may be written in arbitrary chunks. Partial
+/// lines are buffered until their newline arrives or [`HighlightWriter::finish`] is called.
+pub struct HighlightWriter<'a, W: Write> {
+    /// Destination for highlighted output.
+    inner: W,
+    /// Highlighter state shared across all lines written so far.
+    highlighter: HighlightLines<'a>,
+    /// Output format for highlighted lines.
+    format: HighlightFormat,
+    /// Theme used for HTML framing.
+    theme: &'a Theme,
+    /// Whether any output, including the HTML preamble, has been written.
+    started: bool,
+    /// Bytes of the current line that have not yet been terminated by a newline.
+    pending: Vec<u8>,
+}
+
+impl<W: Write> HighlightWriter<'_, W> {
+    /// Highlight any buffered partial line, flush, and return the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        if !self.pending.is_empty() {
+            let line = mem::take(&mut self.pending);
+            self.write_line(&line)?;
+        }
+        if self.format == HighlightFormat::Html {
+            self.start()?;
+            self.inner.write_all(b"</pre>\n")?;
+        }
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    /// Write any preamble required by the output format.
+    fn start(&mut self) -> io::Result<()> {
+        if !self.started {
+            self.started = true;
+            if self.format == HighlightFormat::Html {
+                let (preamble, _) = start_highlighted_html_snippet(self.theme);
+                self.inner.write_all(preamble.as_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Highlight a single line and write it to the inner writer.
+    fn write_line(&mut self, line: &[u8]) -> io::Result<()> {
+        self.start()?;
+        let line = String::from_utf8_lossy(line);
+        let ranges = self
+            .highlighter
+            .highlight_line(&line, &SYNTAX_SET)
+            .map_err(io::Error::other)?;
+        let output = match self.format {
+            HighlightFormat::Ansi(ColorDepth::TrueColor) => {
+                as_24_bit_terminal_escaped(&ranges[..], false)
+            }
+            HighlightFormat::Ansi(depth) => palette_escaped(&ranges, depth),
+            HighlightFormat::Html => {
+                let mut html = String::new();
+                append_highlighted_html_for_styled_line(&ranges, IncludeBackground::No, &mut html)
+                    .map_err(io::Error::other)?;
+                html
+            }
+        };
+        self.inner.write_all(output.as_bytes())
+    }
+}
+
+impl<W: Write> Write for HighlightWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        while let Some(end) = self.pending.iter().position(|&byte| byte == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            self.write_line(&line)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
 
+/// Render styled ranges with foreground escapes limited to a 256- or 16-color palette.
+fn palette_escaped(ranges: &[(Style, &str)], depth: ColorDepth) -> String {
     let mut output = String::new();
-    for line in LinesWithEndings::from(code) {
-        let ranges: Vec<(Style, &str)> = h.highlight_line(line, &SYNTAX_SET)?;
-        let escaped = as_24_bit_terminal_escaped(&ranges[..], false);
-        output.push_str(&escaped);
+    for (style, text) in ranges {
+        let color = style.foreground;
+        let escape = match depth {
+            ColorDepth::Ansi16 => {
+                let index = nearest_ansi16(color);
+                let code = if index < 8 {
+                    30 + index
+                } else {
+                    90 + index - 8
+                };
+                format!("\x1b[{code}m")
+            }
+            _ => format!("\x1b[38;5;{}m", nearest_ansi256(color)),
+        };
+        output.push_str(&escape);
+        output.push_str(text);
     }
+    output
+}
+
+/// Map an RGB color onto the xterm 256-color palette's color cube or grayscale ramp.
+fn nearest_ansi256(color: Color) -> u8 {
+    let Color { r, g, b, .. } = color;
+    if r == g && g == b {
+        return match r {
+            0..=7 => 16,
+            249..=255 => 231,
+            gray => 232 + ((u16::from(gray) - 8) * 24 / 247) as u8,
+        };
+    }
+    let level = |channel: u8| (u16::from(channel) * 5 + 127) / 255;
+    (16 + 36 * level(r) + 6 * level(g) + level(b)) as u8
+}
 
-    Ok(output)
+/// Find the index of the basic ANSI color closest to an RGB color.
+fn nearest_ansi16(color: Color) -> u8 {
+    let distance = |(r, g, b): (u8, u8, u8)| {
+        let dr = i32::from(color.r) - i32::from(r);
+        let dg = i32::from(color.g) - i32::from(g);
+        let db = i32::from(color.b) - i32::from(b);
+        dr * dr + dg * dg + db * db
+    };
+    (0..ANSI_16_PALETTE.len())
+        .min_by_key(|&index| distance(ANSI_16_PALETTE[index]))
+        .unwrap_or(0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sample source exercising comments, keywords and items.
+    const CODE: &str = "/// Docs\npub fn run(x: u32) -> u32 {}\npub struct Point;";
+
+    #[test]
+    fn writer_matches_buffered_highlighting_across_chunk_boundaries() -> Result<()> {
+        let highlighter = Highlighter::default();
+        let mut writer = highlighter.writer(Vec::new())?;
+        for chunk in CODE.as_bytes().chunks(5) {
+            writer.write_all(chunk)?;
+        }
+        let streamed = writer.finish()?;
+
+        assert_eq!(String::from_utf8_lossy(&streamed), highlight_code(CODE)?);
+        Ok(())
+    }
+
+    #[test]
+    fn reduced_color_depths_avoid_truecolor_escapes() -> Result<()> {
+        let ansi256 = Highlighter::new(DEFAULT_THEME, HighlightFormat::Ansi(ColorDepth::Ansi256))?
+            .highlight(CODE)?;
+        assert!(ansi256.contains("\x1b[38;5;"));
+        assert!(!ansi256.contains("\x1b[38;2;"));
+
+        let ansi16 = Highlighter::new(DEFAULT_THEME, HighlightFormat::Ansi(ColorDepth::Ansi16))?
+            .highlight(CODE)?;
+        assert!(!ansi16.contains("\x1b[38;"));
+        assert!(ansi16.contains("pub"));
+        Ok(())
+    }
+
+    #[test]
+    fn html_output_is_wrapped_in_pre_block() -> Result<()> {
+        let html = Highlighter::new("InspiredGitHub", HighlightFormat::Html)?.highlight(CODE)?;
+
+        assert!(html.starts_with("<pre style=\""));
+        assert!(html.ends_with("</pre>\n"));
+        assert!(html.contains("<span style=\""));
+        assert!(!html.contains('\x1b'));
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_theme_lists_builtins() {
+        let err = Highlighter::new("no-such-theme", HighlightFormat::Html)
+            .expect_err("unknown theme should fail");
+
+        assert!(err.to_string().contains(DEFAULT_THEME));
+    }
+
+    #[test]
+    fn color_depth_detection_follows_terminal_env() {
+        assert_eq!(
+            ColorDepth::from_env(Some("truecolor"), Some("xterm-256color")),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            ColorDepth::from_env(None, Some("xterm-256color")),
+            ColorDepth::Ansi256
+        );
+        assert_eq!(
+            ColorDepth::from_env(None, Some("xterm")),
+            ColorDepth::Ansi16
+        );
+        assert_eq!(ColorDepth::from_env(None, None), ColorDepth::Ansi16);
+    }
+
+    #[test]
+    fn palette_mapping_hits_cube_and_grayscale() {
+        assert_eq!(
+            nearest_ansi256(Color {
+                r: 255,
+                g: 0,
+                b: 0,
+                a: 255
+            }),
+            196
+        );
+        assert_eq!(
+            nearest_ansi256(Color {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 255
+            }),
+            16
+        );
+        assert_eq!(
+            nearest_ansi16(Color {
+                r: 250,
+                g: 10,
+                b: 10,
+                a: 255
+            }),
+            9
+        );
+    }
 }
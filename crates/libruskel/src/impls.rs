@@ -0,0 +1,249 @@
+//! Trait implementation views: the implementors of a trait, and the traits a type implements.
+//!
+//! Both views read every impl block in the rustdoc index, so they include the blanket impls and
+//! synthesized auto trait impls that the renderer normally hides.
+
+use std::collections::HashSet;
+
+use rustdoc_types::{Crate, Id, Impl, ItemEnum, ItemKind, Path, Type};
+
+use crate::{
+    crateutils::{render_generic_args, render_type},
+    error::{Result, RuskelError},
+    location::{SourceLocation, SourceLocator},
+    render::RenderSelection,
+    search::SearchIndex,
+    typesearch::{parse_item_path, path_matches},
+};
+
+/// Item kinds that name a type impls can be written for.
+pub const TYPE_KINDS: &[ItemKind] = &[
+    ItemKind::Struct,
+    ItemKind::Enum,
+    ItemKind::Union,
+    ItemKind::TypeAlias,
+    ItemKind::Primitive,
+];
+
+/// How a trait implementation came to exist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ImplOrigin {
+    /// Written in source, including derived impls.
+    Explicit,
+    /// Instantiated from a blanket impl such as `impl<T: Display> ToString for T`.
+    Blanket,
+    /// Synthesized by the compiler for an auto trait such as `Send`.
+    Auto,
+}
+
+impl ImplOrigin {
+    /// Short label used in listings.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Explicit => "impl",
+            Self::Blanket => "blanket",
+            Self::Auto => "auto",
+        }
+    }
+}
+
+/// A trait implementation found by [`ImplQuery`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraitImpl {
+    /// Identifier of the impl block.
+    pub impl_id: Id,
+    /// Implemented trait with its generic arguments, e.g. `core::convert::From<u8>`.
+    pub trait_path: String,
+    /// Implementing type, fully qualified when it is a named type, e.g. `my_crate::Widget`.
+    pub type_path: String,
+    /// Whether the impl is explicit, blanket or synthesized.
+    pub origin: ImplOrigin,
+    /// Where the impl is written, if rustdoc recorded a span.
+    pub location: Option<SourceLocation>,
+}
+
+/// Trait implementations selected by a query, with the skeleton restricted to them.
+#[derive(Debug, Clone)]
+pub struct ImplResponse {
+    /// Matching implementations: explicit impls first, then blanket and auto trait impls.
+    pub impls: Vec<TraitImpl>,
+    /// Rendered skeleton containing the implementing types and the matching impl blocks.
+    pub rendered: String,
+}
+
+/// Which side of an implementation a query names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImplQuery<'a> {
+    /// Every type implementing the trait at this path.
+    Implementors(&'a str),
+    /// Every trait implemented by the type at this path.
+    TraitsOf(&'a str),
+}
+
+impl ImplQuery<'_> {
+    /// Find the matching trait implementations in `crate_data`.
+    ///
+    /// Impls for the crate's own types are kept only when `index` includes the type, so private
+    /// types are omitted unless the index covers private items. Fails when the path does not
+    /// name a trait, or a type, known to the crate.
+    pub(crate) fn find(
+        self,
+        crate_data: &Crate,
+        index: &SearchIndex,
+        locator: &mut SourceLocator,
+    ) -> Result<Vec<TraitImpl>> {
+        let (path, kinds, noun): (&str, &[ItemKind], &str) = match self {
+            Self::Implementors(path) => (path, &[ItemKind::Trait, ItemKind::TraitAlias], "trait"),
+            Self::TraitsOf(path) => (path, TYPE_KINDS, "type"),
+        };
+        let segments = parse_item_path(path)?;
+        let ids: HashSet<Id> = crate_data
+            .paths
+            .iter()
+            .filter(|(_, summary)| kinds.contains(&summary.kind))
+            .filter(|(_, summary)| path_matches(&segments, &summary.path))
+            .map(|(id, _)| *id)
+            .collect();
+        // Primitives such as `u8` are named by impls without an id.
+        let primitive = match segments.as_slice() {
+            [name] if matches!(self, Self::TraitsOf(_)) => Some(name.as_str()),
+            _ => None,
+        };
+
+        let impls = self.select(crate_data, index, &ids, primitive, locator);
+        if impls.is_empty() && ids.is_empty() && primitive.is_none() {
+            return Err(RuskelError::InvalidSearch(format!(
+                "no {noun} named '{path}' was found"
+            )));
+        }
+        Ok(impls)
+    }
+
+    /// Find the trait implementations involving the items in `ids`, or the primitive named
+    /// `primitive`, on the side of the impl this query names.
+    pub(crate) fn select(
+        self,
+        crate_data: &Crate,
+        index: &SearchIndex,
+        ids: &HashSet<Id>,
+        primitive: Option<&str>,
+        locator: &mut SourceLocator,
+    ) -> Vec<TraitImpl> {
+        let mut impls: Vec<TraitImpl> = crate_data
+            .index
+            .values()
+            .filter_map(|item| {
+                let ItemEnum::Impl(impl_) = &item.inner else {
+                    return None;
+                };
+                let trait_ = impl_.trait_.as_ref()?;
+                if impl_.is_negative {
+                    return None;
+                }
+                let selected = match self {
+                    Self::Implementors(_) => ids.contains(&trait_.id),
+                    Self::TraitsOf(_) => match &impl_.for_ {
+                        Type::ResolvedPath(path) => ids.contains(&path.id),
+                        Type::Primitive(name) => primitive == Some(name.as_str()),
+                        _ => false,
+                    },
+                };
+                if !selected {
+                    return None;
+                }
+                if let Type::ResolvedPath(path) = &impl_.for_
+                    && crate_data.index.contains_key(&path.id)
+                    && index.entry(&path.id).is_none()
+                {
+                    return None;
+                }
+                Some(TraitImpl {
+                    impl_id: item.id,
+                    trait_path: qualified_path(crate_data, trait_),
+                    type_path: match &impl_.for_ {
+                        Type::ResolvedPath(path) => qualified_path(crate_data, path),
+                        other => render_type(other),
+                    },
+                    origin: origin(impl_),
+                    location: locator.locate(item),
+                })
+            })
+            .collect();
+
+        impls.sort_by(|a, b| match self {
+            Self::Implementors(_) => (a.origin, &a.type_path, &a.trait_path).cmp(&(
+                b.origin,
+                &b.type_path,
+                &b.trait_path,
+            )),
+            Self::TraitsOf(_) => (a.origin, &a.trait_path, &a.type_path).cmp(&(
+                b.origin,
+                &b.trait_path,
+                &b.type_path,
+            )),
+        });
+        impls
+    }
+}
+
+/// Classify how an impl came to exist.
+fn origin(impl_: &Impl) -> ImplOrigin {
+    if impl_.is_synthetic {
+        ImplOrigin::Auto
+    } else if impl_.blanket_impl.is_some() {
+        ImplOrigin::Blanket
+    } else {
+        ImplOrigin::Explicit
+    }
+}
+
+/// Render a path with its canonical qualification and generic arguments.
+fn qualified_path(crate_data: &Crate, path: &Path) -> String {
+    let base = match crate_data.paths.get(&path.id) {
+        Some(summary) => summary.path.join("::"),
+        None => path.path.replace("$crate::", ""),
+    };
+    let args = path
+        .args
+        .as_ref()
+        .map(|args| render_generic_args(args))
+        .unwrap_or_default();
+    format!("{base}{args}")
+}
+
+/// Select the impl blocks of `impls` for rendering, together with the types they implement and
+/// the modules containing those types. Implementing types are expanded so their fields show, but
+/// their other impl blocks stay hidden.
+///
+/// Impls for types outside the index, such as foreign types, cannot be placed in the skeleton and
+/// are left out. Returns `None` when no impl can be placed.
+pub fn build_impl_selection(
+    crate_data: &Crate,
+    index: &SearchIndex,
+    impls: &[TraitImpl],
+) -> Option<RenderSelection> {
+    let mut matches = HashSet::new();
+    let mut context = HashSet::new();
+    let mut expanded = HashSet::new();
+    for found in impls {
+        let Some(ItemEnum::Impl(impl_)) =
+            crate_data.index.get(&found.impl_id).map(|item| &item.inner)
+        else {
+            continue;
+        };
+        let Type::ResolvedPath(path) = &impl_.for_ else {
+            continue;
+        };
+        if let Some(entry) = index.entry(&path.id) {
+            context.extend(entry.ancestors.iter().copied());
+            context.insert(path.id);
+            expanded.insert(path.id);
+            matches.insert(found.impl_id);
+        }
+    }
+    if matches.is_empty() {
+        return None;
+    }
+    expanded.extend(matches.iter().copied());
+    Some(RenderSelection::new(matches, context, expanded))
+}
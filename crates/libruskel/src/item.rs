@@ -0,0 +1,70 @@
+//! Single item view: one item's signature, documentation and trait implementations.
+
+use std::collections::HashSet;
+
+use rustdoc_types::Crate;
+
+use crate::{
+    impls::{ImplQuery, TraitImpl},
+    location::{SourceLocation, SourceLocator},
+    search::{SearchEntry, SearchIndex, SearchItemKind},
+};
+
+/// A single item described by path, with a skeleton of the item itself.
+#[derive(Debug, Clone)]
+pub struct ItemResponse {
+    /// Fully qualified path of the item.
+    pub path: String,
+    /// Kind of the item.
+    pub kind: SearchItemKind,
+    /// Compact signature of the item, if it has one.
+    pub signature: Option<String>,
+    /// Documentation comment of the item.
+    pub docs: Option<String>,
+    /// Where the item is defined, if rustdoc recorded a span.
+    pub location: Option<SourceLocation>,
+    /// Traits implemented by the item when it is a type, or its implementors when it is a trait.
+    pub impls: Vec<TraitImpl>,
+    /// Rendered skeleton of the item with its members.
+    pub rendered: String,
+}
+
+/// Describe the indexed item `entry`, leaving the skeleton for the caller to render.
+pub fn describe_item(
+    crate_data: &Crate,
+    index: &SearchIndex,
+    entry: &SearchEntry,
+    locator: &mut SourceLocator,
+) -> ItemResponse {
+    let query = match entry.kind {
+        SearchItemKind::Struct
+        | SearchItemKind::Enum
+        | SearchItemKind::Union
+        | SearchItemKind::TypeAlias
+        | SearchItemKind::Primitive => Some(ImplQuery::TraitsOf(&entry.path_string)),
+        SearchItemKind::Trait | SearchItemKind::TraitAlias => {
+            Some(ImplQuery::Implementors(&entry.path_string))
+        }
+        _ => None,
+    };
+    let impls = match query {
+        Some(query) => query.select(
+            crate_data,
+            index,
+            &HashSet::from([entry.item_id]),
+            None,
+            locator,
+        ),
+        None => Vec::new(),
+    };
+
+    ItemResponse {
+        path: entry.path_string.clone(),
+        kind: entry.kind,
+        signature: entry.signature.clone(),
+        docs: entry.docs.clone(),
+        location: entry.location.clone(),
+        impls,
+        rendered: String::new(),
+    }
+}
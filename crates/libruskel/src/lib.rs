@@ -7,23 +7,43 @@
 //!
 //! Ruskel works by first fetching all dependencies, then using the nightly Rust toolchain
 //! to generate JSON documentation data. This data is then parsed and rendered into
-//! the skeletonized format. The skeletonized code is then formatted with rustfmt, and optionally
-//! has syntax highlighting applied.
+//! the skeletonized format. The skeletonized code is then formatted with rustfmt (or an in-process
+//! pretty-printer, see [`FormatBackend`]), and optionally has syntax highlighting applied.
 //!
 //!
 //! You must have the nightly Rust toolchain installed to use (but not to install) Ruskel.
 
+/// Cache of loaded crates shared between requests.
+mod cache;
 /// Helper utilities for querying Cargo metadata and managing crate sources.
 mod cargoutils;
+/// Transitive type closures of items.
+mod closure;
 /// Utilities for normalising rustdoc structures before rendering.
 mod crateutils;
 /// Error types exposed by the libruskel crate.
 mod error;
+/// Formatting backends for rendered skeleton output.
+mod format;
 /// Frontmatter formatting and configuration helpers.
 mod frontmatter;
 pub mod highlight;
+/// Trait implementor and implemented-trait queries.
+mod impls;
+/// Single item views with signature, docs and trait implementations.
+mod item;
 /// Identifier helpers shared across rendering code.
 mod keywords;
+/// Source locations derived from rustdoc spans.
+mod location;
+/// Method resolution across inherent impls, trait impls and `Deref` targets.
+mod methods;
+/// Size-bounded pages of skeletons and search results.
+mod page;
+/// Progress reporting and cancellation for crate loads.
+mod progress;
+/// Structured search query parsing and evaluation.
+mod query;
 /// Rendering logic that turns rustdoc data into skeleton code.
 mod render;
 /// Public API surface for driving the renderer.
@@ -32,19 +52,37 @@ mod ruskel;
 mod search;
 /// Shared signature formatting used by search and rendering.
 mod signature;
+/// Sources of rustdoc data: cargo builds, the sysroot and pre-generated JSON.
+mod source;
 /// Target parsing helpers for user-provided specifications.
 mod target;
 /// Shared nightly/rustup helpers.
 pub mod toolchain;
+/// Type-directed search over function signatures.
+mod typesearch;
 
 pub use ruskel::Ruskel;
 
 pub use crate::{
+    cargoutils::{
+        CrateInfo, CrateRead, CrateReadOptions, PackageInfo, PackageSource, ProjectCrate,
+        ResolvedTarget,
+    },
     error::{Result, RuskelError},
+    format::FormatBackend,
     frontmatter::{FrontmatterBinaryTarget, FrontmatterConfig, FrontmatterHit, FrontmatterSearch},
+    impls::{ImplOrigin, ImplQuery, ImplResponse, TraitImpl},
+    item::ItemResponse,
+    location::SourceLocation,
+    methods::{Method, MethodsResponse},
+    page::{Page, PageItem, PageRequest},
+    progress::{LoadStage, ProgressHook},
     render::Renderer,
     search::{
-        ListItem, SearchDomain, SearchItemKind, SearchOptions, SearchPathSegment, SearchResponse,
-        SearchResult, describe_domains, parse_domain_token, parse_domain_tokens,
+        ListItem, ListOptions, QueryMatcher, REGEX_QUERY_PREFIX, SearchDomain, SearchItemKind,
+        SearchMode, SearchOptions, SearchPathSegment, SearchResponse, SearchResult,
+        describe_domains, parse_domain_token, parse_domain_tokens, parse_kind_token,
     },
+    source::{CargoSource, CrateSource, FixtureSource, SysrootSource},
+    typesearch::TypeUsage,
 };
@@ -0,0 +1,233 @@
+//! Source locations derived from rustdoc item spans.
+
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use rustdoc_types::{Item, Span};
+use syn::{ImplItemFn, ItemFn};
+
+/// Where an item is defined, relative to its package root when that can be determined.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SourceLocation {
+    /// Source file, relative to the package root (e.g. `src/sync/mutex.rs`).
+    pub file: String,
+    /// One-based line on which the item's definition begins.
+    pub line: usize,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
+
+/// Resolves rustdoc spans into package-relative source locations.
+///
+/// Rustdoc records spans relative to the directory cargo was invoked from (the workspace root for
+/// local packages) or as absolute paths (for registry sources). Both are mapped onto the package
+/// that contains the file; resolved file names are cached since many items share a file.
+#[derive(Debug, Default)]
+pub struct SourceLocator {
+    /// Root directory of the package being documented, if known.
+    package_root: Option<PathBuf>,
+    /// Cache of rustdoc file names to their package-relative display form.
+    files: HashMap<PathBuf, String>,
+}
+
+impl SourceLocator {
+    /// Create a locator for a package rooted at `package_root`.
+    pub fn new(package_root: Option<&Path>) -> Self {
+        Self {
+            package_root: package_root.map(Path::to_path_buf),
+            files: HashMap::new(),
+        }
+    }
+
+    /// Resolve the location of `item`, if rustdoc recorded a span for it.
+    pub fn locate(&mut self, item: &Item) -> Option<SourceLocation> {
+        item.span.as_ref().map(|span| self.locate_span(span))
+    }
+
+    /// Read the whole source lines covered by `item`'s span.
+    pub fn read_span(&self, item: &Item) -> Option<String> {
+        let span = item.span.as_ref()?;
+        let contents = fs::read_to_string(self.absolute_file(&span.filename)?).ok()?;
+        let first = span.begin.0.checked_sub(1)?;
+        let count = span.end.0.checked_sub(first)?;
+        let lines: Vec<&str> = contents.lines().skip(first).take(count).collect();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+
+    /// Resolve a span into a source location.
+    fn locate_span(&mut self, span: &Span) -> SourceLocation {
+        let file = match self.files.get(&span.filename) {
+            Some(file) => file.clone(),
+            None => {
+                let file = self.relative_file(&span.filename);
+                self.files.insert(span.filename.clone(), file.clone());
+                file
+            }
+        };
+        SourceLocation {
+            file,
+            line: span.begin.0,
+        }
+    }
+
+    /// Express a rustdoc file name relative to the package that contains it.
+    fn relative_file(&self, filename: &Path) -> String {
+        let Some(absolute) = self.absolute_file(filename) else {
+            return display_path(filename);
+        };
+        if let Some(root) = &self.package_root
+            && let Ok(relative) = absolute.strip_prefix(root)
+        {
+            return display_path(relative);
+        }
+        absolute
+            .ancestors()
+            .skip(1)
+            .find(|dir| dir.join("Cargo.toml").is_file())
+            .and_then(|dir| absolute.strip_prefix(dir).ok())
+            .map_or_else(|| display_path(filename), display_path)
+    }
+
+    /// Locate a rustdoc file name on disk, resolving relative names against the package root and
+    /// its ancestors.
+    fn absolute_file(&self, filename: &Path) -> Option<PathBuf> {
+        if filename.is_absolute() {
+            return Some(filename.to_path_buf());
+        }
+        self.package_root
+            .as_deref()?
+            .ancestors()
+            .map(|dir| dir.join(filename))
+            .find(|candidate| candidate.is_file())
+    }
+}
+
+/// Extract the body block, braces included, from the source of a function definition.
+///
+/// The source is parsed as a free or associated function and the block is sliced from the
+/// original text, so comments and formatting survive. Returns `None` for bodiless declarations
+/// such as `fn f();` and for source that does not parse as a single function.
+pub fn function_body(source: &str) -> Option<&str> {
+    let block = syn::parse_str::<ItemFn>(source)
+        .map(|function| *function.block)
+        .or_else(|_| syn::parse_str::<ImplItemFn>(source).map(|function| function.block))
+        .ok()?;
+    source.get(block.brace_token.span.join().byte_range())
+}
+
+/// Render a path with forward slashes so locations read the same on every platform.
+fn display_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn span(filename: impl Into<PathBuf>, line: usize) -> Span {
+        Span {
+            filename: filename.into(),
+            begin: (line, 1),
+            end: (line, 10),
+        }
+    }
+
+    #[test]
+    fn workspace_relative_spans_are_rebased_onto_the_package() {
+        let workspace = tempdir().expect("tempdir");
+        let package = workspace.path().join("crates/widget");
+        fs::create_dir_all(package.join("src")).expect("create src");
+        fs::write(package.join("src/lib.rs"), "").expect("write lib");
+
+        let mut locator = SourceLocator::new(Some(&package));
+        let location = locator.locate_span(&span("crates/widget/src/lib.rs", 12));
+
+        assert_eq!(location.to_string(), "src/lib.rs:12");
+    }
+
+    #[test]
+    fn absolute_spans_outside_the_package_use_their_own_manifest() {
+        let registry = tempdir().expect("tempdir");
+        let dependency = registry.path().join("dep-1.0.0");
+        fs::create_dir_all(dependency.join("src/sync")).expect("create src");
+        fs::write(dependency.join("Cargo.toml"), "").expect("write manifest");
+        let file = dependency.join("src/sync/mutex.rs");
+        fs::write(&file, "").expect("write file");
+
+        let mut locator = SourceLocator::new(None);
+        let location = locator.locate_span(&span(file, 123));
+
+        assert_eq!(location.to_string(), "src/sync/mutex.rs:123");
+    }
+
+    #[test]
+    fn function_body_skips_signature_punctuation() {
+        let source = "    pub fn run(f: impl Fn() -> [u8; 2], s: &str /* { */) -> u32 {\n        if true { 1 } else { 2 }\n    } // trailing";
+
+        assert_eq!(
+            function_body(source),
+            Some("{\n        if true { 1 } else { 2 }\n    }")
+        );
+        assert_eq!(function_body("fn declared(&self);"), None);
+        assert_eq!(function_body("extern \"C\" fn f() {}"), Some("{}"));
+    }
+
+    #[test]
+    fn function_body_handles_generics_literals_and_trailing_comments() {
+        let source = "    default fn run<const N: usize>() -> Grid<{ N + 1 }> where [(); { N }]: Sized {\n        let (open, raw) = ('{', r#\"}\"#);\n        grid(open, raw)\n    } // }";
+
+        assert_eq!(
+            function_body(source),
+            Some("{\n        let (open, raw) = ('{', r#\"}\"#);\n        grid(open, raw)\n    }")
+        );
+    }
+
+    #[test]
+    fn read_span_returns_whole_lines() {
+        let dir = tempdir().expect("tempdir");
+        let file = dir.path().join("lib.rs");
+        fs::write(&file, "// header\npub fn run() {\n    body();\n}\n").expect("write file");
+        let locator = SourceLocator::new(Some(dir.path()));
+        let item = Item {
+            id: rustdoc_types::Id(0),
+            crate_id: 0,
+            name: Some("run".into()),
+            span: Some(Span {
+                filename: PathBuf::from("lib.rs"),
+                begin: (2, 0),
+                end: (4, 1),
+            }),
+            visibility: rustdoc_types::Visibility::Public,
+            docs: None,
+            links: HashMap::new(),
+            attrs: Vec::new(),
+            deprecation: None,
+            inner: rustdoc_types::ItemEnum::ExternType,
+        };
+
+        assert_eq!(
+            locator.read_span(&item).as_deref(),
+            Some("pub fn run() {\n    body();\n}")
+        );
+    }
+
+    #[test]
+    fn unresolvable_spans_keep_their_original_file_name() {
+        let mut locator = SourceLocator::new(None);
+        let location = locator.locate_span(&span("/rustc/abc/library/core/src/mem/mod.rs", 7));
+
+        assert_eq!(location.file, "/rustc/abc/library/core/src/mem/mod.rs");
+        assert_eq!(location.line, 7);
+    }
+}
@@ -0,0 +1,383 @@
+//! Method resolution view: everything callable on a type, including methods reached through
+//! `Deref`.
+//!
+//! Methods are collected from the type's inherent impls and explicit trait impls, then from the
+//! impls of each `Deref::Target` in turn, following the order in which method calls are resolved.
+//! Blanket and synthesized impls are left out.
+
+use std::collections::HashSet;
+
+use rustdoc_types::{
+    Crate, Id, Impl, Item, ItemEnum, ItemKind, ItemSummary, Path, Type, Visibility,
+};
+
+use crate::{
+    crateutils::{docs, render_generic_args, render_type},
+    error::{Result, RuskelError},
+    impls::TYPE_KINDS,
+    location::SourceLocation,
+    search::{SearchIndex, SearchItemKind},
+    signature::item_signature,
+    typesearch::{parse_item_path, path_matches},
+};
+
+/// Maximum number of `Deref` steps followed from the queried type.
+const MAX_DEREF_DEPTH: usize = 8;
+
+/// A method callable on the queried type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Method {
+    /// Method name.
+    pub name: String,
+    /// Rendered signature, e.g. `pub fn len(&self) -> usize`.
+    pub signature: String,
+    /// Trait providing the method, e.g. `Iterator`, or `None` for inherent methods.
+    pub trait_name: Option<String>,
+    /// `Deref` targets traversed to reach the method, outermost first.
+    pub deref_chain: Vec<String>,
+    /// Where the method is defined, if rustdoc recorded a span.
+    pub location: Option<SourceLocation>,
+    /// Documentation comment rendered as `///` lines.
+    docs: String,
+}
+
+impl Method {
+    /// Describe where the method comes from, e.g. `from trait Iterator` or
+    /// `via Deref<Target = str>`.
+    pub fn origin(&self) -> String {
+        let mut parts = Vec::new();
+        match &self.trait_name {
+            Some(name) => parts.push(format!("from trait {name}")),
+            None if self.deref_chain.is_empty() => parts.push("inherent".to_string()),
+            None => {}
+        }
+        if !self.deref_chain.is_empty() {
+            let chain: Vec<String> = self
+                .deref_chain
+                .iter()
+                .map(|target| format!("Deref<Target = {target}>"))
+                .collect();
+            parts.push(format!("via {}", chain.join(" -> ")));
+        }
+        parts.join(" ")
+    }
+}
+
+/// Methods callable on a type, with a skeleton listing them.
+#[derive(Debug, Clone)]
+pub struct MethodsResponse {
+    /// Fully qualified path of the queried type.
+    pub type_path: String,
+    /// Callable methods: inherent methods, trait methods, then methods reached through `Deref`.
+    pub methods: Vec<Method>,
+    /// Rendered impl block listing the methods, each annotated with its origin.
+    pub rendered: String,
+}
+
+/// The types an impl block may be for, as named by a path or a `Deref` target.
+struct TypeTarget {
+    /// Identifiers of named types.
+    ids: HashSet<Id>,
+    /// Primitive name, for primitives such as `str` that impls name without an id.
+    primitive: Option<String>,
+}
+
+impl TypeTarget {
+    /// Resolve a type path against the crate's item paths.
+    fn resolve(crate_data: &Crate, path: &str) -> Result<(Self, String)> {
+        let segments = parse_item_path(path)?;
+        let mut matches: Vec<(&Id, &ItemSummary)> = crate_data
+            .paths
+            .iter()
+            .filter(|(_, summary)| TYPE_KINDS.contains(&summary.kind))
+            .filter(|(_, summary)| path_matches(&segments, &summary.path))
+            .collect();
+        matches.sort_by(|a, b| a.1.path.cmp(&b.1.path));
+        if let Some((_, first)) = matches.first() {
+            // Impls name primitives by name rather than through their item id.
+            if first.kind == ItemKind::Primitive
+                && let Some(name) = first.path.last()
+            {
+                return Ok((
+                    Self {
+                        ids: HashSet::new(),
+                        primitive: Some(name.clone()),
+                    },
+                    name.clone(),
+                ));
+            }
+            let type_path = first.path.join("::");
+            let ids = matches.into_iter().map(|(id, _)| *id).collect();
+            return Ok((
+                Self {
+                    ids,
+                    primitive: None,
+                },
+                type_path,
+            ));
+        }
+        match segments.as_slice() {
+            [name] if is_primitive(crate_data, name) => Ok((
+                Self {
+                    ids: HashSet::new(),
+                    primitive: Some(name.clone()),
+                },
+                name.clone(),
+            )),
+            _ => Err(RuskelError::InvalidSearch(format!(
+                "no type named '{path}' was found"
+            ))),
+        }
+    }
+
+    /// Build the target for a `Deref::Target` type, if it names a type impls can be found for.
+    fn from_type(ty: &Type) -> Option<Self> {
+        match ty {
+            Type::ResolvedPath(path) => Some(Self {
+                ids: HashSet::from([path.id]),
+                primitive: None,
+            }),
+            Type::Primitive(name) => Some(Self {
+                ids: HashSet::new(),
+                primitive: Some(name.clone()),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Does an impl's `for_` type name this target?
+    fn matches(&self, ty: &Type) -> bool {
+        match ty {
+            Type::ResolvedPath(path) => self.ids.contains(&path.id),
+            Type::Primitive(name) => self.primitive.as_deref() == Some(name.as_str()),
+            _ => false,
+        }
+    }
+
+    /// Key used to detect `Deref` cycles.
+    fn key(&self) -> (Vec<Id>, Option<String>) {
+        let mut ids: Vec<Id> = self.ids.iter().copied().collect();
+        ids.sort_unstable_by_key(|id| id.0);
+        (ids, self.primitive.clone())
+    }
+}
+
+/// Does any impl in the crate name the primitive `name`?
+fn is_primitive(crate_data: &Crate, name: &str) -> bool {
+    crate_data.index.values().any(|item| match &item.inner {
+        ItemEnum::Primitive(primitive) => primitive.name == name,
+        ItemEnum::Impl(impl_) => matches!(&impl_.for_, Type::Primitive(n) if n == name),
+        _ => false,
+    })
+}
+
+/// Collect the methods callable on the type at `type_path`.
+///
+/// Methods hidden by `index`, such as private methods when the index omits private items, are
+/// skipped. Methods reached through `Deref` must take `self` and are skipped when an earlier
+/// method of the same name shadows them.
+pub fn collect_methods(
+    crate_data: &Crate,
+    index: &SearchIndex,
+    type_path: &str,
+) -> Result<MethodsResponse> {
+    let (mut target, type_path) = TypeTarget::resolve(crate_data, type_path)?;
+    let mut methods = Vec::new();
+    let mut seen_names = HashSet::new();
+    let mut visited = HashSet::new();
+    let mut chain = Vec::new();
+
+    loop {
+        visited.insert(target.key());
+        let impls: Vec<&Impl> = crate_data
+            .index
+            .values()
+            .filter_map(|item| match &item.inner {
+                ItemEnum::Impl(impl_) if target.matches(&impl_.for_) => Some(impl_),
+                _ => None,
+            })
+            .filter(|impl_| {
+                !impl_.is_synthetic && !impl_.is_negative && impl_.blanket_impl.is_none()
+            })
+            .collect();
+
+        let mut level = Vec::new();
+        let mut deref_target = None;
+        for impl_ in impls {
+            let trait_name = match &impl_.trait_ {
+                Some(trait_) => {
+                    if is_deref(crate_data, trait_) {
+                        deref_target =
+                            deref_target.or_else(|| associated_target(crate_data, impl_));
+                    }
+                    Some(trait_name(crate_data, trait_))
+                }
+                None => None,
+            };
+            for item in impl_methods(crate_data, impl_) {
+                if !chain.is_empty() && !takes_self(item) {
+                    continue;
+                }
+                // Impls for primitives are not indexed, so fall back on the item's visibility.
+                let location = match index.entry(&item.id) {
+                    Some(entry) => entry.location.clone(),
+                    None if matches!(item.visibility, Visibility::Public | Visibility::Default) => {
+                        None
+                    }
+                    None => continue,
+                };
+                let Some(signature) = item_signature(crate_data, item, SearchItemKind::Method)
+                else {
+                    continue;
+                };
+                level.push(Method {
+                    name: item.name.clone().unwrap_or_default(),
+                    signature,
+                    trait_name: trait_name.clone(),
+                    deref_chain: chain.clone(),
+                    location,
+                    docs: docs(item),
+                });
+            }
+        }
+
+        level.sort_by(|a, b| {
+            (a.trait_name.is_some(), &a.trait_name, &a.name).cmp(&(
+                b.trait_name.is_some(),
+                &b.trait_name,
+                &b.name,
+            ))
+        });
+        // Methods on the same type never shadow each other, only those behind a `Deref`.
+        level.retain(|method| method.deref_chain.is_empty() || !seen_names.contains(&method.name));
+        seen_names.extend(level.iter().map(|method| method.name.clone()));
+        methods.extend(level);
+
+        let Some(next_ty) = deref_target else {
+            break;
+        };
+        let Some(next) = TypeTarget::from_type(next_ty) else {
+            break;
+        };
+        if chain.len() >= MAX_DEREF_DEPTH || visited.contains(&next.key()) {
+            break;
+        }
+        chain.push(render_type(next_ty));
+        target = next;
+    }
+
+    let rendered = render_methods(&type_path, &methods);
+    Ok(MethodsResponse {
+        type_path,
+        methods,
+        rendered,
+    })
+}
+
+/// The methods defined in an impl block, plus the provided methods of its trait that the impl
+/// does not override, when the trait is part of the crate.
+fn impl_methods<'a>(crate_data: &'a Crate, impl_: &'a Impl) -> Vec<&'a Item> {
+    let mut items: Vec<&Item> = impl_
+        .items
+        .iter()
+        .filter_map(|id| crate_data.index.get(id))
+        .filter(|item| matches!(item.inner, ItemEnum::Function(_)))
+        .collect();
+    if let Some(trait_) = &impl_.trait_
+        && let Some(ItemEnum::Trait(trait_item)) =
+            crate_data.index.get(&trait_.id).map(|item| &item.inner)
+    {
+        items.extend(
+            trait_item
+                .items
+                .iter()
+                .filter_map(|id| crate_data.index.get(id))
+                .filter(|item| matches!(item.inner, ItemEnum::Function(_)))
+                .filter(|item| {
+                    item.name
+                        .as_ref()
+                        .is_some_and(|name| impl_.provided_trait_methods.contains(name))
+                }),
+        );
+    }
+    items
+}
+
+/// Does the function take a `self` receiver?
+fn takes_self(item: &Item) -> bool {
+    match &item.inner {
+        ItemEnum::Function(function) => function
+            .sig
+            .inputs
+            .first()
+            .is_some_and(|(name, _)| name == "self"),
+        _ => false,
+    }
+}
+
+/// Is `trait_` the standard library's `Deref` trait?
+fn is_deref(crate_data: &Crate, trait_: &Path) -> bool {
+    match crate_data.paths.get(&trait_.id) {
+        Some(summary) => {
+            summary.path.last().is_some_and(|name| name == "Deref")
+                && summary
+                    .path
+                    .first()
+                    .is_some_and(|root| matches!(root.as_str(), "core" | "std" | "alloc"))
+        }
+        None => trait_.path.rsplit("::").next() == Some("Deref"),
+    }
+}
+
+/// The `Target` type declared by a `Deref` impl.
+fn associated_target<'a>(crate_data: &'a Crate, impl_: &'a Impl) -> Option<&'a Type> {
+    impl_
+        .items
+        .iter()
+        .filter_map(|id| crate_data.index.get(id))
+        .find_map(|item| match &item.inner {
+            ItemEnum::AssocType {
+                type_: Some(ty), ..
+            } if item.name.as_deref() == Some("Target") => Some(ty),
+            _ => None,
+        })
+}
+
+/// Short trait name with its generic arguments, e.g. `From<u8>`.
+fn trait_name(crate_data: &Crate, trait_: &Path) -> String {
+    let name = match crate_data.paths.get(&trait_.id) {
+        Some(summary) => summary.path.last().cloned().unwrap_or_default(),
+        None => trait_
+            .path
+            .rsplit("::")
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+    };
+    let args = trait_
+        .args
+        .as_ref()
+        .map(|args| render_generic_args(args))
+        .unwrap_or_default();
+    format!("{name}{args}")
+}
+
+/// Render the methods as a single impl block, preceding each with a comment naming its origin.
+///
+/// The output is unformatted; callers pass it through the configured formatter.
+fn render_methods(type_path: &str, methods: &[Method]) -> String {
+    if methods.is_empty() {
+        return String::new();
+    }
+    let mut output = format!("impl {type_path} {{\n");
+    for (index, method) in methods.iter().enumerate() {
+        if index > 0 {
+            output.push('\n');
+        }
+        output.push_str(&format!("// {}\n", method.origin()));
+        output.push_str(&method.docs);
+        output.push_str(&format!("{} {{}}\n", method.signature));
+    }
+    output.push_str("}\n");
+    output
+}
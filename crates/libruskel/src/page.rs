@@ -0,0 +1,62 @@
+//! Size-bounded pages of skeletons and search results.
+//!
+//! Skeletons split between the top-level items of the rendered module, and searches between
+//! matches, so every page is valid Rust on its own and the next page starts where the previous
+//! one stopped.
+
+use crate::search::SearchItemKind;
+
+/// Which page of output to produce and how large it may grow.
+///
+/// A page always holds at least one item, even when that item alone exceeds `max_chars`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PageRequest {
+    /// Position of the first item on the page, as returned by [`Page::next`].
+    pub start: usize,
+    /// Maximum number of characters in the rendered page.
+    pub max_chars: Option<usize>,
+    /// Maximum number of top-level items, or search matches, on the page.
+    pub max_items: Option<usize>,
+}
+
+impl PageRequest {
+    /// Whether a page of `items` items rendering to `chars` characters stays within the limits.
+    pub(crate) fn allows(&self, items: usize, chars: usize) -> bool {
+        self.max_items.is_none_or(|max| items <= max)
+            && self.max_chars.is_none_or(|max| chars <= max)
+    }
+}
+
+/// A top-level item of a paged skeleton, or a match of a paged search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageItem {
+    /// Fully qualified path of the item.
+    pub path: String,
+    /// Kind of the item.
+    pub kind: SearchItemKind,
+}
+
+/// One page of a skeleton or of search results.
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    /// Rendered skeleton of the page's items.
+    pub rendered: String,
+    /// Position of the page's first item among all items.
+    pub start: usize,
+    /// Items on this page.
+    pub items: Vec<T>,
+    /// Items following this page.
+    pub remaining: Vec<PageItem>,
+}
+
+impl<T> Page<T> {
+    /// The start of the next page, or `None` when this is the last page.
+    pub fn next(&self) -> Option<usize> {
+        (!self.remaining.is_empty()).then(|| self.start + self.items.len())
+    }
+
+    /// Total number of items across all pages.
+    pub fn total(&self) -> usize {
+        self.start + self.items.len() + self.remaining.len()
+    }
+}
@@ -0,0 +1,216 @@
+//! Progress reporting and cancellation for crate loads.
+
+use std::{
+    fmt,
+    sync::{
+        Arc,
+        atomic::{AtomicU8, Ordering},
+    },
+};
+
+use crate::error::{Result, RuskelError};
+
+/// A stage of loading and rendering a crate, in the order a load passes through them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LoadStage {
+    /// Locating the package that provides the target.
+    Resolving,
+    /// Downloading the package or its dependencies.
+    Fetching,
+    /// Compiling dependencies ahead of documentation.
+    Building,
+    /// Running rustdoc on the package itself.
+    Documenting,
+    /// Indexing and rendering the loaded crate.
+    Rendering,
+}
+
+impl LoadStage {
+    /// Every stage, in order.
+    pub const ALL: [Self; 5] = [
+        Self::Resolving,
+        Self::Fetching,
+        Self::Building,
+        Self::Documenting,
+        Self::Rendering,
+    ];
+
+    /// One-based position of the stage within [`Self::ALL`].
+    pub fn step(self) -> usize {
+        self as usize + 1
+    }
+
+    /// Human-readable description of the stage.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Resolving => "resolving target",
+            Self::Fetching => "fetching crates",
+            Self::Building => "building dependencies",
+            Self::Documenting => "documenting",
+            Self::Rendering => "rendering",
+        }
+    }
+
+    /// Classify a cargo status line such as `   Compiling serde v1.0.219`.
+    pub(crate) fn from_cargo_status(line: &str) -> Option<Self> {
+        match line.split_whitespace().next()? {
+            "Updating" | "Locking" | "Downloading" | "Downloaded" => Some(Self::Fetching),
+            "Compiling" | "Checking" => Some(Self::Building),
+            "Documenting" => Some(Self::Documenting),
+            _ => None,
+        }
+    }
+}
+
+/// Observes a crate load: told when the load enters each stage, and asked whether to stop.
+pub trait ProgressHook: Send + Sync {
+    /// Called once as the load enters `stage`; stages only move forward.
+    fn stage(&self, stage: LoadStage);
+
+    /// Whether the caller has abandoned the load. Checked between stages and while cargo runs;
+    /// returning `true` kills the cargo process and fails the load with
+    /// [`RuskelError::Cancelled`].
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+/// The progress hook of a load, if any, with the furthest stage reported so far.
+#[derive(Clone, Default)]
+pub struct Progress {
+    /// Hook receiving stage changes and cancellation checks.
+    hook: Option<Arc<dyn ProgressHook>>,
+    /// Step of the furthest stage reported, shared between clones.
+    reached: Arc<AtomicU8>,
+}
+
+impl fmt::Debug for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Progress")
+            .field("hooked", &self.hook.is_some())
+            .field("reached", &self.reached.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+impl Progress {
+    /// Report progress of a load to `hook`.
+    pub(crate) fn new(hook: Arc<dyn ProgressHook>) -> Self {
+        Self {
+            hook: Some(hook),
+            reached: Arc::default(),
+        }
+    }
+
+    /// Progress of a new load reporting to the same hook, starting again from the first stage.
+    pub(crate) fn restart(&self) -> Self {
+        Self {
+            hook: self.hook.clone(),
+            reached: Arc::default(),
+        }
+    }
+
+    /// Report entering `stage`, unless the load already reached it or a later stage.
+    pub(crate) fn stage(&self, stage: LoadStage) {
+        let Some(hook) = &self.hook else {
+            return;
+        };
+        let step = stage.step() as u8;
+        if self.reached.fetch_max(step, Ordering::SeqCst) < step {
+            hook.stage(stage);
+        }
+    }
+
+    /// Whether a hook may cancel the load.
+    pub(crate) fn is_cancellable(&self) -> bool {
+        self.hook.is_some()
+    }
+
+    /// Fail with [`RuskelError::Cancelled`] when the hook reports cancellation.
+    pub(crate) fn check(&self) -> Result<()> {
+        match &self.hook {
+            Some(hook) if hook.is_cancelled() => Err(RuskelError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    };
+
+    use super::{LoadStage, Progress, ProgressHook};
+    use crate::error::RuskelError;
+
+    #[derive(Default)]
+    struct Recorder {
+        stages: Mutex<Vec<LoadStage>>,
+        cancelled: AtomicBool,
+    }
+
+    impl ProgressHook for Recorder {
+        fn stage(&self, stage: LoadStage) {
+            self.stages.lock().expect("stages").push(stage);
+        }
+
+        fn is_cancelled(&self) -> bool {
+            self.cancelled.load(Ordering::SeqCst)
+        }
+    }
+
+    #[test]
+    fn progress_reports_each_stage_once_and_in_order() {
+        let recorder = Arc::new(Recorder::default());
+        let progress = Progress::new(recorder.clone());
+        for line in [
+            "    Updating crates.io index",
+            "   Compiling serde v1.0.219",
+            "   Compiling serde_json v1.0.140",
+            "    Checking itoa v1.0.15",
+            " Documenting app v0.1.0",
+            "warning: unused import",
+        ] {
+            if let Some(stage) = LoadStage::from_cargo_status(line) {
+                progress.stage(stage);
+            }
+        }
+        progress.stage(LoadStage::Building);
+        progress.stage(LoadStage::Rendering);
+
+        assert_eq!(
+            *recorder.stages.lock().expect("stages"),
+            [
+                LoadStage::Fetching,
+                LoadStage::Building,
+                LoadStage::Documenting,
+                LoadStage::Rendering
+            ]
+        );
+        assert!(progress.check().is_ok());
+        recorder.cancelled.store(true, Ordering::SeqCst);
+        assert!(matches!(progress.check(), Err(RuskelError::Cancelled)));
+    }
+
+    #[test]
+    fn restarted_progress_reports_stages_again() {
+        let recorder = Arc::new(Recorder::default());
+        let progress = Progress::new(recorder.clone());
+        progress.stage(LoadStage::Rendering);
+        let next = progress.restart();
+        next.stage(LoadStage::Resolving);
+        next.stage(LoadStage::Rendering);
+        progress.stage(LoadStage::Resolving);
+
+        assert_eq!(
+            *recorder.stages.lock().expect("stages"),
+            [
+                LoadStage::Rendering,
+                LoadStage::Resolving,
+                LoadStage::Rendering
+            ]
+        );
+    }
+}
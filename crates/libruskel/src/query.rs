@@ -0,0 +1,598 @@
+//! Structured search query language.
+//!
+//! A structured query is a sequence of terms combined with `AND` (implied between adjacent
+//! terms), `OR` and `NOT` (or a leading `-`), grouped with parentheses:
+//!
+//! ```text
+//! kind:trait name:Async doc:"cancel safe" -deprecated path:sync::*
+//! ```
+//!
+//! A term is a word or a quoted phrase, optionally scoped to a field. `name:`, `doc:`, `path:`
+//! and `sig:` restrict a term to a single search domain, while unscoped terms match across the
+//! domains selected in [`SearchOptions`]. `kind:` filters by item kind and accepts
+//! comma-separated alternatives, as in `kind:struct,enum`. Unquoted values may use `*` as a
+//! wildcard, and a `regex:` prefix turns a value into a regular expression. The bare word
+//! `deprecated`, or `is:deprecated`, matches items carrying a `#[deprecated]` attribute; quote it
+//! to search for the word instead.
+
+use std::{iter::Peekable, str::Chars};
+
+use crate::{
+    error::{Result, RuskelError},
+    search::{
+        EntryMatch, QueryMatcher, REGEX_QUERY_PREFIX, SearchDomain, SearchEntry, SearchItemKind,
+        SearchOptions, parse_kind_token,
+    },
+};
+
+/// Field that scopes a query term.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    /// Item names.
+    Name,
+    /// Documentation strings.
+    Doc,
+    /// Canonical paths.
+    Path,
+    /// Rendered signatures.
+    Signature,
+    /// Item kinds.
+    Kind,
+    /// Item flags.
+    Is,
+}
+
+impl Field {
+    /// Recognise a field prefix, returning `None` for anything else.
+    fn parse(prefix: &str) -> Option<Self> {
+        match prefix.to_ascii_lowercase().as_str() {
+            "name" => Some(Self::Name),
+            "doc" | "docs" => Some(Self::Doc),
+            "path" => Some(Self::Path),
+            "sig" | "signature" => Some(Self::Signature),
+            "kind" => Some(Self::Kind),
+            "is" => Some(Self::Is),
+            _ => None,
+        }
+    }
+
+    /// Search domain matched by the field, or `None` for kind and flag filters.
+    fn domain(self) -> Option<SearchDomain> {
+        match self {
+            Self::Name => Some(SearchDomain::NAMES),
+            Self::Doc => Some(SearchDomain::DOCS),
+            Self::Path => Some(SearchDomain::PATHS),
+            Self::Signature => Some(SearchDomain::SIGNATURES),
+            Self::Kind | Self::Is => None,
+        }
+    }
+}
+
+/// Lexical unit of a structured query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// Opening parenthesis.
+    Open,
+    /// Closing parenthesis.
+    Close,
+    /// `AND` keyword.
+    And,
+    /// `OR` keyword.
+    Or,
+    /// `NOT` keyword or a leading `-`.
+    Not,
+    /// A word or phrase, optionally scoped to a field.
+    Term {
+        /// Field prefix, if any.
+        field: Option<Field>,
+        /// Term text without the field prefix or quotes.
+        value: String,
+        /// Whether the value was quoted, which disables wildcards.
+        quoted: bool,
+    },
+}
+
+/// Split a query into tokens.
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&ch) = chars.peek() {
+        match ch {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '"' => {
+                chars.next();
+                tokens.push(Token::Term {
+                    field: None,
+                    value: read_phrase(&mut chars)?,
+                    quoted: true,
+                });
+            }
+            '-' => {
+                chars.next();
+                if chars
+                    .peek()
+                    .is_some_and(|next| !next.is_whitespace() && *next != ')')
+                {
+                    tokens.push(Token::Not);
+                } else {
+                    tokens.push(Token::Term {
+                        field: None,
+                        value: "-".to_string(),
+                        quoted: false,
+                    });
+                }
+            }
+            _ => tokens.push(read_word(&mut chars)?),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Read the remainder of a quoted phrase, after its opening quote.
+fn read_phrase(chars: &mut Peekable<Chars<'_>>) -> Result<String> {
+    let mut phrase = String::new();
+    for ch in chars.by_ref() {
+        if ch == '"' {
+            return Ok(phrase);
+        }
+        phrase.push(ch);
+    }
+    Err(invalid("unterminated quoted phrase"))
+}
+
+/// Read an unquoted word, a keyword, or a field-scoped term.
+fn read_word(chars: &mut Peekable<Chars<'_>>) -> Result<Token> {
+    let mut word = String::new();
+    while let Some(&ch) = chars.peek() {
+        if ch.is_whitespace() || ch == '(' || ch == ')' {
+            break;
+        }
+        if ch == '"'
+            && let Some(field) = word.strip_suffix(':').and_then(Field::parse)
+        {
+            chars.next();
+            return Ok(Token::Term {
+                field: Some(field),
+                value: read_phrase(chars)?,
+                quoted: true,
+            });
+        }
+        word.push(ch);
+        chars.next();
+    }
+
+    match word.as_str() {
+        "AND" => return Ok(Token::And),
+        "OR" => return Ok(Token::Or),
+        "NOT" => return Ok(Token::Not),
+        _ => {}
+    }
+    if let Some((prefix, value)) = word.split_once(':')
+        && let Some(field) = Field::parse(prefix)
+    {
+        if value.is_empty() {
+            return Err(invalid(format!("missing value after '{prefix}:'")));
+        }
+        return Ok(Token::Term {
+            field: Some(field),
+            value: value.to_string(),
+            quoted: false,
+        });
+    }
+    Ok(Token::Term {
+        field: None,
+        value: word,
+        quoted: false,
+    })
+}
+
+/// Build an invalid-search error.
+fn invalid(message: impl Into<String>) -> RuskelError {
+    RuskelError::InvalidSearch(message.into())
+}
+
+/// Parsed query expression.
+#[derive(Debug)]
+enum Expr {
+    /// Text term matched within a set of domains.
+    Term {
+        /// Compiled term matcher.
+        matcher: QueryMatcher,
+        /// Domains the term is matched against.
+        domains: SearchDomain,
+    },
+    /// Item kind filter.
+    Kind(Vec<SearchItemKind>),
+    /// Deprecated item filter.
+    Deprecated,
+    /// Negated sub-expression.
+    Not(Box<Self>),
+    /// Conjunction of sub-expressions.
+    All(Vec<Self>),
+    /// Disjunction of sub-expressions.
+    Any(Vec<Self>),
+}
+
+impl Expr {
+    /// Evaluate the expression, recording the evidence of positive matches in `hits`.
+    ///
+    /// Evidence from negated terms and failed alternatives is discarded, so highlights and
+    /// scores only reflect the terms that selected the entry.
+    fn eval<'q>(&'q self, entry: &SearchEntry, hits: &mut EntryMatch<'q>) -> bool {
+        match self {
+            Self::Term { matcher, domains } => hits.record(matcher, entry, *domains),
+            Self::Kind(kinds) => kinds.contains(&entry.kind),
+            Self::Deprecated => entry.deprecated,
+            Self::Not(inner) => !inner.eval(entry, &mut EntryMatch::default()),
+            Self::All(parts) => parts.iter().all(|part| part.eval(entry, hits)),
+            Self::Any(branches) => {
+                let mut any = false;
+                for branch in branches {
+                    let mut branch_hits = EntryMatch::default();
+                    if branch.eval(entry, &mut branch_hits) {
+                        hits.merge(branch_hits);
+                        any = true;
+                    }
+                }
+                any
+            }
+        }
+    }
+}
+
+/// Recursive-descent parser over a token stream.
+struct Parser<'a> {
+    /// Tokens being parsed.
+    tokens: Vec<Token>,
+    /// Index of the next unread token.
+    pos: usize,
+    /// Options controlling how term values are compiled.
+    options: &'a SearchOptions,
+}
+
+impl Parser<'_> {
+    /// Return the next token without consuming it.
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    /// Consume the next token if it equals `token`.
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Parse `and ( OR and )*`.
+    fn parse_or(&mut self) -> Result<Expr> {
+        let mut branches = vec![self.parse_and()?];
+        while self.eat(&Token::Or) {
+            branches.push(self.parse_and()?);
+        }
+        Ok(collapse(branches, Expr::Any))
+    }
+
+    /// Parse `unary ( [AND] unary )*`.
+    fn parse_and(&mut self) -> Result<Expr> {
+        let mut parts = vec![self.parse_unary()?];
+        loop {
+            match self.peek() {
+                None | Some(Token::Or | Token::Close) => break,
+                Some(Token::And) => self.pos += 1,
+                Some(_) => {}
+            }
+            parts.push(self.parse_unary()?);
+        }
+        Ok(collapse(parts, Expr::All))
+    }
+
+    /// Parse `NOT unary | primary`.
+    fn parse_unary(&mut self) -> Result<Expr> {
+        if self.eat(&Token::Not) {
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    /// Parse a parenthesised group or a single term.
+    fn parse_primary(&mut self) -> Result<Expr> {
+        match self.tokens.get(self.pos).cloned() {
+            Some(Token::Open) => {
+                self.pos += 1;
+                let expr = self.parse_or()?;
+                if !self.eat(&Token::Close) {
+                    return Err(invalid("missing closing parenthesis"));
+                }
+                Ok(expr)
+            }
+            Some(Token::Term {
+                field,
+                value,
+                quoted,
+            }) => {
+                self.pos += 1;
+                self.term(field, &value, quoted)
+            }
+            Some(Token::Close) => Err(invalid("unexpected ')'")),
+            Some(Token::And | Token::Or | Token::Not) | None => {
+                Err(invalid("expected a search term"))
+            }
+        }
+    }
+
+    /// Compile a single term into an expression.
+    fn term(&self, field: Option<Field>, value: &str, quoted: bool) -> Result<Expr> {
+        if field == Some(Field::Kind) {
+            let mut kinds = Vec::new();
+            for token in value.split(',').filter(|token| !token.trim().is_empty()) {
+                kinds.extend_from_slice(parse_kind_token(token).map_err(invalid)?);
+            }
+            return Ok(Expr::Kind(kinds));
+        }
+        if field == Some(Field::Is) {
+            return match value.to_ascii_lowercase().as_str() {
+                "deprecated" => Ok(Expr::Deprecated),
+                _ => Err(invalid(format!("unknown flag 'is:{value}'"))),
+            };
+        }
+        if field.is_none() && !quoted && value.eq_ignore_ascii_case("deprecated") {
+            return Ok(Expr::Deprecated);
+        }
+
+        let (pattern, regex) = match value.strip_prefix(REGEX_QUERY_PREFIX) {
+            Some(pattern) => (pattern, true),
+            None => (value, false),
+        };
+        if pattern.is_empty() {
+            return Err(invalid(format!("empty pattern in term '{value}'")));
+        }
+        let case_sensitive = self.options.case_sensitive;
+        let matcher = if !regex && !quoted && pattern.contains('*') {
+            let source = pattern
+                .split('*')
+                .map(regex::escape)
+                .collect::<Vec<_>>()
+                .join(".*");
+            QueryMatcher::compile(&source, true, case_sensitive, false)?
+        } else {
+            QueryMatcher::compile(pattern, regex, case_sensitive, self.options.fuzzy)?
+        };
+        let domains = field
+            .and_then(Field::domain)
+            .unwrap_or(self.options.domains);
+        Ok(Expr::Term { matcher, domains })
+    }
+}
+
+/// Unwrap single-element groups so simple queries evaluate without extra nesting.
+fn collapse(mut exprs: Vec<Expr>, group: fn(Vec<Expr>) -> Expr) -> Expr {
+    if exprs.len() == 1 {
+        exprs.remove(0)
+    } else {
+        group(exprs)
+    }
+}
+
+/// Parsed structured query, usable as a predicate over index entries.
+#[derive(Debug)]
+pub struct StructuredQuery {
+    /// Root expression.
+    expr: Expr,
+}
+
+impl StructuredQuery {
+    /// Parse `options.query`, or return `None` when the query is empty.
+    ///
+    /// Case sensitivity and fuzzy settings apply to every text term, and unscoped terms
+    /// match across `options.domains`.
+    pub fn parse(options: &SearchOptions) -> Result<Option<Self>> {
+        let tokens = tokenize(&options.query)?;
+        if tokens.is_empty() {
+            return Ok(None);
+        }
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            options,
+        };
+        let expr = parser.parse_or()?;
+        if parser.pos < parser.tokens.len() {
+            return Err(invalid("unexpected ')'"));
+        }
+        Ok(Some(Self { expr }))
+    }
+
+    /// Test `entry` against the query, returning the evidence of a match.
+    pub(crate) fn evaluate(&self, entry: &SearchEntry) -> Option<EntryMatch<'_>> {
+        let mut hits = EntryMatch::default();
+        self.expr.eval(entry, &mut hits).then_some(hits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::SearchMode;
+
+    fn options(query: &str) -> SearchOptions {
+        let mut options = SearchOptions::new(query);
+        options.mode = SearchMode::Structured;
+        options
+    }
+
+    fn entry(kind: SearchItemKind, path: &str, docs: Option<&str>) -> SearchEntry {
+        let raw_name = path.rsplit("::").next().unwrap_or(path).to_string();
+        SearchEntry {
+            item_id: rustdoc_types::Id(0),
+            kind,
+            path: Vec::new(),
+            path_string: path.to_string(),
+            raw_name: raw_name.clone(),
+            display_name: raw_name,
+            docs: docs.map(ToOwned::to_owned),
+            signature: None,
+            ancestors: Vec::new(),
+            location: None,
+            deprecated: false,
+        }
+    }
+
+    fn matches(query: &StructuredQuery, entry: &SearchEntry) -> bool {
+        query.evaluate(entry).is_some()
+    }
+
+    fn parse(query: &str) -> StructuredQuery {
+        StructuredQuery::parse(&options(query))
+            .expect("query should parse")
+            .expect("query should not be empty")
+    }
+
+    #[test]
+    fn tokenizer_handles_fields_phrases_and_negation() {
+        let tokens = tokenize(r#"kind:trait doc:"cancel safe" -(a OR b)"#).unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Term {
+                    field: Some(Field::Kind),
+                    value: "trait".into(),
+                    quoted: false,
+                },
+                Token::Term {
+                    field: Some(Field::Doc),
+                    value: "cancel safe".into(),
+                    quoted: true,
+                },
+                Token::Not,
+                Token::Open,
+                Token::Term {
+                    field: None,
+                    value: "a".into(),
+                    quoted: false,
+                },
+                Token::Or,
+                Token::Term {
+                    field: None,
+                    value: "b".into(),
+                    quoted: false,
+                },
+                Token::Close,
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_prefixes_stay_part_of_the_word() {
+        let tokens = tokenize("Widget::id").unwrap();
+        assert_eq!(
+            tokens,
+            vec![Token::Term {
+                field: None,
+                value: "Widget::id".into(),
+                quoted: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn fields_kinds_and_negation_combine() {
+        let query = parse(r#"kind:trait name:Async doc:"cancel safe" -deprecated"#);
+        let matching = entry(
+            SearchItemKind::Trait,
+            "tokio::io::AsyncRead",
+            Some("Reads are cancel safe."),
+        );
+        assert!(matches(&query, &matching));
+
+        let deprecated = SearchEntry {
+            deprecated: true,
+            ..entry(
+                SearchItemKind::Trait,
+                "tokio::io::AsyncOld",
+                Some("Reads are cancel safe."),
+            )
+        };
+        assert!(!matches(&query, &deprecated));
+        assert!(matches(&parse("is:deprecated"), &deprecated));
+        assert!(!matches(&parse("deprecated"), &matching));
+
+        // Quoted, the word is searched for as text.
+        let mentions = entry(
+            SearchItemKind::Trait,
+            "tokio::io::AsyncNew",
+            Some("Replaces a deprecated trait."),
+        );
+        assert!(matches(&parse(r#""deprecated""#), &mentions));
+        assert!(!matches(&parse("deprecated"), &mentions));
+
+        let wrong_kind = entry(
+            SearchItemKind::Struct,
+            "tokio::io::AsyncBuf",
+            Some("cancel safe"),
+        );
+        assert!(!matches(&query, &wrong_kind));
+    }
+
+    #[test]
+    fn or_binds_looser_than_and() {
+        let query = parse("kind:struct name:Mutex OR kind:fn name:spawn");
+        assert!(matches(
+            &query,
+            &entry(SearchItemKind::Struct, "sync::Mutex", None)
+        ));
+        assert!(matches(
+            &query,
+            &entry(SearchItemKind::Function, "task::spawn", None)
+        ));
+        assert!(!matches(
+            &query,
+            &entry(SearchItemKind::Function, "sync::Mutex", None)
+        ));
+    }
+
+    #[test]
+    fn path_wildcards_match_nested_items() {
+        let query = parse("path:sync::* kind:struct,enum");
+        assert!(matches(
+            &query,
+            &entry(SearchItemKind::Struct, "tokio::sync::Mutex", None)
+        ));
+        assert!(matches(
+            &query,
+            &entry(SearchItemKind::Enum, "tokio::sync::TryLock", None)
+        ));
+        assert!(!matches(
+            &query,
+            &entry(SearchItemKind::Struct, "tokio::net::Socket", None)
+        ));
+    }
+
+    #[test]
+    fn malformed_queries_are_rejected() {
+        for query in [
+            "(name:a",
+            "name:a)",
+            "a OR",
+            "kind:widget",
+            r#"doc:"open"#,
+            "name:",
+        ] {
+            let err = StructuredQuery::parse(&options(query)).expect_err(query);
+            assert!(matches!(err, RuskelError::InvalidSearch(_)), "{query}");
+        }
+        assert!(StructuredQuery::parse(&options("  ")).unwrap().is_none());
+    }
+}
@@ -1,8 +1,11 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    io::Write,
+    path::PathBuf,
+};
 
 use once_cell::sync::Lazy;
 use regex::Regex;
-use rust_format::{Config, Formatter, RustFmt};
 use rustdoc_types::{
     AssocItemConstraint, AssocItemConstraintKind, Crate, FunctionPointer, FunctionSignature,
     GenericArg, GenericArgs, GenericBound, Id, Impl, Item, ItemEnum, MacroKind, Path, PolyTrait,
@@ -12,12 +15,18 @@ use rustdoc_types::{
 use crate::{
     crateutils::*,
     error::{Result, RuskelError},
+    format::{FormatBackend, SkeletonFormatter},
     frontmatter::FrontmatterConfig,
     keywords::is_reserved_word,
+    location::{SourceLocator, function_body},
+    page::{Page, PageItem, PageRequest},
     search::SearchItemKind,
     signature,
 };
 
+/// Bytes of rendered items [`Renderer::render_to`] collects before formatting and writing them.
+const STREAM_BATCH_BYTES: usize = 64 * 1024;
+
 /// Traits that we render via `#[derive(...)]` annotations instead of explicit impl blocks.
 const DERIVE_TRAITS: &[&str] = &[
     "Clone",
@@ -570,7 +579,7 @@ impl RenderSelection {
 /// Configurable renderer that turns rustdoc data into skeleton Rust source.
 pub struct Renderer {
     /// Formatter used to produce tidy Rust output.
-    formatter: RustFmt,
+    formatter: SkeletonFormatter,
     /// Whether auto trait implementations should be included in the output.
     render_auto_impls: bool,
     /// Whether private items should be rendered.
@@ -583,6 +592,12 @@ pub struct Renderer {
     selection: Option<RenderSelection>,
     /// Optional frontmatter configuration rendered before crate content.
     frontmatter: Option<FrontmatterConfig>,
+    /// Whether items are annotated with comments pointing at their source location.
+    source_locations: bool,
+    /// Whether matched or targeted functions embed their original body.
+    item_source: bool,
+    /// Package root that source locations are reported relative to.
+    package_root: Option<PathBuf>,
 }
 
 /// Mutable rendering context shared across helper functions.
@@ -595,6 +610,8 @@ struct RenderState<'a, 'b> {
     filter_matched: bool,
     /// Pre-split filter path components to avoid reallocating per item check.
     filter_components: Vec<&'a str>,
+    /// Resolves item spans for location annotations and embedded source.
+    locator: SourceLocator,
 }
 
 impl Default for Renderer {
@@ -606,15 +623,17 @@ impl Default for Renderer {
 impl Renderer {
     /// Create a renderer with default configuration.
     pub fn new() -> Self {
-        let config = Config::new_str().option("brace_style", "PreferSameLine");
         Self {
-            formatter: RustFmt::from_config(config),
+            formatter: SkeletonFormatter::default(),
             render_auto_impls: false,
             render_private_items: false,
             render_blanket_impls: false,
             filter: String::new(),
             selection: None,
             frontmatter: None,
+            source_locations: false,
+            item_source: false,
+            package_root: None,
         }
     }
 
@@ -642,6 +661,40 @@ impl Renderer {
         self
     }
 
+    /// Select the backend used to format rendered output.
+    pub fn with_format_backend(mut self, backend: FormatBackend) -> Self {
+        self.formatter.set_backend(backend);
+        self
+    }
+
+    /// Return the unformatted output with a warning comment when formatting fails, instead of
+    /// an error.
+    pub fn with_best_effort_format(mut self, best_effort: bool) -> Self {
+        self.formatter.set_best_effort(best_effort);
+        self
+    }
+
+    /// Annotate each rendered item with a `// file:line` comment pointing at its definition?
+    pub fn with_source_locations(mut self, source_locations: bool) -> Self {
+        self.source_locations = source_locations;
+        self
+    }
+
+    /// Embed the original body of matched or targeted functions in place of `{}`?
+    ///
+    /// Bodies are read from the files named by rustdoc spans. Only search matches and items at or
+    /// below the filter path are expanded; rendering a whole crate embeds nothing.
+    pub fn with_item_source(mut self, item_source: bool) -> Self {
+        self.item_source = item_source;
+        self
+    }
+
+    /// Report source locations, and resolve embedded source, relative to this package root.
+    pub fn with_package_root(mut self, package_root: impl Into<PathBuf>) -> Self {
+        self.package_root = Some(package_root.into());
+        self
+    }
+
     /// Restrict rendering to the provided selection.
     pub(crate) fn with_selection(mut self, selection: RenderSelection) -> Self {
         self.selection = Some(selection);
@@ -656,7 +709,31 @@ impl Renderer {
 
     /// Render a crate into formatted Rust source text.
     pub fn render(&self, crate_data: &Crate) -> Result<String> {
-        let mut state = RenderState {
+        self.state(crate_data).render()
+    }
+
+    /// Render a crate into `out`, formatting and writing top-level items in batches so
+    /// consumers see the first items before the whole crate has been rendered. The output is the
+    /// same as [`Self::render`]'s.
+    ///
+    /// Nothing is written if the configured filter matches no items.
+    pub fn render_to<W: Write + ?Sized>(&self, crate_data: &Crate, out: &mut W) -> Result<()> {
+        self.state(crate_data).render_to(out)
+    }
+
+    /// Render one page of a crate, splitting between the top-level items of the rendered
+    /// module: the filter target when it names a module, the crate root otherwise.
+    ///
+    /// The page is enclosed in the modules leading to the rendered module, so it is valid Rust
+    /// on its own. Only the items that can fit on the page are formatted; the items after them
+    /// are just listed in [`Page::remaining`].
+    pub fn render_page(&self, crate_data: &Crate, request: &PageRequest) -> Result<Page<PageItem>> {
+        self.state(crate_data).render_page(request)
+    }
+
+    /// Build a fresh rendering context for `crate_data`.
+    fn state<'a, 'b>(&'a self, crate_data: &'b Crate) -> RenderState<'a, 'b> {
+        RenderState {
             config: self,
             filter_matched: false,
             crate_data,
@@ -665,11 +742,16 @@ impl Renderer {
             } else {
                 self.filter.split("::").collect()
             },
-        };
-        state.render()
+            locator: SourceLocator::new(self.package_root.as_deref()),
+        }
     }
 }
 
+/// Write a chunk of rendered output, mapping IO failures into [`RuskelError::Write`].
+fn write_chunk<W: Write + ?Sized>(out: &mut W, chunk: &str) -> Result<()> {
+    out.write_all(chunk.as_bytes()).map_err(RuskelError::Write)
+}
+
 impl RenderState<'_, '_> {
     /// Render the crate, applying filters and formatting output.
     pub fn render(&mut self) -> Result<String> {
@@ -681,19 +763,215 @@ impl RenderState<'_, '_> {
             return Err(RuskelError::FilterNotMatched(self.config.filter.clone()));
         }
 
-        let mut composed = String::new();
-        if let Some(frontmatter) = &self.config.frontmatter
-            && let Some(prefix) = frontmatter.render(
-                self.config.render_private_items,
-                self.config.render_auto_impls,
-                self.config.render_blanket_impls,
-            )
-        {
-            composed.push_str(&prefix);
+        let mut composed = self.frontmatter().unwrap_or_default();
+        composed.push_str(&self.config.formatter.format(&output)?);
+
+        Ok(composed)
+    }
+
+    /// Render the crate one top-level item at a time, formatting and writing the items in
+    /// batches of roughly [`STREAM_BATCH_BYTES`] as they are produced.
+    pub fn render_to<W: Write + ?Sized>(&mut self, out: &mut W) -> Result<()> {
+        let root_item = must_get(self.crate_data, &self.crate_data.root)?;
+        if !self.selection_context_contains(&root_item.id) {
+            return write_chunk(out, &self.frontmatter().unwrap_or_default());
+        }
+        let module = try_extract_item!(root_item, ItemEnum::Module)?;
+        let path_prefix = render_name(root_item);
+        let opening = self.render_module_header(&path_prefix, root_item);
+
+        let mut batch = String::new();
+        let mut started = false;
+        for item_id in &module.items {
+            let item = must_get(self.crate_data, item_id)?;
+            let chunk = self.render_item(&path_prefix, item, false)?;
+            if chunk.trim().is_empty() {
+                continue;
+            }
+            if batch.len() >= STREAM_BATCH_BYTES {
+                // Trailing whitespace separates the batch from this item, so it moves along.
+                let carried = batch.split_off(batch.trim_end().len());
+                self.write_batch(out, &opening, &batch, &mut started)?;
+                batch = carried;
+            }
+            batch.push_str(&chunk);
+        }
+
+        if !self.config.filter.is_empty() && !self.filter_matched {
+            return Err(RuskelError::FilterNotMatched(self.config.filter.clone()));
+        }
+        let closing = self.write_batch(out, &opening, &batch, &mut started)?;
+        write_chunk(out, &closing)?;
+        out.flush().map_err(RuskelError::Write)
+    }
+
+    /// Format `batch` as the next items of the root module opened by `opening` and write them
+    /// to `out`, preceded by the frontmatter and the formatted module header unless `started`.
+    ///
+    /// Returns the formatted closing brace of the module.
+    fn write_batch<W: Write + ?Sized>(
+        &self,
+        out: &mut W,
+        opening: &str,
+        batch: &str,
+        started: &mut bool,
+    ) -> Result<String> {
+        let items = if batch.trim().is_empty() {
+            Vec::new()
+        } else {
+            vec![batch.to_string()]
+        };
+        let formatted = self.config.formatter.format_items(opening, 1, &items)?;
+        if !*started {
+            *started = true;
+            write_chunk(out, &self.frontmatter().unwrap_or_default())?;
+            write_chunk(out, &formatted.opening)?;
+        }
+        for item in &formatted.items {
+            write_chunk(out, item)?;
+        }
+        Ok(formatted.closing)
+    }
+
+    /// Render one page of the crate. See [`Renderer::render_page`].
+    pub fn render_page(&mut self, request: &PageRequest) -> Result<Page<PageItem>> {
+        let root_item = must_get(self.crate_data, &self.crate_data.root)?;
+        let mut module_item = root_item;
+        let mut path_prefix = render_name(root_item);
+        let mut headers = vec![self.render_module_header(&path_prefix, root_item)];
+
+        // Descend along the filter so a module target pages through its own items.
+        while let Some(child_id) = self.filter_module_child(&path_prefix, module_item)? {
+            let child = must_get(self.crate_data, &child_id)?;
+            let target = matches!(self.filter_match(&path_prefix, child), FilterMatch::Hit);
+            path_prefix = ppush(&path_prefix, &render_name(child));
+            let header = self.render_module_header(&path_prefix, child);
+            headers.push(self.annotate_location(child, header));
+            module_item = child;
+            if target {
+                self.filter_matched = true;
+                break;
+            }
         }
-        composed.push_str(&output);
 
-        Ok(self.config.formatter.format_str(&composed)?)
+        // Only the items that can land on the page keep their rendered chunk: they are
+        // collected until the unformatted output passes the limits, plus the item that passes
+        // them. Items after that are only counted for the summary of what remains.
+        let mut entries = Vec::new();
+        let mut remaining = Vec::new();
+        let mut skipped = 0;
+        let mut chars = headers
+            .iter()
+            .map(|header| header.chars().count())
+            .sum::<usize>();
+        let mut collecting = true;
+        if self.selection_context_contains(&root_item.id) {
+            let module = try_extract_item!(module_item, ItemEnum::Module)?;
+            for item_id in &module.items {
+                let item = must_get(self.crate_data, item_id)?;
+                let chunk = self.render_item(&path_prefix, item, false)?;
+                if chunk.trim().is_empty() {
+                    continue;
+                }
+                if skipped < request.start {
+                    skipped += 1;
+                    continue;
+                }
+                let name = match &item.inner {
+                    ItemEnum::Use(use_) => use_.name.clone(),
+                    _ => render_name(item),
+                };
+                let page_item = PageItem {
+                    path: ppush(&path_prefix, &name),
+                    kind: SearchItemKind::of_item(item).unwrap_or(SearchItemKind::Use),
+                };
+                if collecting {
+                    chars += chunk.chars().count();
+                    entries.push((page_item, chunk));
+                    collecting = request.allows(entries.len(), chars);
+                } else {
+                    remaining.push(page_item);
+                }
+            }
+        }
+        if !self.config.filter.is_empty() && !self.filter_matched {
+            return Err(RuskelError::FilterNotMatched(self.config.filter.clone()));
+        }
+
+        // Each item takes over the trailing whitespace of the one before it on the page, which
+        // is what separates the two.
+        let mut separator = String::new();
+        let chunks: Vec<String> = entries
+            .iter()
+            .map(|(_, chunk)| {
+                let content = chunk.trim_end();
+                let item = format!("{separator}{content}");
+                separator = chunk[content.len()..].to_string();
+                item
+            })
+            .collect();
+        let formatted =
+            self.config
+                .formatter
+                .format_items(&headers.concat(), headers.len(), &chunks)?;
+
+        let mut rendered = self.frontmatter().unwrap_or_default();
+        rendered.push_str(&formatted.opening);
+        let mut chars = rendered.chars().count() + formatted.closing.chars().count();
+        let mut items = Vec::new();
+        let mut collected = entries.into_iter().zip(formatted.items).peekable();
+        while let Some((_, piece)) = collected.peek() {
+            let piece_chars = piece.chars().count();
+            if !items.is_empty() && !request.allows(items.len() + 1, chars + piece_chars) {
+                break;
+            }
+            if let Some(((item, _), piece)) = collected.next() {
+                items.push(item);
+                rendered.push_str(&piece);
+            }
+            chars += piece_chars;
+        }
+        rendered.push_str(&formatted.closing);
+
+        let mut left: Vec<PageItem> = collected.map(|((item, _), _)| item).collect();
+        left.append(&mut remaining);
+        Ok(Page {
+            rendered,
+            start: request.start,
+            items,
+            remaining: left,
+        })
+    }
+
+    /// The rendered child module of `module_item` that is, or leads to, the filter target.
+    fn filter_module_child(&self, path_prefix: &str, module_item: &Item) -> Result<Option<Id>> {
+        if self.config.filter.is_empty() {
+            return Ok(None);
+        }
+        let module = try_extract_item!(module_item, ItemEnum::Module)?;
+        for item_id in &module.items {
+            let item = must_get(self.crate_data, item_id)?;
+            if matches!(item.inner, ItemEnum::Module(_))
+                && matches!(
+                    self.filter_match(path_prefix, item),
+                    FilterMatch::Hit | FilterMatch::Prefix
+                )
+                && self.is_visible(item)
+                && self.selection_context_contains(&item.id)
+            {
+                return Ok(Some(item.id));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The configured frontmatter comment block, if any.
+    fn frontmatter(&self) -> Option<String> {
+        self.config.frontmatter.as_ref()?.render(
+            self.config.render_private_items,
+            self.config.render_auto_impls,
+            self.config.render_blanket_impls,
+        )
     }
 
     /// Return the active render selection, if any.
@@ -739,12 +1017,20 @@ impl RenderState<'_, '_> {
     }
 
     /// Determine whether an impl block should be rendered in the output.
-    fn should_render_impl(&self, impl_: &Impl) -> bool {
-        if impl_.is_synthetic && !self.config.render_auto_impls {
-            return false;
+    ///
+    /// Impls matched by the selection render even when they are synthetic or blanket impls.
+    /// Synthetic impls of traits listed in `DERIVE_TRAITS`, such as `Send`, are never shown as
+    /// derives, so they render as impl blocks when matched.
+    fn should_render_impl(&self, impl_id: &Id, impl_: &Impl) -> bool {
+        if DERIVE_TRAITS.contains(&impl_.trait_.as_ref().map_or("", |t| t.path.as_str())) {
+            return impl_.is_synthetic && self.selection_matches(impl_id);
         }
 
-        if DERIVE_TRAITS.contains(&impl_.trait_.as_ref().map_or("", |t| t.path.as_str())) {
+        if self.selection_matches(impl_id) {
+            return true;
+        }
+
+        if impl_.is_synthetic && !self.config.render_auto_impls {
             return false;
         }
 
@@ -829,9 +1115,12 @@ impl RenderState<'_, '_> {
             ItemEnum::Module(_) => self.render_module(path_prefix, item)?,
             ItemEnum::Struct(_) => self.render_struct(path_prefix, item)?,
             ItemEnum::Enum(_) => self.render_enum(path_prefix, item)?,
-            ItemEnum::Trait(_) => self.render_trait(item)?,
+            ItemEnum::Trait(_) => self.render_trait(path_prefix, item)?,
             ItemEnum::Use(_) => self.render_use(path_prefix, item)?,
-            ItemEnum::Function(_) => self.render_function(item, false)?,
+            ItemEnum::Function(_) => {
+                let body = self.source_body(path_prefix, item);
+                self.render_function(item, false, body.as_deref())?
+            }
             ItemEnum::Constant { .. } => self.render_constant(item)?,
             ItemEnum::TypeAlias(_) => self.render_type_alias(item)?,
             ItemEnum::Macro(_) => self.render_macro(item)?,
@@ -842,10 +1131,51 @@ impl RenderState<'_, '_> {
         if !force_private && !self.is_visible(item) {
             Ok(String::new())
         } else {
-            Ok(output)
+            Ok(self.annotate_location(item, output))
+        }
+    }
+
+    /// Prefix rendered output with the item's source location when annotations are enabled.
+    fn annotate_location(&mut self, item: &Item, output: String) -> String {
+        if !self.config.source_locations || output.is_empty() || item.id == self.crate_data.root {
+            return output;
+        }
+        match self.locator.locate(item) {
+            Some(location) => format!("// {location}\n{output}"),
+            None => output,
         }
     }
 
+    /// Should this function have its original body embedded?
+    fn is_source_target(&self, path_prefix: &str, item: &Item) -> bool {
+        if !self.config.item_source || !matches!(item.inner, ItemEnum::Function(_)) {
+            return false;
+        }
+        if self.selection_matches(&item.id) {
+            return true;
+        }
+        !self.config.filter.is_empty()
+            && matches!(
+                self.filter_match(path_prefix, item),
+                FilterMatch::Hit | FilterMatch::Suffix
+            )
+    }
+
+    /// The original body of a function, headed by a comment naming its location, when
+    /// requested and available.
+    fn source_body(&mut self, path_prefix: &str, item: &Item) -> Option<String> {
+        if !self.is_source_target(path_prefix, item) {
+            return None;
+        }
+        let source = self.locator.read_span(item)?;
+        let body = function_body(&source)?;
+        let location = self.locator.locate(item)?;
+        Some(format!(
+            "{{\n// ruskel: original source from {location}\n{}",
+            body[1..].trim_start_matches(['\r', '\n'])
+        ))
+    }
+
     /// Render a procedural macro definition.
     fn render_proc_macro(&self, item: &Item) -> Result<String> {
         let mut output = docs(item);
@@ -1005,7 +1335,9 @@ impl RenderState<'_, '_> {
         for impl_id in impl_ids {
             let impl_item = must_get(self.crate_data, impl_id)?;
             let impl_ = try_extract_item!(impl_item, ItemEnum::Impl)?;
-            if !self.should_render_impl(impl_) || !self.selection_allows_child(parent_id, impl_id) {
+            if !self.should_render_impl(impl_id, impl_)
+                || !self.selection_allows_child(parent_id, impl_id)
+            {
                 continue;
             }
 
@@ -1130,7 +1462,8 @@ impl RenderState<'_, '_> {
             }
         }
 
-        if !has_content {
+        // Matched impls of marker traits, such as auto trait impls, have no items to show.
+        if !has_content && !self.selection_matches(&item.id) {
             return Ok(None);
         }
 
@@ -1156,14 +1489,17 @@ impl RenderState<'_, '_> {
         }
 
         let rendered = match &item.inner {
-            ItemEnum::Function(_) => self.render_function(item, false)?,
+            ItemEnum::Function(_) => {
+                let body = self.source_body(path_prefix, item);
+                self.render_function(item, false, body.as_deref())?
+            }
             ItemEnum::Constant { .. } => self.render_constant(item)?,
             ItemEnum::AssocType { .. } => render_associated_type(item),
             ItemEnum::TypeAlias(_) => self.render_type_alias(item)?,
             _ => String::new(),
         };
 
-        Ok(rendered)
+        Ok(self.annotate_location(item, rendered))
     }
 
     /// Render an enum definition, including variants.
@@ -1277,7 +1613,7 @@ impl RenderState<'_, '_> {
     }
 
     /// Render a trait definition.
-    fn render_trait(&self, item: &Item) -> Result<String> {
+    fn render_trait(&mut self, path_prefix: &str, item: &Item) -> Result<String> {
         let mut output = docs(item);
 
         let trait_ = try_extract_item!(item, ItemEnum::Trait)?;
@@ -1298,10 +1634,11 @@ impl RenderState<'_, '_> {
             })?;
         output.push_str(&format!("{signature} {{\n"));
 
+        let path_prefix = ppush(path_prefix, &render_name(item));
         for item_id in &trait_.items {
             if !selection_active || expand_children || self.selection_context_contains(item_id) {
                 let item = must_get(self.crate_data, item_id)?;
-                output.push_str(&self.render_trait_item(item, expand_children)?);
+                output.push_str(&self.render_trait_item(&path_prefix, item, expand_children)?);
             }
         }
 
@@ -1311,12 +1648,20 @@ impl RenderState<'_, '_> {
     }
 
     /// Render an item contained within a trait (method, associated type, etc.).
-    fn render_trait_item(&self, item: &Item, include_all: bool) -> Result<String> {
+    fn render_trait_item(
+        &mut self,
+        path_prefix: &str,
+        item: &Item,
+        include_all: bool,
+    ) -> Result<String> {
         if !include_all && !self.selection_context_contains(&item.id) {
             return Ok(String::new());
         }
         let rendered = match &item.inner {
-            ItemEnum::Function(_) => self.render_function(item, true)?,
+            ItemEnum::Function(_) => {
+                let body = self.source_body(path_prefix, item);
+                self.render_function(item, true, body.as_deref())?
+            }
             ItemEnum::AssocConst { type_, value } => {
                 let default_str = value
                     .as_ref()
@@ -1485,16 +1830,7 @@ impl RenderState<'_, '_> {
     /// Render a module and its children.
     fn render_module(&mut self, path_prefix: &str, item: &Item) -> Result<String> {
         let path_prefix = ppush(path_prefix, &render_name(item));
-        let mut output = format!("{}mod {} {{\n", render_vis(item), render_name(item));
-        // Add module doc comment if present
-        if self.should_module_doc(&path_prefix, item)
-            && let Some(docs) = &item.docs
-        {
-            for line in docs.lines() {
-                output.push_str(&format!("    //! {line}\n"));
-            }
-            output.push('\n');
-        }
+        let mut output = self.render_module_header(&path_prefix, item);
 
         let module = try_extract_item!(item, ItemEnum::Module)?;
 
@@ -1507,8 +1843,28 @@ impl RenderState<'_, '_> {
         Ok(output)
     }
 
-    /// Render a function or method signature.
-    fn render_function(&self, item: &Item, is_trait_method: bool) -> Result<String> {
+    /// Render the opening line of a module along with its inner doc comments.
+    fn render_module_header(&self, path_prefix: &str, item: &Item) -> String {
+        let mut output = format!("{}mod {} {{\n", render_vis(item), render_name(item));
+        // Add module doc comment if present
+        if self.should_module_doc(path_prefix, item)
+            && let Some(docs) = &item.docs
+        {
+            for line in docs.lines() {
+                output.push_str(&format!("    //! {line}\n"));
+            }
+            output.push('\n');
+        }
+        output
+    }
+
+    /// Render a function or method signature, followed by `body` or an empty body.
+    fn render_function(
+        &self,
+        item: &Item,
+        is_trait_method: bool,
+        body: Option<&str>,
+    ) -> Result<String> {
         let mut output = docs(item);
         let function = try_extract_item!(item, ItemEnum::Function)?;
         let kind = if is_trait_method {
@@ -1529,7 +1885,9 @@ impl RenderState<'_, '_> {
         if is_trait_method && !function.has_body {
             output.push_str(";\n\n");
         } else {
-            output.push_str(" {}\n\n");
+            output.push(' ');
+            output.push_str(body.unwrap_or("{}"));
+            output.push_str("\n\n");
         }
 
         Ok(output)
@@ -1549,7 +1907,10 @@ mod tests {
     use super::*;
     use crate::{
         frontmatter::{FrontmatterConfig, FrontmatterHit, FrontmatterSearch},
-        search::{SearchDomain, SearchIndex, SearchOptions, SearchResult, build_render_selection},
+        search::{
+            SearchDomain, SearchIndex, SearchItemKind, SearchOptions, SearchResult,
+            build_render_selection,
+        },
     };
 
     fn empty_generics() -> Generics {
@@ -1610,6 +1971,7 @@ mod tests {
             crate_data: &crate_data,
             filter_matched: false,
             filter_components: Vec::new(),
+            locator: SourceLocator::default(),
         };
 
         let item = crate_data
@@ -2110,6 +2472,7 @@ path = "src/lib.rs"
                     } else {
                         renderer.filter.split("::").collect()
                     },
+                    locator: SourceLocator::default(),
                 };
                 let mut composed = String::new();
                 if let Some(frontmatter) = &renderer.frontmatter
@@ -2143,6 +2506,7 @@ path = "src/lib.rs"
                     } else {
                         renderer.filter.split("::").collect()
                     },
+                    locator: SourceLocator::default(),
                 };
                 let root = super::must_get(crate_data, &crate_data.root)?;
                 state.render_item("", root, false)
@@ -2164,12 +2528,13 @@ path = "src/lib.rs"
     #[test]
     fn selection_renders_only_matching_struct_field() -> Result<()> {
         let crate_data = fixture_crate();
-        let index = SearchIndex::build(&crate_data, false);
+        let index = SearchIndex::build(&crate_data, false, None);
         let mut options = SearchOptions::new("Widget::id");
         options.domains = SearchDomain::PATHS;
-        let results = index.search(&options);
+        let results = index.search(&options)?;
         let field = find_result_by_suffix(results, "Widget::id")?;
-        let selection = build_render_selection(&index, slice::from_ref(&field), true);
+        let selection =
+            build_render_selection(&index, slice::from_ref(&field), true, &HashSet::new());
         let rendered = render_with_selection(&crate_data, selection)?;
 
         assert!(rendered.contains("struct Widget"));
@@ -2183,12 +2548,13 @@ path = "src/lib.rs"
     #[test]
     fn selection_renders_only_matching_impl_method() -> Result<()> {
         let crate_data = fixture_crate();
-        let index = SearchIndex::build(&crate_data, false);
+        let index = SearchIndex::build(&crate_data, false, None);
         let mut options = SearchOptions::new("render");
         options.domains = SearchDomain::NAMES;
-        let results = index.search(&options);
+        let results = index.search(&options)?;
         let method = find_result_by_suffix(results, "Widget::render")?;
-        let selection = build_render_selection(&index, slice::from_ref(&method), true);
+        let selection =
+            build_render_selection(&index, slice::from_ref(&method), true, &HashSet::new());
         let rendered = render_with_selection(&crate_data, selection)?;
 
         assert!(rendered.contains("impl"));
@@ -2201,12 +2567,13 @@ path = "src/lib.rs"
     #[test]
     fn selection_renders_only_matching_enum_variant() -> Result<()> {
         let crate_data = fixture_crate();
-        let index = SearchIndex::build(&crate_data, false);
+        let index = SearchIndex::build(&crate_data, false, None);
         let mut options = SearchOptions::new("Named");
         options.domains = SearchDomain::NAMES;
-        let results = index.search(&options);
+        let results = index.search(&options)?;
         let variant = find_result_by_suffix(results, "Palette::Named")?;
-        let selection = build_render_selection(&index, slice::from_ref(&variant), true);
+        let selection =
+            build_render_selection(&index, slice::from_ref(&variant), true, &HashSet::new());
         let rendered = render_with_selection(&crate_data, selection)?;
 
         assert!(rendered.contains("enum Palette"));
@@ -2220,12 +2587,13 @@ path = "src/lib.rs"
     #[test]
     fn struct_match_expands_children_by_default() -> Result<()> {
         let crate_data = fixture_crate();
-        let index = SearchIndex::build(&crate_data, false);
+        let index = SearchIndex::build(&crate_data, false, None);
         let mut options = SearchOptions::new("Widget");
         options.domains = SearchDomain::NAMES;
-        let results = index.search(&options);
+        let results = index.search(&options)?;
         let widget = find_result_by_suffix(results, "Widget")?;
-        let selection = build_render_selection(&index, slice::from_ref(&widget), true);
+        let selection =
+            build_render_selection(&index, slice::from_ref(&widget), true, &HashSet::new());
         let rendered = render_with_selection(&crate_data, selection)?;
 
         assert!(rendered.contains("struct Widget"));
@@ -2239,12 +2607,13 @@ path = "src/lib.rs"
     #[test]
     fn struct_match_respects_direct_match_only() -> Result<()> {
         let crate_data = fixture_crate();
-        let index = SearchIndex::build(&crate_data, false);
+        let index = SearchIndex::build(&crate_data, false, None);
         let mut options = SearchOptions::new("Widget");
         options.domains = SearchDomain::NAMES;
-        let results = index.search(&options);
+        let results = index.search(&options)?;
         let widget = find_result_by_suffix(results, "Widget")?;
-        let selection = build_render_selection(&index, slice::from_ref(&widget), false);
+        let selection =
+            build_render_selection(&index, slice::from_ref(&widget), false, &HashSet::new());
         let rendered = render_with_selection(&crate_data, selection)?;
 
         assert!(rendered.contains("struct Widget"));
@@ -2255,15 +2624,36 @@ path = "src/lib.rs"
         Ok(())
     }
 
+    #[test]
+    fn struct_match_expands_only_selected_kinds() -> Result<()> {
+        let crate_data = fixture_crate();
+        let index = SearchIndex::build(&crate_data, false, None);
+        let mut options = SearchOptions::new("Widget");
+        options.domains = SearchDomain::NAMES;
+        let results = index.search(&options)?;
+        let widget = find_result_by_suffix(results, "Widget")?;
+        let kinds = HashSet::from([SearchItemKind::Struct, SearchItemKind::Method]);
+        let selection = build_render_selection(&index, slice::from_ref(&widget), true, &kinds);
+        let rendered = render_with_selection(&crate_data, selection)?;
+
+        assert!(rendered.contains("struct Widget"));
+        assert!(rendered.contains("fn render"));
+        assert!(!rendered.contains("id: u32"));
+        assert!(!rendered.contains("name: String"));
+
+        Ok(())
+    }
+
     #[test]
     fn module_match_expands_children_by_default() -> Result<()> {
         let crate_data = fixture_crate();
-        let index = SearchIndex::build(&crate_data, false);
+        let index = SearchIndex::build(&crate_data, false, None);
         let mut options = SearchOptions::new("tools");
         options.domains = SearchDomain::NAMES;
-        let results = index.search(&options);
+        let results = index.search(&options)?;
         let module = find_result_by_suffix(results, "tools")?;
-        let selection = build_render_selection(&index, slice::from_ref(&module), true);
+        let selection =
+            build_render_selection(&index, slice::from_ref(&module), true, &HashSet::new());
         let rendered = render_with_selection(&crate_data, selection)?;
 
         assert!(rendered.contains("mod tools"));
@@ -2275,12 +2665,13 @@ path = "src/lib.rs"
     #[test]
     fn module_match_respects_direct_match_only() -> Result<()> {
         let crate_data = fixture_crate();
-        let index = SearchIndex::build(&crate_data, false);
+        let index = SearchIndex::build(&crate_data, false, None);
         let mut options = SearchOptions::new("tools");
         options.domains = SearchDomain::NAMES;
-        let results = index.search(&options);
+        let results = index.search(&options)?;
         let module = find_result_by_suffix(results, "tools")?;
-        let selection = build_render_selection(&index, slice::from_ref(&module), false);
+        let selection =
+            build_render_selection(&index, slice::from_ref(&module), false, &HashSet::new());
         let rendered = render_with_selection(&crate_data, selection)?;
 
         assert!(rendered.contains("mod tools"));
@@ -2339,6 +2730,40 @@ path = "src/lib.rs"
         Ok(())
     }
 
+    #[test]
+    fn render_to_streams_frontmatter_then_items() -> Result<()> {
+        let crate_data = fixture_crate();
+        let renderer = Renderer::new()
+            .with_format_backend(FormatBackend::Unformatted)
+            .with_frontmatter(FrontmatterConfig::for_target("fixture"));
+        let mut output = Vec::new();
+        renderer.render_to(&crate_data, &mut output)?;
+        let output = String::from_utf8(output).expect("rendered output is UTF-8");
+
+        assert!(output.starts_with("// Ruskel skeleton"));
+        let module = output.find("mod fixture {").expect("module header");
+        let widget = output.find("pub struct Widget").expect("struct");
+        assert!(module < widget);
+        assert_eq!(output.trim_end(), renderer.render(&crate_data)?.trim_end());
+
+        Ok(())
+    }
+
+    #[test]
+    fn render_to_writes_nothing_when_filter_misses() {
+        let crate_data = fixture_crate();
+        let renderer = Renderer::new()
+            .with_format_backend(FormatBackend::Unformatted)
+            .with_filter("missing");
+        let mut output = Vec::new();
+        let err = renderer
+            .render_to(&crate_data, &mut output)
+            .expect_err("filter should not match");
+
+        assert!(matches!(err, RuskelError::FilterNotMatched(_)));
+        assert!(output.is_empty());
+    }
+
     #[test]
     fn frontmatter_can_be_disabled() -> Result<()> {
         let crate_data = fixture_crate();
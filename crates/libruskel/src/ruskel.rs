@@ -1,14 +1,26 @@
-use rustdoc_types::Crate;
+use std::{collections::HashSet, io::Write, path::PathBuf, sync::Arc};
+
+use rustdoc_types::{Crate, Id};
 
 use super::{
+    cache::{CacheKey, CrateCache, CrateLoad},
     cargoutils::*,
+    closure::{build_closure_selection, collect_closure},
     error::*,
+    format::{FormatBackend, SkeletonFormatter},
     frontmatter::{FrontmatterBinaryTarget, FrontmatterConfig, FrontmatterHit, FrontmatterSearch},
+    impls::{ImplQuery, ImplResponse, build_impl_selection},
+    item::{ItemResponse, describe_item},
+    location::SourceLocator,
+    methods::{MethodsResponse, collect_methods},
+    page::{Page, PageItem, PageRequest},
+    progress::{LoadStage, Progress, ProgressHook},
     render::*,
     search::{
-        ListItem, SearchIndex, SearchItemKind, SearchOptions, SearchResponse,
-        build_render_selection,
+        ListItem, ListOptions, SearchIndex, SearchItemKind, SearchOptions, SearchResponse,
+        SearchResult, build_render_selection,
     },
+    source::{CargoSource, CrateSource},
 };
 
 /// Ruskel generates a skeletonized version of a Rust crate in a single page.
@@ -34,6 +46,31 @@ pub struct Ruskel {
 
     /// Optional binary target override for bin-only crates or bin rendering.
     bin_target: Option<String>,
+
+    /// Directory that relative paths and workspace names resolve against, instead of the
+    /// current directory.
+    root: Option<PathBuf>,
+
+    /// Backend used to format rendered skeletons.
+    format_backend: FormatBackend,
+
+    /// Whether formatting failures fall back to unformatted output with a warning.
+    best_effort_format: bool,
+
+    /// Whether rendered items are annotated with their source locations.
+    source_locations: bool,
+
+    /// Whether matched or targeted functions embed their original source body.
+    item_source: bool,
+
+    /// Source that turns resolved targets into rustdoc data.
+    source: Arc<dyn CrateSource>,
+
+    /// Cache of loaded crates shared by clones of this instance, if enabled.
+    cache: Option<Arc<CrateCache>>,
+
+    /// Hook notified as crates load and polled for cancellation.
+    progress: Progress,
 }
 
 /// Drop `use` matches when more specific items are present.
@@ -47,18 +84,36 @@ fn prune_redundant_use_items(results: &mut Vec<ListItem>) {
     }
 }
 
+/// Items whose path within the crate equals the target filter, such as `Item` or `module::Item`.
+fn filter_roots(index: &SearchIndex, filter: &str) -> Vec<Id> {
+    let all_kinds = HashSet::new();
+    index
+        .entries_of_kind(&all_kinds)
+        .filter(|entry| {
+            entry
+                .path_string
+                .split_once("::")
+                .is_some_and(|(_, path)| path == filter)
+        })
+        .map(|entry| entry.item_id)
+        .collect()
+}
+
 /// Crate data loaded for a resolved target together with render metadata.
 struct LoadedTarget {
-    /// Resolved package location and intra-crate filter path.
-    resolved_target: ResolvedTarget,
-    /// Parsed rustdoc JSON for the selected target.
-    crate_data: Crate,
-    /// Binary target metadata for bin rendering and frontmatter output.
-    bin_target: Option<BinaryTarget>,
+    /// Resolved target, rustdoc data and lazily built search indexes.
+    load: Arc<CrateLoad>,
     /// Effective private-item visibility after accounting for bin-only targets.
     render_private_items: bool,
 }
 
+impl LoadedTarget {
+    /// The search index matching the effective render visibility.
+    fn index(&self) -> &SearchIndex {
+        self.load.index(self.render_private_items)
+    }
+}
+
 /// Visibility policy used while generating rustdoc JSON and rendering output.
 #[derive(Debug, Clone, Copy)]
 struct VisibilityPolicy {
@@ -134,6 +189,14 @@ impl Ruskel {
             silent: false,
             frontmatter: true,
             bin_target: None,
+            root: None,
+            format_backend: FormatBackend::default(),
+            best_effort_format: false,
+            source_locations: false,
+            item_source: false,
+            source: Arc::new(CargoSource::default()),
+            cache: None,
+            progress: Progress::default(),
         }
     }
 
@@ -168,6 +231,90 @@ impl Ruskel {
         self
     }
 
+    /// Resolves relative target paths and workspace crate names against `root` rather than the
+    /// current directory. `None` restores the current directory.
+    pub fn with_root(mut self, root: Option<PathBuf>) -> Self {
+        self.root = root;
+        self
+    }
+
+    /// Selects the backend used to format rendered skeletons.
+    pub fn with_format_backend(mut self, format_backend: FormatBackend) -> Self {
+        self.format_backend = format_backend;
+        self
+    }
+
+    /// Enables or disables best-effort formatting, which returns the unformatted skeleton with a
+    /// warning comment instead of failing when formatting fails.
+    pub fn with_best_effort_format(mut self, best_effort_format: bool) -> Self {
+        self.best_effort_format = best_effort_format;
+        self
+    }
+
+    /// Enables or disables `// file:line` comments on rendered items, pointing at each item's
+    /// definition relative to the package root.
+    pub fn with_source_locations(mut self, source_locations: bool) -> Self {
+        self.source_locations = source_locations;
+        self
+    }
+
+    /// Enables or disables embedding the original source body of matched or targeted functions.
+    ///
+    /// Bodies are read from the package sources, including registry sources for crates.io
+    /// dependencies, and are introduced by a `// ruskel: original source from file:line` marker.
+    pub fn with_item_source(mut self, item_source: bool) -> Self {
+        self.item_source = item_source;
+        self
+    }
+
+    /// Reads crates from `source` instead of building them with `cargo rustdoc`.
+    ///
+    /// Clones share the crate cache, so set the source before [`Self::with_crate_cache`].
+    pub fn with_source(mut self, source: Arc<dyn CrateSource>) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Keeps up to `capacity` loaded crates, with their search indexes, in a cache shared by
+    /// clones of this instance.
+    ///
+    /// Later requests with the same target, features and privacy reuse the loaded crate instead of
+    /// regenerating rustdoc JSON, and concurrent identical requests wait for a single load. Use
+    /// [`Self::invalidate`] after local sources change.
+    pub fn with_crate_cache(mut self, capacity: usize) -> Self {
+        self.cache = Some(Arc::new(CrateCache::new(capacity)));
+        self
+    }
+
+    /// Reports the stages of loading and rendering to `hook`, which can also cancel the work.
+    ///
+    /// Cancelling kills a running `cargo rustdoc` build and fails the request with
+    /// [`RuskelError::Cancelled`]. Crates served from the cache skip straight to rendering.
+    pub fn with_progress(mut self, hook: Arc<dyn ProgressHook>) -> Self {
+        self.progress = Progress::new(hook);
+        self
+    }
+
+    /// Drops cached crates so the next request regenerates them, returning how many were dropped.
+    ///
+    /// With a target, only crates loaded for that target, or from the same package directory, are
+    /// dropped; otherwise the whole cache is cleared. Does nothing when no cache is enabled.
+    pub fn invalidate(&self, target: Option<&str>) -> Result<usize> {
+        let Some(cache) = &self.cache else {
+            return Ok(0);
+        };
+        let Some(target) = target else {
+            return Ok(cache.invalidate(|_, _| true));
+        };
+        let resolved = resolve_target(target, self.root.as_deref(), self.offline)?;
+        let package_root = resolved.package_root();
+        Ok(cache.invalidate(|key, load| {
+            key.target == target
+                || package_root
+                    .is_some_and(|root| load.resolved_target.package_root() == Some(root))
+        }))
+    }
+
     /// Returns the parsed representation of the crate's API.
     ///
     /// # Arguments
@@ -192,7 +339,9 @@ impl Ruskel {
                 features,
                 VisibilityPolicy::mirrored(private_items),
             )?
-            .crate_data)
+            .load
+            .crate_data
+            .clone())
     }
 
     /// Execute a search against the crate and return the matched items along with a rendered skeleton.
@@ -214,18 +363,83 @@ impl Ruskel {
             features,
             VisibilityPolicy::mirrored(options.include_private),
         )?;
-        let index = SearchIndex::build(&loaded.crate_data, loaded.render_private_items);
-        let results = index.search(options);
+        let results = loaded.index().search(options)?;
+        let rendered = self.render_matches(&loaded, target, options, &results)?;
+
+        Ok(SearchResponse { results, rendered })
+    }
 
+    /// Execute a search and return one page of its matches, with a skeleton restricted to them.
+    ///
+    /// Pages hold up to `page.max_items` matches, fewer when their skeleton would exceed
+    /// `page.max_chars`, and at least one match unless the search found nothing.
+    pub fn search_page(
+        &self,
+        target: &str,
+        no_default_features: bool,
+        all_features: bool,
+        features: Vec<String>,
+        options: &SearchOptions,
+        page: &PageRequest,
+    ) -> Result<Page<SearchResult>> {
+        let loaded = self.load_target(
+            target,
+            no_default_features,
+            all_features,
+            features,
+            VisibilityPolicy::mirrored(options.include_private),
+        )?;
+        let mut results = loaded.index().search(options)?;
+        let mut items = results.split_off(page.start.min(results.len()));
+        let mut taken = items.len().min(page.max_items.unwrap_or(usize::MAX).max(1));
+        let mut rendered = self.render_matches(&loaded, target, options, &items[..taken])?;
+        // Shrink the page in proportion to its overflow until the skeleton fits.
+        loop {
+            let chars = rendered.chars().count();
+            if taken <= 1 || page.allows(taken, chars) {
+                break;
+            }
+            let max_chars = page.max_chars.unwrap_or(chars);
+            taken = (taken * max_chars / chars).clamp(1, taken - 1);
+            rendered = self.render_matches(&loaded, target, options, &items[..taken])?;
+        }
+        let remaining = items
+            .split_off(taken)
+            .into_iter()
+            .map(|result| PageItem {
+                path: result.path_string,
+                kind: result.kind,
+            })
+            .collect();
+
+        Ok(Page {
+            rendered,
+            start: page.start,
+            items,
+            remaining,
+        })
+    }
+
+    /// Render the skeleton restricted to the given search matches, or nothing when there are
+    /// none.
+    fn render_matches(
+        &self,
+        loaded: &LoadedTarget,
+        target: &str,
+        options: &SearchOptions,
+        results: &[SearchResult],
+    ) -> Result<String> {
         if results.is_empty() {
-            return Ok(SearchResponse {
-                results,
-                rendered: String::new(),
-            });
+            return Ok(String::new());
         }
 
-        let selection = build_render_selection(&index, &results, options.expand_containers);
-        let mut renderer = self.base_renderer(&loaded).with_selection(selection);
+        let selection = build_render_selection(
+            loaded.index(),
+            results,
+            options.expand_containers,
+            &options.kinds,
+        );
+        let mut renderer = self.base_renderer(loaded).with_selection(selection);
         if self.frontmatter {
             let hits = results
                 .iter()
@@ -238,25 +452,26 @@ impl Ruskel {
                 options.expand_containers,
                 hits,
             );
-            renderer = self.attach_frontmatter(renderer, &loaded, target, Some(search_meta));
+            renderer = self.attach_frontmatter(renderer, loaded, target, Some(search_meta));
         }
-        let rendered = renderer.render(&loaded.crate_data)?;
-
-        Ok(SearchResponse { results, rendered })
+        renderer.render(&loaded.load.crate_data)
     }
 
     /// Produce a lightweight listing of crate items, optionally filtered by a search query.
+    ///
+    /// Search listings are ordered by relevance and carry each match's score.
     pub fn list(
         &self,
         target: &str,
         no_default_features: bool,
         all_features: bool,
         features: Vec<String>,
-        include_private: bool,
-        search: Option<&SearchOptions>,
+        options: &ListOptions,
     ) -> Result<Vec<ListItem>> {
+        let search = options.search.as_ref();
+        let kinds = &options.kinds;
         let include_private =
-            include_private || search.is_some_and(|options| options.include_private);
+            options.include_private || search.is_some_and(|search| search.include_private);
         let loaded = self.load_target(
             target,
             no_default_features,
@@ -264,25 +479,32 @@ impl Ruskel {
             features,
             VisibilityPolicy::mirrored(include_private),
         )?;
-        let index = SearchIndex::build(&loaded.crate_data, loaded.render_private_items);
+        let index = loaded.index();
 
         let mut results: Vec<ListItem> = if let Some(options) = search {
+            let mut options = options.clone();
+            options.kinds.extend(kinds.iter().copied());
             index
-                .search(options)
+                .search(&options)?
                 .into_iter()
                 .map(|result| ListItem {
                     kind: result.kind,
                     path: result.path_string,
+                    location: result.location,
+                    highlights: result.highlights,
+                    score: Some(result.score),
                 })
                 .collect()
         } else {
             index
-                .entries()
-                .iter()
+                .entries_of_kind(kinds)
                 .cloned()
                 .map(|entry| ListItem {
                     kind: entry.kind,
                     path: entry.path_string,
+                    location: entry.location,
+                    highlights: Vec::new(),
+                    score: None,
                 })
                 .collect()
         };
@@ -292,6 +514,170 @@ impl Ruskel {
         Ok(results)
     }
 
+    /// Find the implementors of a trait, or the traits implemented by a type, as selected by
+    /// `query`.
+    ///
+    /// Unlike rendered skeletons, the results include blanket impls and synthesized auto trait
+    /// impls. The response also carries a skeleton restricted to the matching impl blocks and the
+    /// types they implement, which is empty when none of the impls is for a type in the crate.
+    pub fn trait_impls(
+        &self,
+        target: &str,
+        no_default_features: bool,
+        all_features: bool,
+        features: Vec<String>,
+        include_private: bool,
+        query: ImplQuery<'_>,
+    ) -> Result<ImplResponse> {
+        let loaded = self.load_target(
+            target,
+            no_default_features,
+            all_features,
+            features,
+            VisibilityPolicy::mirrored(include_private),
+        )?;
+        let package_root = loaded.load.resolved_target.package_root();
+        let index = loaded.index();
+        let mut locator = SourceLocator::new(package_root);
+        let impls = query.find(&loaded.load.crate_data, index, &mut locator)?;
+
+        let Some(selection) = build_impl_selection(&loaded.load.crate_data, index, &impls) else {
+            return Ok(ImplResponse {
+                impls,
+                rendered: String::new(),
+            });
+        };
+        let mut renderer = self.base_renderer(&loaded).with_selection(selection);
+        if self.frontmatter {
+            renderer = self.attach_frontmatter(renderer, &loaded, target, None);
+        }
+        let rendered = renderer.render(&loaded.load.crate_data)?;
+
+        Ok(ImplResponse { impls, rendered })
+    }
+
+    /// Collect the methods callable on the type at `type_path`: inherent methods, methods of
+    /// explicitly implemented traits, and methods reached through `Deref` targets.
+    ///
+    /// The response renders the methods as one impl block, each preceded by a comment such as
+    /// `// via Deref<Target = str>` naming where it comes from.
+    pub fn methods(
+        &self,
+        target: &str,
+        no_default_features: bool,
+        all_features: bool,
+        features: Vec<String>,
+        include_private: bool,
+        type_path: &str,
+    ) -> Result<MethodsResponse> {
+        let loaded = self.load_target(
+            target,
+            no_default_features,
+            all_features,
+            features,
+            VisibilityPolicy::mirrored(include_private),
+        )?;
+        let index = loaded.index();
+        let mut response = collect_methods(&loaded.load.crate_data, index, type_path)?;
+        if !response.rendered.is_empty() {
+            let mut formatter = SkeletonFormatter::new(self.format_backend);
+            formatter.set_best_effort(self.best_effort_format);
+            response.rendered = formatter.format(&response.rendered)?;
+        }
+        Ok(response)
+    }
+
+    /// Render the item named by a path target together with the transitive closure of the types
+    /// it mentions within the same crate.
+    ///
+    /// Types are followed through signatures, fields, variants, bounds and associated types, up
+    /// to `max_depth` hops from the item when a limit is given. Each type in the closure is
+    /// rendered with its fields, variants and impls.
+    pub fn render_closure(
+        &self,
+        target: &str,
+        no_default_features: bool,
+        all_features: bool,
+        features: Vec<String>,
+        private_items: bool,
+        max_depth: Option<usize>,
+    ) -> Result<String> {
+        let loaded = self.load_target(
+            target,
+            no_default_features,
+            all_features,
+            features,
+            VisibilityPolicy::mirrored(private_items),
+        )?;
+        let filter = loaded.load.resolved_target.filter.clone();
+        if filter.is_empty() {
+            return Err(RuskelError::InvalidTarget(format!(
+                "'{target}' does not name an item; closures need a path target such as crate::Item"
+            )));
+        }
+        let index = loaded.index();
+        let roots = filter_roots(index, &filter);
+        if roots.is_empty() {
+            return Err(RuskelError::FilterNotMatched(filter));
+        }
+
+        let closure = collect_closure(&loaded.load.crate_data, index, &roots, max_depth);
+        let selection = build_closure_selection(index, &closure);
+        let mut renderer = self
+            .base_renderer(&loaded)
+            .with_filter("")
+            .with_selection(selection);
+        if self.frontmatter {
+            renderer = self.attach_frontmatter(renderer, &loaded, target, None);
+        }
+        renderer.render(&loaded.load.crate_data)
+    }
+
+    /// Describe the single item named by a path target: its signature, documentation, trait
+    /// implementations and rendered skeleton.
+    ///
+    /// Types list the traits they implement and traits list their implementors, including blanket
+    /// and auto trait impls.
+    pub fn item(
+        &self,
+        target: &str,
+        no_default_features: bool,
+        all_features: bool,
+        features: Vec<String>,
+        private_items: bool,
+    ) -> Result<ItemResponse> {
+        let loaded = self.load_target(
+            target,
+            no_default_features,
+            all_features,
+            features,
+            VisibilityPolicy::mirrored(private_items),
+        )?;
+        let filter = loaded.load.resolved_target.filter.clone();
+        if filter.is_empty() {
+            return Err(RuskelError::InvalidTarget(format!(
+                "'{target}' does not name an item; pass a path target such as crate::Item"
+            )));
+        }
+        let package_root = loaded.load.resolved_target.package_root();
+        let index = loaded.index();
+        let Some(entry) = filter_roots(index, &filter)
+            .first()
+            .and_then(|id| index.entry(id))
+        else {
+            return Err(RuskelError::FilterNotMatched(filter));
+        };
+
+        let mut locator = SourceLocator::new(package_root);
+        let mut response = describe_item(&loaded.load.crate_data, index, entry, &mut locator);
+        let mut renderer = self.base_renderer(&loaded);
+        if self.frontmatter {
+            renderer = self.attach_frontmatter(renderer, &loaded, target, None);
+        }
+        response.rendered = renderer.render(&loaded.load.crate_data)?;
+        Ok(response)
+    }
+
     /// Render the crate target into a Rust skeleton without filtering.
     pub fn render(
         &self,
@@ -301,6 +687,69 @@ impl Ruskel {
         features: Vec<String>,
         private_items: bool,
     ) -> Result<String> {
+        let (loaded, renderer) = self.prepare_render(
+            target,
+            no_default_features,
+            all_features,
+            features,
+            private_items,
+        )?;
+        let rendered = renderer.render(&loaded.load.crate_data)?;
+
+        Ok(rendered)
+    }
+
+    /// Render one page of the crate target's skeleton, split between the top-level items of the
+    /// target module. See [`Renderer::render_page`].
+    pub fn render_page(
+        &self,
+        target: &str,
+        no_default_features: bool,
+        all_features: bool,
+        features: Vec<String>,
+        private_items: bool,
+        page: &PageRequest,
+    ) -> Result<Page<PageItem>> {
+        let (loaded, renderer) = self.prepare_render(
+            target,
+            no_default_features,
+            all_features,
+            features,
+            private_items,
+        )?;
+        renderer.render_page(&loaded.load.crate_data, page)
+    }
+
+    /// Render the crate target into `out`, writing each top-level item as soon as it has been
+    /// formatted. See [`Renderer::render_to`].
+    pub fn render_to<W: Write + ?Sized>(
+        &self,
+        target: &str,
+        no_default_features: bool,
+        all_features: bool,
+        features: Vec<String>,
+        private_items: bool,
+        out: &mut W,
+    ) -> Result<()> {
+        let (loaded, renderer) = self.prepare_render(
+            target,
+            no_default_features,
+            all_features,
+            features,
+            private_items,
+        )?;
+        renderer.render_to(&loaded.load.crate_data, out)
+    }
+
+    /// Load the target and configure a renderer for an unfiltered skeleton.
+    fn prepare_render(
+        &self,
+        target: &str,
+        no_default_features: bool,
+        all_features: bool,
+        features: Vec<String>,
+        private_items: bool,
+    ) -> Result<(LoadedTarget, Renderer)> {
         let loaded = self.load_target(
             target,
             no_default_features,
@@ -316,10 +765,55 @@ impl Ruskel {
         if self.frontmatter {
             renderer = self.attach_frontmatter(renderer, &loaded, target, None);
         }
+        Ok((loaded, renderer))
+    }
 
-        let rendered = renderer.render(&loaded.crate_data)?;
+    /// List the crates of the project containing the root directory, or the current directory
+    /// without one: its workspace members, then the packages they depend on directly. Each name
+    /// is a valid target.
+    pub fn project_crates(&self) -> Result<Vec<ProjectCrate>> {
+        project_crates(self.root.as_deref(), self.offline)
+    }
 
-        Ok(rendered)
+    /// List the same crates as [`Self::project_crates`] with their resolved features, source,
+    /// whether their docs are already built, and a target string that loads each one.
+    ///
+    /// Loads the manifest of every dependency, downloading any cargo has not cached yet.
+    pub fn project_packages(&self) -> Result<Vec<PackageInfo>> {
+        project_packages(self.root.as_deref(), self.offline)
+    }
+
+    /// Name, version and enabled features of the crate that `target` loads.
+    ///
+    /// With a crate cache this reuses a crate already loaded for the target and features,
+    /// whether or not it documented private items, so it is cheap after any other call for the
+    /// same target. Otherwise the crate is loaded as [`Self::render`] would load it.
+    pub fn crate_info(
+        &self,
+        target: &str,
+        no_default_features: bool,
+        all_features: bool,
+        features: Vec<String>,
+    ) -> Result<CrateInfo> {
+        if let Some(cache) = &self.cache {
+            let options =
+                self.read_options(no_default_features, all_features, features.clone(), false);
+            let key = CacheKey::new(target, self.root.as_deref(), &options);
+            if let Some(load) = [false, true]
+                .into_iter()
+                .find_map(|private_items| cache.peek(&key.with_private_items(private_items)))
+            {
+                return Ok(load.info());
+            }
+        }
+        let loaded = self.load_target(
+            target,
+            no_default_features,
+            all_features,
+            features,
+            VisibilityPolicy::render_public(),
+        )?;
+        Ok(loaded.load.info())
     }
 
     /// Returns a pretty-printed version of the crate's JSON representation.
@@ -347,6 +841,28 @@ impl Ruskel {
         )?)?)
     }
 
+    /// Options for reading a crate with this instance's settings.
+    ///
+    /// Each call starts a fresh [`Progress`], so every load reports its stages from the start.
+    fn read_options(
+        &self,
+        no_default_features: bool,
+        all_features: bool,
+        features: Vec<String>,
+        private_items: bool,
+    ) -> CrateReadOptions {
+        CrateReadOptions {
+            no_default_features,
+            all_features,
+            features,
+            private_items,
+            silent: self.silent,
+            offline: self.offline,
+            bin_override: self.bin_target.clone(),
+            progress: self.progress.restart(),
+        }
+    }
+
     /// Load crate data and normalize the privacy policy derived from the selected target.
     fn load_target(
         &self,
@@ -356,36 +872,59 @@ impl Ruskel {
         features: Vec<String>,
         visibility: VisibilityPolicy,
     ) -> Result<LoadedTarget> {
-        let resolved_target = resolve_target(target, self.offline)?;
-        let read_options = CrateReadOptions {
+        let read_options = self.read_options(
             no_default_features,
             all_features,
             features,
-            private_items: visibility.document_private_items,
-            silent: self.silent,
-            offline: self.offline,
-            bin_override: self.bin_target.clone(),
+            visibility.document_private_items,
+        );
+        let read = || {
+            read_options.progress.stage(LoadStage::Resolving);
+            let resolved_target = resolve_target(target, self.root.as_deref(), self.offline)?;
+            read_options.progress.check()?;
+            let CrateRead {
+                crate_data,
+                bin_target,
+                features,
+            } = self.source.read_crate(&resolved_target, &read_options)?;
+            Ok(CrateLoad::new(
+                resolved_target,
+                crate_data,
+                bin_target,
+                features,
+            ))
         };
-        let CrateRead {
-            crate_data,
-            bin_target,
-        } = resolved_target.read_crate(&read_options)?;
-        let render_private_items = visibility.effective_render_private(bin_target.as_ref());
+        let load = match &self.cache {
+            Some(cache) => cache.get_or_load(
+                &CacheKey::new(target, self.root.as_deref(), &read_options),
+                read,
+            )?,
+            None => Arc::new(read()?),
+        };
+        let render_private_items = visibility.effective_render_private(load.bin_target.as_ref());
+        read_options.progress.check()?;
+        read_options.progress.stage(LoadStage::Rendering);
 
         Ok(LoadedTarget {
-            resolved_target,
-            crate_data,
-            bin_target,
+            load,
             render_private_items,
         })
     }
 
     /// Create the renderer preconfigured with target filtering and visibility policy.
     fn base_renderer(&self, loaded: &LoadedTarget) -> Renderer {
-        Renderer::default()
-            .with_filter(&loaded.resolved_target.filter)
+        let renderer = Renderer::default()
+            .with_filter(&loaded.load.resolved_target.filter)
             .with_auto_impls(self.auto_impls)
             .with_private_items(loaded.render_private_items)
+            .with_format_backend(self.format_backend)
+            .with_best_effort_format(self.best_effort_format)
+            .with_source_locations(self.source_locations)
+            .with_item_source(self.item_source);
+        match loaded.load.resolved_target.package_root() {
+            Some(root) => renderer.with_package_root(root),
+            None => renderer,
+        }
     }
 
     /// Attach frontmatter metadata to a renderer when enabled.
@@ -396,13 +935,13 @@ impl Ruskel {
         target: &str,
         search: Option<FrontmatterSearch>,
     ) -> Renderer {
-        let filter = (!loaded.resolved_target.filter.is_empty())
-            .then(|| loaded.resolved_target.filter.clone());
+        let filter = (!loaded.load.resolved_target.filter.is_empty())
+            .then(|| loaded.load.resolved_target.filter.clone());
         let mut frontmatter = FrontmatterConfig::for_target(target.to_string()).with_filter(filter);
         if let Some(search) = search {
             frontmatter = frontmatter.with_search(search);
         }
-        if let Some(bin_target) = &loaded.bin_target {
+        if let Some(bin_target) = &loaded.load.bin_target {
             frontmatter = frontmatter.with_binary_target(FrontmatterBinaryTarget::new(
                 bin_target.name.clone(),
                 bin_target.is_bin_only,
@@ -420,6 +959,9 @@ mod tests {
         ListItem {
             kind,
             path: path.to_string(),
+            location: None,
+            highlights: Vec::new(),
+            score: None,
         }
     }
 
@@ -1,15 +1,27 @@
 //! Internal search index implementation.
 #![allow(clippy::missing_docs_in_private_items)]
 
-use std::collections::HashSet;
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    ops::Range,
+    path::Path,
+    result::Result as StdResult,
+};
 
 use bitflags::bitflags;
+use regex::{Regex, RegexBuilder};
 use rustdoc_types::{Crate, Id, Item, ItemEnum, Module, Struct, StructKind, Visibility};
+use strsim::osa_distance;
 
 use crate::{
     crateutils::{render_name, render_path, render_type},
+    error::{Result, RuskelError},
+    location::{SourceLocation, SourceLocator},
+    query::StructuredQuery,
     render::RenderSelection,
     signature,
+    typesearch::{TypeIndex, TypeQuery, TypeUsage},
 };
 
 bitflags! {
@@ -46,8 +58,46 @@ pub struct SearchOptions {
     pub include_private: bool,
     /// Whether matched container items should expand to include their children.
     pub expand_containers: bool,
+    /// How the query is matched against items.
+    pub mode: SearchMode,
+    /// Whether literal queries also match item names within a small edit distance.
+    pub fuzzy: bool,
+    /// Item kinds the results are restricted to; empty selects every kind.
+    ///
+    /// The restriction also applies when expanding matched containers.
+    pub kinds: HashSet<SearchItemKind>,
+    /// Whether type searches let a reference match its referent, so `&[u8]` and `[u8]` match
+    /// each other at a small cost.
+    pub ignore_references: bool,
+}
+
+/// How a search query is matched against items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchMode {
+    /// Match the query as a literal substring.
+    ///
+    /// Queries starting with `regex:` are treated as regular expressions.
+    #[default]
+    Literal,
+    /// Match the query as a regular expression.
+    Regex,
+    /// Evaluate the query in the structured query language.
+    ///
+    /// Structured queries combine field-scoped terms such as `kind:trait name:Async` with `AND`,
+    /// `OR`, `NOT` (or a leading `-`) and parentheses. Unscoped terms match across
+    /// [`SearchOptions::domains`], and values starting with `regex:` are regular expressions.
+    Structured,
+    /// Match the query as a type signature such as `&str -> Result<_, _>`, structurally against
+    /// function inputs and outputs rather than as text.
+    Types,
+    /// Treat the query as a type path, and find the functions and methods that return it or take
+    /// it as a parameter, or every item referencing it.
+    Usage(TypeUsage),
 }
 
+/// Query prefix that selects regular expression matching.
+pub const REGEX_QUERY_PREFIX: &str = "regex:";
+
 impl SearchOptions {
     /// Create a new options struct with the provided query string.
     pub fn new(query: impl Into<String>) -> Self {
@@ -57,9 +107,36 @@ impl SearchOptions {
             case_sensitive: false,
             include_private: false,
             expand_containers: true,
+            mode: SearchMode::Literal,
+            fuzzy: false,
+            kinds: HashSet::new(),
+            ignore_references: false,
         }
     }
 
+    /// Create options finding the functions and methods that return the type at `type_path`.
+    pub fn producers(type_path: impl Into<String>) -> Self {
+        let mut options = Self::new(type_path);
+        options.mode = SearchMode::Usage(TypeUsage::Producers);
+        options
+    }
+
+    /// Create options finding the functions and methods that take the type at `type_path` as a
+    /// parameter.
+    pub fn consumers(type_path: impl Into<String>) -> Self {
+        let mut options = Self::new(type_path);
+        options.mode = SearchMode::Usage(TypeUsage::Consumers);
+        options
+    }
+
+    /// Create options finding every item whose signature, type, generics or bounds mention the
+    /// type at `type_path`.
+    pub fn references(type_path: impl Into<String>) -> Self {
+        let mut options = Self::new(type_path);
+        options.mode = SearchMode::Usage(TypeUsage::References);
+        options
+    }
+
     /// Create fully-specified search options with transport-independent defaults.
     pub fn configured(
         query: impl Into<String>,
@@ -83,6 +160,293 @@ impl SearchOptions {
             self.domains = SearchDomain::default();
         }
     }
+
+    /// Compile the query into a matcher, or `None` when the query is empty.
+    ///
+    /// Literal queries are escaped, so both modes share the same matching machinery. Fuzzy name
+    /// matching only applies to literal queries.
+    pub fn matcher(&self) -> Result<Option<QueryMatcher>> {
+        let trimmed = self.query.trim();
+        let (pattern, regex) = match trimmed.strip_prefix(REGEX_QUERY_PREFIX) {
+            Some(pattern) => (pattern.trim(), true),
+            None => (trimmed, self.mode == SearchMode::Regex),
+        };
+        if pattern.is_empty() {
+            return Ok(None);
+        }
+        QueryMatcher::compile(pattern, regex, self.case_sensitive, self.fuzzy).map(Some)
+    }
+}
+
+/// Typo-tolerant comparison of a literal query against whole item names.
+#[derive(Debug, Clone)]
+struct FuzzyName {
+    /// Query, lowercased unless matching is case sensitive.
+    needle: String,
+    case_sensitive: bool,
+    /// Largest edit distance accepted for this query length.
+    max_distance: usize,
+}
+
+impl FuzzyName {
+    fn new(pattern: &str, case_sensitive: bool) -> Self {
+        let needle = if case_sensitive {
+            pattern.to_string()
+        } else {
+            pattern.to_lowercase()
+        };
+        let max_distance = match needle.chars().count() {
+            0..=3 => 0,
+            4..=7 => 1,
+            _ => 2,
+        };
+        Self {
+            needle,
+            case_sensitive,
+            max_distance,
+        }
+    }
+
+    /// Edit distance between the query and `name`, when within the accepted range.
+    ///
+    /// Transposed letters count as a single edit, so `spwan` is one edit away from `spawn`.
+    fn distance(&self, name: &str) -> Option<usize> {
+        if self.max_distance == 0 {
+            return None;
+        }
+        let distance = if self.case_sensitive {
+            osa_distance(&self.needle, name)
+        } else {
+            osa_distance(&self.needle, &name.to_lowercase())
+        };
+        (distance <= self.max_distance).then_some(distance)
+    }
+}
+
+/// How strongly a query matched an item's name, strongest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum NameMatch {
+    /// The query covers the entire name.
+    Exact,
+    /// The query matches at the start of the name.
+    Prefix,
+    /// The query matches elsewhere within the name.
+    Substring,
+    /// The name is within the given edit distance of the query.
+    Fuzzy(usize),
+}
+
+/// Compiled search query, matching either a literal substring or a regular expression.
+#[derive(Debug, Clone)]
+pub struct QueryMatcher {
+    regex: Regex,
+    fuzzy: Option<FuzzyName>,
+}
+
+impl QueryMatcher {
+    /// Compile `pattern` as a literal substring or a regular expression.
+    ///
+    /// Fuzzy name matching is only enabled for literal patterns.
+    pub(crate) fn compile(
+        pattern: &str,
+        regex: bool,
+        case_sensitive: bool,
+        fuzzy: bool,
+    ) -> Result<Self> {
+        let source = if regex {
+            pattern.to_string()
+        } else {
+            regex::escape(pattern)
+        };
+        let fuzzy = (fuzzy && !regex).then(|| FuzzyName::new(pattern, case_sensitive));
+        RegexBuilder::new(&source)
+            .case_insensitive(!case_sensitive)
+            .build()
+            .map(|regex| Self { regex, fuzzy })
+            .map_err(|err| RuskelError::InvalidSearch(err.to_string()))
+    }
+
+    /// Does the query match anywhere in `haystack`?
+    pub fn is_match(&self, haystack: &str) -> bool {
+        self.regex.is_match(haystack)
+    }
+
+    /// Byte ranges of every non-empty match in `haystack`.
+    pub fn find_ranges(&self, haystack: &str) -> Vec<Range<usize>> {
+        self.regex
+            .find_iter(haystack)
+            .map(|found| found.range())
+            .filter(|range| !range.is_empty())
+            .collect()
+    }
+
+    /// Classify how the query matches `name`, if at all.
+    fn name_match(&self, name: &str) -> Option<NameMatch> {
+        let mut best = None;
+        for found in self.regex.find_iter(name) {
+            if found.range() == (0..name.len()) {
+                return Some(NameMatch::Exact);
+            }
+            if found.start() == 0 {
+                best = Some(NameMatch::Prefix);
+            } else if best.is_none() {
+                best = Some(NameMatch::Substring);
+            }
+        }
+        best.or_else(|| {
+            self.fuzzy
+                .as_ref()
+                .and_then(|fuzzy| fuzzy.distance(name))
+                .map(NameMatch::Fuzzy)
+        })
+    }
+
+    /// Determine which of `domains` the query matches for `entry`, and how strongly it matched
+    /// the name.
+    fn match_entry(
+        &self,
+        entry: &SearchEntry,
+        domains: SearchDomain,
+    ) -> (SearchDomain, Option<NameMatch>) {
+        let mut matched = SearchDomain::empty();
+        let name_match = if domains.contains(SearchDomain::NAMES) {
+            self.name_match(&entry.raw_name)
+        } else {
+            None
+        };
+        if name_match.is_some() {
+            matched |= SearchDomain::NAMES;
+        }
+        if domains.contains(SearchDomain::DOCS)
+            && entry.docs.as_ref().is_some_and(|docs| self.is_match(docs))
+        {
+            matched |= SearchDomain::DOCS;
+        }
+        if domains.contains(SearchDomain::PATHS) && self.is_match(&entry.path_string) {
+            matched |= SearchDomain::PATHS;
+        }
+        if domains.contains(SearchDomain::SIGNATURES)
+            && entry
+                .signature
+                .as_ref()
+                .is_some_and(|sig| self.is_match(sig))
+        {
+            matched |= SearchDomain::SIGNATURES;
+        }
+        (matched, name_match)
+    }
+}
+
+/// Accumulated evidence that an entry matched a query.
+#[derive(Debug)]
+pub struct EntryMatch<'q> {
+    /// Domains matched by any contributing matcher.
+    matched: SearchDomain,
+    /// Strongest name match among the contributing matchers.
+    name_match: Option<NameMatch>,
+    /// Contributing matchers, each with the domains it matched, used for highlighting.
+    sources: Vec<(SearchDomain, &'q QueryMatcher)>,
+}
+
+impl Default for EntryMatch<'_> {
+    fn default() -> Self {
+        Self {
+            matched: SearchDomain::empty(),
+            name_match: None,
+            sources: Vec::new(),
+        }
+    }
+}
+
+impl<'q> EntryMatch<'q> {
+    /// Test `matcher` against `entry` within `domains`, recording any hit.
+    pub(crate) fn record(
+        &mut self,
+        matcher: &'q QueryMatcher,
+        entry: &SearchEntry,
+        domains: SearchDomain,
+    ) -> bool {
+        let (matched, name_match) = matcher.match_entry(entry, domains);
+        if matched.is_empty() {
+            return false;
+        }
+        self.matched |= matched;
+        self.name_match = strongest(self.name_match, name_match);
+        self.sources.push((matched, matcher));
+        true
+    }
+
+    /// Fold the evidence gathered by another branch of the query into this one.
+    pub(crate) fn merge(&mut self, other: Self) {
+        self.matched |= other.matched;
+        self.name_match = strongest(self.name_match, other.name_match);
+        self.sources.extend(other.sources);
+    }
+}
+
+/// Pick the stronger of two optional name matches.
+fn strongest(a: Option<NameMatch>, b: Option<NameMatch>) -> Option<NameMatch> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Relevance of a name match; each tier outranks every bonus below it.
+const SCORE_EXACT_NAME: u32 = 1400;
+const SCORE_NAME_PREFIX: u32 = 1200;
+const SCORE_NAME_SUBSTRING: u32 = 1000;
+const SCORE_FUZZY_NAME: u32 = 800;
+/// Penalty per edit for fuzzy name matches.
+const SCORE_FUZZY_EDIT: u32 = 50;
+/// Relevance of matches outside the name.
+const SCORE_PATH: u32 = 600;
+const SCORE_SIGNATURE: u32 = 400;
+const SCORE_DOC: u32 = 200;
+/// Bonus for items whose entire path is public.
+const SCORE_PUBLIC: u32 = 50;
+/// Bonus for items at the crate root, reduced by `SCORE_DEPTH_STEP` per nested segment.
+const SCORE_SHALLOW: u32 = 90;
+const SCORE_DEPTH_STEP: u32 = 10;
+/// Relevance of an exact type signature match.
+const SCORE_TYPE_MATCH: u32 = 1400;
+/// Penalty per unit of type match cost.
+const SCORE_TYPE_COST: u32 = 100;
+
+/// Score a matched entry: match strength first, then shallow paths, then public visibility.
+fn relevance(entry: &SearchEntry, matched: SearchDomain, name_match: Option<NameMatch>) -> u32 {
+    let strength = match name_match {
+        Some(NameMatch::Exact) => SCORE_EXACT_NAME,
+        Some(NameMatch::Prefix) => SCORE_NAME_PREFIX,
+        Some(NameMatch::Substring) => SCORE_NAME_SUBSTRING,
+        Some(NameMatch::Fuzzy(distance)) => {
+            SCORE_FUZZY_NAME.saturating_sub(SCORE_FUZZY_EDIT * (distance as u32 - 1))
+        }
+        None if matched.contains(SearchDomain::PATHS) => SCORE_PATH,
+        None if matched.contains(SearchDomain::SIGNATURES) => SCORE_SIGNATURE,
+        None => SCORE_DOC,
+    };
+    strength + placement(entry)
+}
+
+/// Score a type signature match from its unification cost; closer matches rank higher.
+fn type_relevance(entry: &SearchEntry, cost: u32) -> u32 {
+    let strength = SCORE_TYPE_MATCH
+        .saturating_sub(SCORE_TYPE_COST * cost)
+        .max(SCORE_DOC);
+    strength + placement(entry)
+}
+
+/// Bonus for shallow paths and public visibility.
+fn placement(entry: &SearchEntry) -> u32 {
+    let depth = entry.path.len().saturating_sub(1) as u32;
+    let shallow = SCORE_SHALLOW.saturating_sub(SCORE_DEPTH_STEP * depth);
+    let public = if entry.path.iter().all(|segment| segment.is_public) {
+        SCORE_PUBLIC
+    } else {
+        0
+    };
+    shallow + public
 }
 
 /// Classified kind associated with a search result.
@@ -135,6 +499,29 @@ pub enum SearchItemKind {
 }
 
 impl SearchItemKind {
+    /// The kind of a rustdoc item that names a definition, module or `use` declaration.
+    pub(crate) fn of_item(item: &Item) -> Option<Self> {
+        match item.inner {
+            ItemEnum::Module(_) => Some(Self::Module),
+            ItemEnum::Struct(_) => Some(Self::Struct),
+            ItemEnum::Enum(_) => Some(Self::Enum),
+            ItemEnum::Union(_) => Some(Self::Union),
+            ItemEnum::Trait(_) => Some(Self::Trait),
+            ItemEnum::TraitAlias(_) => Some(Self::TraitAlias),
+            ItemEnum::Function(_) => Some(Self::Function),
+            ItemEnum::TypeAlias(_) => Some(Self::TypeAlias),
+            ItemEnum::Constant { .. } => Some(Self::Constant),
+            ItemEnum::Static(_) => Some(Self::Static),
+            ItemEnum::Macro(_) => Some(Self::Macro),
+            ItemEnum::ProcMacro(_) => Some(Self::ProcMacro),
+            ItemEnum::Primitive(_) => Some(Self::Primitive),
+            ItemEnum::StructField(_) => Some(Self::Field),
+            ItemEnum::Variant(_) => Some(Self::EnumVariant),
+            ItemEnum::Use(_) => Some(Self::Use),
+            _ => None,
+        }
+    }
+
     /// Human-friendly label describing the item kind.
     pub fn label(self) -> &'static str {
         match self {
@@ -207,6 +594,10 @@ pub struct SearchEntry {
     pub signature: Option<String>,
     /// Ancestor chain of items that must be rendered for context.
     pub ancestors: Vec<Id>,
+    /// Where the item is defined, if rustdoc recorded a span.
+    pub location: Option<SourceLocation>,
+    /// Whether the item carries a `#[deprecated]` attribute.
+    pub deprecated: bool,
 }
 
 /// Lightweight record describing an item for list mode output.
@@ -216,6 +607,42 @@ pub struct ListItem {
     pub kind: SearchItemKind,
     /// Canonical path rendered as a `::` separated string.
     pub path: String,
+    /// Where the item is defined, if rustdoc recorded a span.
+    pub location: Option<SourceLocation>,
+    /// Byte ranges of `path` matched by the search query, for highlighting.
+    pub highlights: Vec<Range<usize>>,
+    /// Relevance score when the listing was filtered by a search query.
+    pub score: Option<u32>,
+}
+
+/// Options that control which items a crate listing includes.
+#[derive(Debug, Clone, Default)]
+pub struct ListOptions {
+    /// Whether to include private or crate-private items.
+    pub include_private: bool,
+    /// Item kinds the listing is restricted to, on top of any kinds selected by
+    /// [`Self::search`]; empty selects every kind.
+    pub kinds: HashSet<SearchItemKind>,
+    /// Search filtering the listing, or `None` to list every item.
+    pub search: Option<SearchOptions>,
+}
+
+impl ListOptions {
+    /// List every item, including private ones when `include_private` is set.
+    pub fn all(include_private: bool) -> Self {
+        Self {
+            include_private,
+            ..Self::default()
+        }
+    }
+
+    /// List the items matching `search`.
+    pub fn matching(search: SearchOptions) -> Self {
+        Self {
+            search: Some(search),
+            ..Self::default()
+        }
+    }
 }
 
 /// Result of performing a query against a crate index.
@@ -241,10 +668,43 @@ pub struct SearchResult {
     pub ancestors: Vec<Id>,
     /// Domains that produced a match for this query result.
     pub matched: SearchDomain,
+    /// Where the item is defined, if rustdoc recorded a span.
+    pub location: Option<SourceLocation>,
+    /// Byte ranges of `path_string` matched through the name or path domains.
+    pub highlights: Vec<Range<usize>>,
+    /// Relevance of the match; results are sorted by descending score.
+    ///
+    /// Exact name matches rank above name prefixes, substrings, fuzzy names, paths, signatures
+    /// and docs, in that order. Within a tier, shallower and fully public items rank higher.
+    pub score: u32,
 }
 
 impl SearchResult {
-    fn from_entry(entry: &SearchEntry, matched: SearchDomain) -> Self {
+    fn from_match(entry: &SearchEntry, hits: &EntryMatch<'_>) -> Self {
+        Self::from_parts(
+            entry,
+            hits.matched,
+            path_highlights(entry, &hits.sources),
+            relevance(entry, hits.matched, hits.name_match),
+        )
+    }
+
+    /// Build a result for a type signature match with the given unification cost.
+    fn from_signature(entry: &SearchEntry, cost: u32) -> Self {
+        Self::from_parts(
+            entry,
+            SearchDomain::SIGNATURES,
+            Vec::new(),
+            type_relevance(entry, cost),
+        )
+    }
+
+    fn from_parts(
+        entry: &SearchEntry,
+        matched: SearchDomain,
+        highlights: Vec<Range<usize>>,
+        score: u32,
+    ) -> Self {
         Self {
             item_id: entry.item_id,
             kind: entry.kind,
@@ -256,80 +716,141 @@ impl SearchResult {
             signature: entry.signature.clone(),
             ancestors: entry.ancestors.clone(),
             matched,
+            location: entry.location.clone(),
+            highlights,
+            score,
         }
     }
 }
 
+/// Locate the parts of an entry's path that the query matched, merging overlapping ranges.
+fn path_highlights(
+    entry: &SearchEntry,
+    sources: &[(SearchDomain, &QueryMatcher)],
+) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    for (matched, matcher) in sources {
+        if matched.contains(SearchDomain::PATHS) {
+            ranges.extend(matcher.find_ranges(&entry.path_string));
+        }
+        if matched.contains(SearchDomain::NAMES)
+            && let Some(offset) = entry.path_string.len().checked_sub(entry.raw_name.len())
+            && entry.path_string[offset..] == entry.raw_name
+        {
+            ranges.extend(
+                matcher
+                    .find_ranges(&entry.raw_name)
+                    .into_iter()
+                    .map(|range| range.start + offset..range.end + offset),
+            );
+        }
+    }
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
 /// Index of crate items prepared for search queries.
 #[derive(Debug, Default, Clone)]
 pub struct SearchIndex {
     entries: Vec<SearchEntry>,
+    /// Position in `entries` of the first entry indexed for each item.
+    positions: HashMap<Id, usize>,
+    /// Function signatures for type-directed queries.
+    types: TypeIndex,
 }
 
 impl SearchIndex {
     /// Construct a new index by traversing the provided crate.
-    pub(crate) fn build(crate_data: &Crate, include_private: bool) -> Self {
-        let mut builder = IndexBuilder::new(crate_data, include_private);
+    ///
+    /// Source locations are reported relative to `package_root` when it is provided.
+    pub(crate) fn build(
+        crate_data: &Crate,
+        include_private: bool,
+        package_root: Option<&Path>,
+    ) -> Self {
+        let mut builder = IndexBuilder::new(crate_data, include_private, package_root);
         builder.traverse();
         builder.finish()
     }
 
-    /// Retrieve the immutable list of indexed entries.
-    pub(crate) fn entries(&self) -> &[SearchEntry] {
-        &self.entries
+    /// Look up the entry indexed for an item.
+    pub(crate) fn entry(&self, id: &Id) -> Option<&SearchEntry> {
+        self.positions
+            .get(id)
+            .map(|&position| &self.entries[position])
     }
 
-    /// Execute a query against the index and return matching results.
-    pub(crate) fn search(&self, options: &SearchOptions) -> Vec<SearchResult> {
+    /// Iterate over the entries whose kind is admitted by `kinds`.
+    pub(crate) fn entries_of_kind<'a>(
+        &'a self,
+        kinds: &'a HashSet<SearchItemKind>,
+    ) -> impl Iterator<Item = &'a SearchEntry> + 'a {
+        self.entries
+            .iter()
+            .filter(move |entry| kind_selected(kinds, entry.kind))
+    }
+
+    /// Execute a query against the index and return matching results, most relevant first.
+    ///
+    /// Fails when a regular expression does not compile, or a structured query, type signature
+    /// or type path is malformed.
+    pub(crate) fn search(&self, options: &SearchOptions) -> Result<Vec<SearchResult>> {
         let mut opts = options.clone();
         opts.ensure_domains();
-        let trimmed = opts.query.trim();
-        if trimmed.is_empty() {
-            return Vec::new();
-        }
-
-        let normalized_query = if opts.case_sensitive {
-            trimmed.to_string()
-        } else {
-            trimmed.to_lowercase()
-        };
 
         let mut results = Vec::new();
-        for entry in &self.entries {
-            let mut matched = SearchDomain::empty();
-            if opts.domains.contains(SearchDomain::NAMES)
-                && contains(&entry.raw_name, &normalized_query, opts.case_sensitive)
-            {
-                matched |= SearchDomain::NAMES;
-            }
-            if opts.domains.contains(SearchDomain::DOCS)
-                && entry
-                    .docs
-                    .as_ref()
-                    .is_some_and(|docs| contains(docs, &normalized_query, opts.case_sensitive))
-            {
-                matched |= SearchDomain::DOCS;
+        match opts.mode {
+            SearchMode::Usage(usage) => {
+                for (index, cost) in self.types.usage(&opts.query, usage)? {
+                    let entry = &self.entries[index];
+                    if kind_selected(&opts.kinds, entry.kind) {
+                        results.push(SearchResult::from_signature(entry, cost));
+                    }
+                }
             }
-            if opts.domains.contains(SearchDomain::PATHS)
-                && contains(&entry.path_string, &normalized_query, opts.case_sensitive)
-            {
-                matched |= SearchDomain::PATHS;
+            SearchMode::Types => {
+                let Some(query) = TypeQuery::parse(&opts.query)? else {
+                    return Ok(Vec::new());
+                };
+                for (index, cost) in self.types.search(&query, opts.ignore_references) {
+                    let entry = &self.entries[index];
+                    if kind_selected(&opts.kinds, entry.kind) {
+                        results.push(SearchResult::from_signature(entry, cost));
+                    }
+                }
             }
-            if opts.domains.contains(SearchDomain::SIGNATURES)
-                && entry
-                    .signature
-                    .as_ref()
-                    .is_some_and(|sig| contains(sig, &normalized_query, opts.case_sensitive))
-            {
-                matched |= SearchDomain::SIGNATURES;
+            SearchMode::Structured => {
+                let Some(query) = StructuredQuery::parse(&opts)? else {
+                    return Ok(Vec::new());
+                };
+                for entry in self.entries_of_kind(&opts.kinds) {
+                    if let Some(hits) = query.evaluate(entry) {
+                        results.push(SearchResult::from_match(entry, &hits));
+                    }
+                }
             }
-
-            if !matched.is_empty() {
-                results.push(SearchResult::from_entry(entry, matched));
+            SearchMode::Literal | SearchMode::Regex => {
+                let Some(matcher) = opts.matcher()? else {
+                    return Ok(Vec::new());
+                };
+                for entry in self.entries_of_kind(&opts.kinds) {
+                    let mut hits = EntryMatch::default();
+                    if hits.record(&matcher, entry, opts.domains) {
+                        results.push(SearchResult::from_match(entry, &hits));
+                    }
+                }
             }
         }
 
-        results
+        results.sort_by_key(|result| Reverse(result.score));
+        Ok(results)
     }
 }
 
@@ -345,16 +866,18 @@ struct IndexBuilder<'a> {
     stack: Vec<PathStackEntry>,
     entries: Vec<SearchEntry>,
     visited: HashSet<Id>,
+    locator: SourceLocator,
 }
 
 impl<'a> IndexBuilder<'a> {
-    fn new(crate_data: &'a Crate, include_private: bool) -> Self {
+    fn new(crate_data: &'a Crate, include_private: bool, package_root: Option<&Path>) -> Self {
         Self {
             crate_data,
             include_private,
             stack: Vec::new(),
             entries: Vec::new(),
             visited: HashSet::new(),
+            locator: SourceLocator::new(package_root),
         }
     }
 
@@ -365,8 +888,15 @@ impl<'a> IndexBuilder<'a> {
     }
 
     fn finish(self) -> SearchIndex {
+        let types = TypeIndex::build(self.crate_data, &self.entries);
+        let mut positions = HashMap::with_capacity(self.entries.len());
+        for (position, entry) in self.entries.iter().enumerate() {
+            positions.entry(entry.item_id).or_insert(position);
+        }
         SearchIndex {
             entries: self.entries,
+            positions,
+            types,
         }
     }
 
@@ -700,9 +1230,7 @@ impl<'a> IndexBuilder<'a> {
             rustdoc_types::Type::ResolvedPath(path) => {
                 let name = render_type(ty);
                 if let Some(item) = self.crate_data.index.get(&path.id) {
-                    let kind = self
-                        .kind_from_item(item)
-                        .unwrap_or(SearchItemKind::ImplTarget);
+                    let kind = SearchItemKind::of_item(item).unwrap_or(SearchItemKind::ImplTarget);
                     let segment = self.make_segment(item, kind, None);
                     Some(PathStackEntry {
                         id: Some(item.id),
@@ -740,27 +1268,6 @@ impl<'a> IndexBuilder<'a> {
         }
     }
 
-    fn kind_from_item(&self, item: &Item) -> Option<SearchItemKind> {
-        match item.inner {
-            ItemEnum::Module(_) => Some(SearchItemKind::Module),
-            ItemEnum::Struct(_) => Some(SearchItemKind::Struct),
-            ItemEnum::Enum(_) => Some(SearchItemKind::Enum),
-            ItemEnum::Union(_) => Some(SearchItemKind::Union),
-            ItemEnum::Trait(_) => Some(SearchItemKind::Trait),
-            ItemEnum::TraitAlias(_) => Some(SearchItemKind::TraitAlias),
-            ItemEnum::Function(_) => Some(SearchItemKind::Function),
-            ItemEnum::TypeAlias(_) => Some(SearchItemKind::TypeAlias),
-            ItemEnum::Constant { .. } => Some(SearchItemKind::Constant),
-            ItemEnum::Static(_) => Some(SearchItemKind::Static),
-            ItemEnum::Macro(_) => Some(SearchItemKind::Macro),
-            ItemEnum::ProcMacro(_) => Some(SearchItemKind::ProcMacro),
-            ItemEnum::Primitive(_) => Some(SearchItemKind::Primitive),
-            ItemEnum::StructField(_) => Some(SearchItemKind::Field),
-            ItemEnum::Variant(_) => Some(SearchItemKind::EnumVariant),
-            _ => None,
-        }
-    }
-
     fn record_simple(&mut self, item: &Item, kind: SearchItemKind) {
         let segment = self.make_segment(item, kind, None);
         self.record_item(item, kind, &segment, false, &[]);
@@ -825,6 +1332,8 @@ impl<'a> IndexBuilder<'a> {
             docs: item.docs.clone(),
             signature,
             ancestors,
+            location: self.locator.locate(item),
+            deprecated: item.deprecation.is_some(),
         };
 
         self.entries.push(result);
@@ -853,27 +1362,29 @@ fn join_path(path: &[SearchPathSegment]) -> String {
     out
 }
 
-fn contains(haystack: &str, needle: &str, case_sensitive: bool) -> bool {
-    if needle.is_empty() {
-        return false;
-    }
-    if case_sensitive {
-        haystack.contains(needle)
-    } else {
-        haystack.to_lowercase().contains(needle)
-    }
+/// Does `kinds` admit `kind`? An empty set admits every kind.
+pub fn kind_selected(kinds: &HashSet<SearchItemKind>, kind: SearchItemKind) -> bool {
+    kinds.is_empty() || kinds.contains(&kind)
 }
 
 /// Build a renderer selection set covering matches, their ancestors, and optionally their children.
+///
+/// When `kinds` is non-empty, matched containers are not expanded wholesale; only descendants of
+/// the selected kinds are pulled in, along with the items needed to reach them.
 pub fn build_render_selection(
     index: &SearchIndex,
     results: &[SearchResult],
     expand_containers: bool,
+    kinds: &HashSet<SearchItemKind>,
 ) -> RenderSelection {
     let mut matches = HashSet::new();
     let mut context = HashSet::new();
     let mut expanded = HashSet::new();
-    for result in results {
+    let expand_all = kinds.is_empty();
+    for result in results
+        .iter()
+        .filter(|result| kind_selected(kinds, result.kind))
+    {
         matches.insert(result.item_id);
         context.insert(result.item_id);
         context.extend(result.ancestors.iter().copied());
@@ -881,6 +1392,7 @@ pub fn build_render_selection(
     if expand_containers {
         let containers: HashSet<Id> = results
             .iter()
+            .filter(|result| matches.contains(&result.item_id))
             .filter(|result| {
                 matches!(
                     result.kind,
@@ -894,9 +1406,11 @@ pub fn build_render_selection(
             .collect();
 
         if !containers.is_empty() {
-            expanded.extend(containers.iter().copied());
+            if expand_all {
+                expanded.extend(containers.iter().copied());
+            }
             let mut descendant_containers = HashSet::new();
-            for entry in index.entries() {
+            for entry in index.entries_of_kind(kinds) {
                 if let Some(pos) = entry
                     .ancestors
                     .iter()
@@ -909,7 +1423,9 @@ pub fn build_render_selection(
                     }
                 }
             }
-            expanded.extend(descendant_containers);
+            if expand_all {
+                expanded.extend(descendant_containers);
+            }
         }
     }
 
@@ -935,7 +1451,7 @@ pub fn describe_domains(domains: SearchDomain) -> Vec<&'static str> {
 }
 
 /// Parse a single domain token (case-insensitive) into a [`SearchDomain`] flag.
-pub fn parse_domain_token(token: &str) -> Result<SearchDomain, String> {
+pub fn parse_domain_token(token: &str) -> StdResult<SearchDomain, String> {
     match token.to_ascii_lowercase().as_str() {
         "name" | "names" => Ok(SearchDomain::NAMES),
         "doc" | "docs" | "documentation" => Ok(SearchDomain::DOCS),
@@ -965,6 +1481,51 @@ where
     }
 }
 
+/// Parse a single item kind token (case-insensitive) into the [`SearchItemKind`]s it selects.
+///
+/// Broad tokens cover related kinds: `method` includes trait method declarations, `const` and
+/// `type` include their associated forms, and `macro` includes procedural macros.
+pub fn parse_kind_token(token: &str) -> StdResult<&'static [SearchItemKind], String> {
+    let kinds: &'static [SearchItemKind] = match token
+        .trim()
+        .to_ascii_lowercase()
+        .replace(['-', ' '], "_")
+        .as_str()
+    {
+        "crate" => &[SearchItemKind::Crate],
+        "mod" | "module" => &[SearchItemKind::Module],
+        "struct" => &[SearchItemKind::Struct],
+        "union" => &[SearchItemKind::Union],
+        "enum" => &[SearchItemKind::Enum],
+        "variant" | "enum_variant" => &[SearchItemKind::EnumVariant],
+        "field" => &[SearchItemKind::Field],
+        "trait" => &[SearchItemKind::Trait],
+        "trait_alias" => &[SearchItemKind::TraitAlias],
+        "fn" | "function" => &[SearchItemKind::Function],
+        "method" => &[SearchItemKind::Method, SearchItemKind::TraitMethod],
+        "trait_method" => &[SearchItemKind::TraitMethod],
+        "const" | "constant" => &[SearchItemKind::Constant, SearchItemKind::AssocConst],
+        "assoc_const" => &[SearchItemKind::AssocConst],
+        "type" => &[SearchItemKind::TypeAlias, SearchItemKind::AssocType],
+        "type_alias" => &[SearchItemKind::TypeAlias],
+        "assoc_type" => &[SearchItemKind::AssocType],
+        "static" => &[SearchItemKind::Static],
+        "use" => &[SearchItemKind::Use],
+        "macro" => &[SearchItemKind::Macro, SearchItemKind::ProcMacro],
+        "proc_macro" => &[SearchItemKind::ProcMacro],
+        "primitive" => &[SearchItemKind::Primitive],
+        _ => {
+            return Err(format!(
+                "invalid item kind '{token}'. Expected one of: crate, module, struct, union, \
+                     enum, variant, field, trait, trait_alias, fn, method, trait_method, const, \
+                     assoc_const, type, type_alias, assoc_type, static, use, macro, proc_macro, \
+                     primitive."
+            ));
+        }
+    };
+    Ok(kinds)
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -975,7 +1536,6 @@ mod tests {
     };
 
     use super::*;
-    use crate::error::{Result, RuskelError};
 
     fn empty_generics() -> Generics {
         Generics {
@@ -1228,7 +1788,7 @@ mod tests {
 
     fn build_index() -> SearchIndex {
         let crate_data = fixture_crate();
-        SearchIndex::build(&crate_data, false)
+        SearchIndex::build(&crate_data, false, None)
     }
 
     #[test]
@@ -1236,7 +1796,7 @@ mod tests {
         let index = build_index();
         let mut options = SearchOptions::new("render");
         options.domains = SearchDomain::NAMES;
-        let results = index.search(&options);
+        let results = index.search(&options).unwrap();
         assert!(results.iter().any(|r| r.raw_name == "render"));
         assert!(
             results
@@ -1250,7 +1810,7 @@ mod tests {
         let index = build_index();
         let mut options = SearchOptions::new("Widget");
         options.domains = SearchDomain::NAMES | SearchDomain::DOCS;
-        let results = index.search(&options);
+        let results = index.search(&options).unwrap();
         let widget = results
             .into_iter()
             .find(|r| r.raw_name == "Widget")
@@ -1261,6 +1821,16 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn entry_finds_each_indexed_item_by_id() {
+        let index = build_index();
+        for entry in &index.entries {
+            let found = index.entry(&entry.item_id).expect("indexed entry");
+            assert_eq!(found.item_id, entry.item_id);
+        }
+        assert!(index.entry(&Id(u32::MAX)).is_none());
+    }
+
     #[test]
     fn default_domains_exclude_paths() {
         let defaults = SearchDomain::default();
@@ -1275,7 +1845,7 @@ mod tests {
         let index = build_index();
         let mut options = SearchOptions::new("fixture::Widget::render");
         options.domains = SearchDomain::PATHS;
-        let results = index.search(&options);
+        let results = index.search(&options).unwrap();
         assert!(results.iter().any(|r| r.raw_name == "render"));
     }
 
@@ -1284,7 +1854,7 @@ mod tests {
         let index = build_index();
         let mut options = SearchOptions::new("fn helper");
         options.domains = SearchDomain::SIGNATURES;
-        let results = index.search(&options);
+        let results = index.search(&options).unwrap();
         assert!(results.iter().any(|r| r.raw_name == "helper"));
     }
 
@@ -1294,16 +1864,16 @@ mod tests {
         let mut options = SearchOptions::new("widget docs");
         options.domains = SearchDomain::DOCS;
         options.case_sensitive = true;
-        assert!(index.search(&options).is_empty());
+        assert!(index.search(&options).unwrap().is_empty());
         options.case_sensitive = false;
-        assert!(!index.search(&options).is_empty());
+        assert!(!index.search(&options).unwrap().is_empty());
     }
 
     #[test]
     fn negative_query_returns_empty() {
         let index = build_index();
         let options = SearchOptions::new("missing");
-        assert!(index.search(&options).is_empty());
+        assert!(index.search(&options).unwrap().is_empty());
     }
 
     #[test]
@@ -1318,4 +1888,191 @@ mod tests {
             vec!["name", "doc"]
         );
     }
+
+    #[test]
+    fn regex_queries_match_names_and_signatures() {
+        let index = build_index();
+        let mut options = SearchOptions::new("^rend");
+        options.domains = SearchDomain::NAMES;
+        assert!(index.search(&options).unwrap().is_empty());
+        options.mode = SearchMode::Regex;
+        let results = index.search(&options).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].raw_name, "render");
+
+        let mut options = SearchOptions::new(r"regex:fn \w+\(count: i32\)\s*-> Widget$");
+        options.domains = SearchDomain::SIGNATURES;
+        let results = index.search(&options).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].raw_name, "helper");
+    }
+
+    #[test]
+    fn invalid_regex_is_reported() {
+        let index = build_index();
+        let options = SearchOptions::new("regex:fn (");
+        assert!(matches!(
+            index.search(&options),
+            Err(RuskelError::InvalidSearch(_))
+        ));
+    }
+
+    #[test]
+    fn highlights_cover_name_and_path_matches() {
+        let index = build_index();
+        let mut options = SearchOptions::new("regex:w.d");
+        options.domains = SearchDomain::NAMES | SearchDomain::PATHS;
+        let results = index.search(&options).unwrap();
+        let render = results
+            .iter()
+            .find(|r| r.raw_name == "render")
+            .expect("render should match by path");
+        assert_eq!(render.path_string, "fixture::Widget::render");
+        assert_eq!(render.highlights, vec![9..12]);
+
+        let mut options = SearchOptions::new("E");
+        options.domains = SearchDomain::NAMES;
+        let results = index.search(&options).unwrap();
+        let render = results
+            .iter()
+            .find(|r| r.raw_name == "render")
+            .expect("render should match by name");
+        assert_eq!(render.highlights, vec![18..19, 21..22]);
+    }
+
+    #[test]
+    fn results_rank_exact_names_before_prefixes_and_docs() {
+        let index = build_index();
+        let results = index.search(&SearchOptions::new("paint")).unwrap();
+        let names: Vec<&str> = results.iter().map(|r| r.raw_name.as_str()).collect();
+        assert_eq!(names, vec!["paint", "Paintable"]);
+        assert!(results[0].score > results[1].score);
+
+        let mut options = SearchOptions::new("widget");
+        options.domains = SearchDomain::NAMES | SearchDomain::DOCS;
+        let results = index.search(&options).unwrap();
+        assert_eq!(results[0].raw_name, "Widget");
+        assert!(results[1..].iter().all(|r| r.matched == SearchDomain::DOCS));
+        assert!(
+            results
+                .windows(2)
+                .all(|pair| pair[0].score >= pair[1].score)
+        );
+    }
+
+    #[test]
+    fn shallower_and_public_items_score_higher() {
+        let index = build_index();
+        let entry = |name: &str| {
+            index
+                .entries
+                .iter()
+                .find(|entry| entry.raw_name == name)
+                .expect("entry should exist")
+                .clone()
+        };
+        let shallow = relevance(&entry("helper"), SearchDomain::DOCS, None);
+        let deep = relevance(&entry("render"), SearchDomain::DOCS, None);
+        assert!(shallow > deep);
+
+        let mut private = entry("helper");
+        private.path[1].is_public = false;
+        assert!(relevance(&private, SearchDomain::DOCS, None) < shallow);
+    }
+
+    #[test]
+    fn fuzzy_names_tolerate_typos() {
+        let index = build_index();
+        let mut options = SearchOptions::new("Paintabel");
+        options.domains = SearchDomain::NAMES;
+        assert!(index.search(&options).unwrap().is_empty());
+
+        options.fuzzy = true;
+        let results = index.search(&options).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].raw_name, "Paintable");
+        assert!(results[0].highlights.is_empty());
+
+        options.query = "regex:Paintabel".into();
+        assert!(index.search(&options).unwrap().is_empty());
+    }
+
+    #[test]
+    fn structured_queries_filter_by_field_and_kind() {
+        let index = build_index();
+        let mut options = SearchOptions::new("kind:method,fn doc:widget -name:render");
+        options.mode = SearchMode::Structured;
+        let results = index.search(&options).unwrap();
+        let names: Vec<&str> = results.iter().map(|r| r.raw_name.as_str()).collect();
+        assert_eq!(names, vec!["helper"]);
+        assert_eq!(results[0].matched, SearchDomain::DOCS);
+
+        options.query = "name:paint OR path:*::Widget::ren*".into();
+        let results = index.search(&options).unwrap();
+        let names: Vec<&str> = results.iter().map(|r| r.raw_name.as_str()).collect();
+        assert_eq!(names, vec!["paint", "Paintable", "render"]);
+        assert_eq!(results[2].highlights, vec![0..23]);
+
+        options.query = "kind:trait (".into();
+        assert!(matches!(
+            index.search(&options),
+            Err(RuskelError::InvalidSearch(_))
+        ));
+    }
+
+    #[test]
+    fn type_queries_match_signatures() {
+        let index = build_index();
+        let mut options = SearchOptions::new("i32 -> Widget");
+        options.mode = SearchMode::Types;
+        let results = index.search(&options).unwrap();
+        let names: Vec<&str> = results.iter().map(|r| r.raw_name.as_str()).collect();
+        assert_eq!(names, vec!["helper"]);
+        assert_eq!(results[0].matched, SearchDomain::SIGNATURES);
+
+        options.query = "&Widget -> u32".into();
+        let results = index.search(&options).unwrap();
+        let names: Vec<&str> = results.iter().map(|r| r.raw_name.as_str()).collect();
+        assert_eq!(names, vec!["render"]);
+
+        options.query = "&Paintable -> ()".into();
+        let results = index.search(&options).unwrap();
+        let names: Vec<&str> = results.iter().map(|r| r.raw_name.as_str()).collect();
+        assert_eq!(names, vec!["paint"]);
+
+        options.query = "Widget -> u32".into();
+        assert!(index.search(&options).unwrap().is_empty());
+        options.ignore_references = true;
+        let exact = SearchOptions {
+            query: "&Widget -> u32".into(),
+            ignore_references: false,
+            ..options.clone()
+        };
+        let loose = index.search(&options).unwrap();
+        assert_eq!(loose.len(), 1);
+        assert!(loose[0].score < index.search(&exact).unwrap()[0].score);
+
+        options.kinds = HashSet::from([SearchItemKind::Function]);
+        assert!(index.search(&options).unwrap().is_empty());
+    }
+
+    #[test]
+    fn usage_queries_find_producers_and_consumers() {
+        let index = build_index();
+        let results = index.search(&SearchOptions::producers("Widget")).unwrap();
+        let names: Vec<&str> = results.iter().map(|r| r.raw_name.as_str()).collect();
+        assert_eq!(names, vec!["helper"]);
+
+        // `render` takes `&self`, which does not count as consuming a `Widget`.
+        assert!(
+            index
+                .search(&SearchOptions::consumers("crate::Widget"))
+                .unwrap()
+                .is_empty()
+        );
+        assert!(matches!(
+            index.search(&SearchOptions::producers("::")),
+            Err(RuskelError::InvalidSearch(_))
+        ));
+    }
 }
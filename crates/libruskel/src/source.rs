@@ -0,0 +1,172 @@
+//! Sources of rustdoc data for resolved targets.
+//!
+//! [`Ruskel`](crate::Ruskel) resolves a target specification to a package or standard library
+//! crate, then asks its [`CrateSource`] for the rustdoc data. [`CargoSource`] builds it with
+//! `cargo rustdoc`, [`SysrootSource`] reads the toolchain's pre-built standard library JSON, and
+//! [`FixtureSource`] reads JSON generated ahead of time, so renders and searches can run without
+//! a toolchain.
+
+use std::{
+    fmt::Debug,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use rustdoc_types::Crate;
+
+use crate::{
+    cargoutils::{CrateRead, CrateReadOptions, ResolvedTarget},
+    error::{Result, RuskelError},
+    toolchain::nightly_sysroot,
+};
+
+/// Turns a resolved target into the rustdoc data of its crate.
+pub trait CrateSource: Debug + Send + Sync {
+    /// Read the crate `target` points at, documented as `options` describe.
+    ///
+    /// The target's module filter is applied afterwards, so sources always return the whole
+    /// crate.
+    fn read_crate(&self, target: &ResolvedTarget, options: &CrateReadOptions) -> Result<CrateRead>;
+}
+
+/// Builds rustdoc JSON with `cargo rustdoc` on the nightly toolchain, reading standard library
+/// crates from the sysroot instead. This is the default source.
+#[derive(Debug, Clone, Default)]
+pub struct CargoSource {
+    /// Source of standard library crates.
+    sysroot: SysrootSource,
+}
+
+impl CargoSource {
+    /// Reads standard library crates from `sysroot` rather than the nightly toolchain's.
+    pub fn with_sysroot(mut self, sysroot: SysrootSource) -> Self {
+        self.sysroot = sysroot;
+        self
+    }
+}
+
+impl CrateSource for CargoSource {
+    fn read_crate(&self, target: &ResolvedTarget, options: &CrateReadOptions) -> Result<CrateRead> {
+        if target.std_names().is_some() {
+            return self.sysroot.read_crate(target, options);
+        }
+        target.read_package(options)
+    }
+}
+
+/// Reads the standard library JSON installed by the `rust-docs-json` component.
+///
+/// Only standard library targets can be read; packages fail with an error.
+#[derive(Debug, Clone, Default)]
+pub struct SysrootSource {
+    /// Toolchain sysroot, or `None` for the nightly toolchain's.
+    sysroot: Option<PathBuf>,
+}
+
+impl SysrootSource {
+    /// Reads the JSON of the toolchain installed at `sysroot`, as `rustc --print sysroot` prints
+    /// it.
+    pub fn at(sysroot: impl Into<PathBuf>) -> Self {
+        Self {
+            sysroot: Some(sysroot.into()),
+        }
+    }
+}
+
+impl CrateSource for SysrootSource {
+    fn read_crate(
+        &self,
+        target: &ResolvedTarget,
+        _options: &CrateReadOptions,
+    ) -> Result<CrateRead> {
+        let Some((actual, display)) = target.std_names() else {
+            return Err(RuskelError::Generate(format!(
+                "'{}' is not a standard library crate",
+                target.crate_name()?
+            )));
+        };
+        let sysroot = match &self.sysroot {
+            Some(sysroot) => sysroot.clone(),
+            None => nightly_sysroot()?,
+        };
+        let json_path = sysroot
+            .join("share")
+            .join("doc")
+            .join("rust")
+            .join("json")
+            .join(format!("{actual}.json"));
+
+        if !json_path.exists() {
+            return Err(RuskelError::Generate(
+                "Standard library documentation not available (missing rust-docs-json component)"
+                    .to_string(),
+            ));
+        }
+
+        let mut crate_data = read_json(&json_path)?;
+        rename_root(&mut crate_data, actual, display);
+        Ok(CrateRead::new(crate_data))
+    }
+}
+
+/// Reads rustdoc JSON generated ahead of time from a directory holding one `<crate>.json` file
+/// per crate, named as the crate is used in paths, e.g. `rustdoc_types.json`.
+///
+/// Targets still resolve as usual, so packages need their manifest on disk, but nothing is built.
+/// Fixtures are documented once, with private items; requests for public output filter them.
+/// Features and binary targets are not recorded.
+#[derive(Debug, Clone)]
+pub struct FixtureSource {
+    /// Directory holding the JSON files.
+    dir: PathBuf,
+}
+
+impl FixtureSource {
+    /// Reads JSON files from `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl CrateSource for FixtureSource {
+    fn read_crate(
+        &self,
+        target: &ResolvedTarget,
+        _options: &CrateReadOptions,
+    ) -> Result<CrateRead> {
+        let name = target.crate_name()?;
+        let json_path = self.dir.join(format!("{name}.json"));
+        if !json_path.exists() {
+            return Err(RuskelError::Generate(format!(
+                "no rustdoc JSON for '{name}' in {}",
+                self.dir.display()
+            )));
+        }
+
+        let mut crate_data = read_json(&json_path)?;
+        if let Some((actual, display)) = target.std_names() {
+            rename_root(&mut crate_data, actual, display);
+        }
+        Ok(CrateRead::new(crate_data))
+    }
+}
+
+/// Parse the rustdoc JSON file at `path`.
+fn read_json(path: &Path) -> Result<Crate> {
+    let json_content = fs::read_to_string(path)?;
+    serde_json::from_str(&json_content).map_err(|e| {
+        RuskelError::Generate(format!(
+            "Failed to parse rustdoc JSON '{}': {e}",
+            path.display()
+        ))
+    })
+}
+
+/// Show a standard library crate under the name it was requested by, e.g. `alloc` as `std`.
+fn rename_root(crate_data: &mut Crate, actual: &str, display: &str) {
+    if actual != display
+        && let Some(root_item) = crate_data.index.get_mut(&crate_data.root)
+    {
+        root_item.name = Some(display.to_string());
+    }
+}
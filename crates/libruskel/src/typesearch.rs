@@ -0,0 +1,1361 @@
+//! Type-directed search over function signatures.
+//!
+//! A query describes the shape of a function, such as `&str -> Result<_, _>` or
+//! `Iterator<Item = T> -> Vec<T>`: comma-separated input types, then `->` and the output type.
+//! A query without `->` describes the output alone, and a leading `->` leaves the inputs
+//! unconstrained. Single uppercase letters such as `T` are type variables that bind consistently
+//! across the query, and `_` matches any type. Lifetimes are always ignored.
+//!
+//! Candidates are unified structurally against the query. A candidate's generic parameters can
+//! stand in for any query type, and are preferred when their bounds name the queried trait. Each
+//! inexact step adds to the cost of a match, which ranks the results.
+//!
+//! The same index answers type usage queries: which functions produce a type in their output,
+//! and which consume it through a parameter. These match the ids of resolved paths rather than
+//! shapes, so they find every signature naming the type, however deeply nested. Reference
+//! queries widen this to every item of the API naming the type: function signatures, fields,
+//! variants, bounds, type aliases, associated types and constants.
+
+use std::{
+    collections::{HashMap, HashSet},
+    mem, slice, vec,
+};
+
+use rustdoc_types::{
+    AssocItemConstraintKind, Crate, GenericArg, GenericArgs, GenericBound, GenericParamDefKind,
+    Generics, Id, Item, ItemEnum, Path, Term, Type, WherePredicate,
+};
+
+use crate::{
+    crateutils::{TypeVisitor, walk_bounds, walk_generics, walk_type},
+    error::{Result, RuskelError},
+    search::SearchEntry,
+};
+
+/// Cost of instantiating a candidate's generic parameter with a query type.
+const COST_GENERIC: u32 = 2;
+/// Cost of matching a query type against a generic parameter or `impl Trait` through its bounds.
+const COST_BOUND: u32 = 1;
+/// Cost of matching a reference against its referent when references are ignored.
+const COST_REFERENCE: u32 = 1;
+/// Cost of each candidate input the query does not mention.
+const COST_EXTRA_INPUT: u32 = 1;
+
+/// Normalised type used for structural matching, with lifetimes erased.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TypeShape {
+    /// Named type or trait such as `Vec<T>` or `Iterator<Item = T>`.
+    Path {
+        /// Canonical path segments, or the segments as written when the path is not indexed.
+        segments: Vec<String>,
+        /// Positional type arguments.
+        args: Vec<Self>,
+        /// Associated type bindings such as `Item = T`, by name.
+        bindings: Vec<(String, Self)>,
+    },
+    /// Query type variable, or candidate generic parameter with its trait bounds.
+    Var {
+        /// Name of the variable or parameter.
+        name: String,
+        /// Traits the parameter is bounded by; always empty for query variables.
+        bounds: Vec<Self>,
+    },
+    /// `_` in a query, or an inferred type.
+    Any,
+    /// Reference `&T` or `&mut T`.
+    Ref {
+        /// Whether the reference is mutable.
+        mutable: bool,
+        /// Referenced type.
+        inner: Box<Self>,
+    },
+    /// Raw pointer `*const T` or `*mut T`.
+    Ptr {
+        /// Whether the pointer is `*mut`.
+        mutable: bool,
+        /// Pointed-to type.
+        inner: Box<Self>,
+    },
+    /// Slice `[T]`.
+    Slice(Box<Self>),
+    /// Array `[T; N]`, whatever its length.
+    Array(Box<Self>),
+    /// Tuple, with `()` as the unit type.
+    Tuple(Vec<Self>),
+    /// `impl Trait` or `dyn Trait`, described by its trait bounds.
+    Bounds(Vec<Self>),
+    /// Function pointer `fn(A) -> B`.
+    Fn {
+        /// Parameter types.
+        inputs: Vec<Self>,
+        /// Return type, `()` when omitted.
+        output: Box<Self>,
+    },
+    /// Associated type projection such as `T::Item`.
+    Projection {
+        /// Type the associated type is projected from.
+        base: Box<Self>,
+        /// Name of the associated type.
+        name: String,
+    },
+}
+
+impl TypeShape {
+    /// A path with a single segment and no arguments, such as a primitive.
+    fn named(name: &str) -> Self {
+        Self::Path {
+            segments: vec![name.to_string()],
+            args: Vec::new(),
+            bindings: Vec::new(),
+        }
+    }
+
+    /// The unit type `()`.
+    fn unit() -> Self {
+        Self::Tuple(Vec::new())
+    }
+}
+
+/// Converts rustdoc types into shapes within the generic scope of one function.
+struct ShapeBuilder<'a> {
+    /// Crate whose path table gives canonical path segments.
+    crate_data: &'a Crate,
+    /// Trait bounds of the generic parameters in scope, including `Self` inside traits.
+    bounds: HashMap<String, Vec<TypeShape>>,
+    /// Implementing type substituted for `Self` inside impl blocks.
+    self_type: Option<TypeShape>,
+    /// Generic parameter of a blanket impl, which stands for the implementing type.
+    blanket_param: Option<String>,
+}
+
+impl<'a> ShapeBuilder<'a> {
+    /// Create a builder with no generics in scope.
+    fn new(crate_data: &'a Crate) -> Self {
+        Self {
+            crate_data,
+            bounds: HashMap::new(),
+            self_type: None,
+            blanket_param: None,
+        }
+    }
+
+    /// Bring the generics of an enclosing impl or trait into scope.
+    fn enter(&mut self, item: &Item) {
+        match &item.inner {
+            ItemEnum::Impl(impl_) => {
+                self.add_generics(&impl_.generics);
+                self.self_type = Some(self.shape(&impl_.for_));
+                if let Some(Type::Generic(name)) = &impl_.blanket_impl {
+                    self.blanket_param = Some(name.clone());
+                }
+            }
+            ItemEnum::Trait(trait_) => {
+                self.add_generics(&trait_.generics);
+                let bound = self.trait_shape(item, &trait_.generics);
+                self.bounds
+                    .entry("Self".to_string())
+                    .or_default()
+                    .push(bound);
+            }
+            _ => {}
+        }
+    }
+
+    /// Record the bounds declared by `generics`, inline and in the where clause.
+    fn add_generics(&mut self, generics: &Generics) {
+        for param in &generics.params {
+            if let GenericParamDefKind::Type { bounds, .. } = &param.kind {
+                let shapes = self.bound_shapes(bounds);
+                self.bounds
+                    .entry(param.name.clone())
+                    .or_default()
+                    .extend(shapes);
+            }
+        }
+        for predicate in &generics.where_predicates {
+            if let WherePredicate::BoundPredicate {
+                type_: Type::Generic(name),
+                bounds,
+                ..
+            } = predicate
+            {
+                let shapes = self.bound_shapes(bounds);
+                self.bounds.entry(name.clone()).or_default().extend(shapes);
+            }
+        }
+    }
+
+    /// The trait an item defines, applied to its own type parameters.
+    fn trait_shape(&self, item: &Item, generics: &Generics) -> TypeShape {
+        let segments = self
+            .crate_data
+            .paths
+            .get(&item.id)
+            .map(|summary| summary.path.clone())
+            .unwrap_or_else(|| item.name.clone().into_iter().collect());
+        let args = generics
+            .params
+            .iter()
+            .filter(|param| matches!(param.kind, GenericParamDefKind::Type { .. }))
+            .map(|param| self.var(&param.name))
+            .collect();
+        TypeShape::Path {
+            segments,
+            args,
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Shapes of the traits named by trait bounds, skipping lifetime and `use<..>` bounds.
+    fn bound_shapes(&self, bounds: &[GenericBound]) -> Vec<TypeShape> {
+        let mut folder = ShapeFolder::new(self);
+        walk_bounds(bounds, &mut folder);
+        folder.shapes
+    }
+
+    /// A generic parameter with the bounds it has in scope.
+    fn var(&self, name: &str) -> TypeShape {
+        TypeShape::Var {
+            name: name.to_string(),
+            bounds: self.bounds.get(name).cloned().unwrap_or_default(),
+        }
+    }
+
+    /// Shape of a type, substituting the implementing type for `Self`.
+    fn shape(&self, ty: &Type) -> TypeShape {
+        let mut folder = ShapeFolder::new(self);
+        walk_type(ty, &mut folder);
+        folder.shapes.pop().unwrap_or(TypeShape::Any)
+    }
+
+    /// Shape of a function output, where a missing output is the unit type.
+    fn output(&self, output: Option<&Type>) -> TypeShape {
+        output.map_or_else(TypeShape::unit, |ty| self.shape(ty))
+    }
+
+    /// Assemble the shape of `ty` from the shapes of the types and paths nested directly within
+    /// it, in the order the walker visits them.
+    fn fold_type(&self, ty: &Type, nested: &mut vec::IntoIter<TypeShape>) -> TypeShape {
+        match ty {
+            Type::ResolvedPath(_) | Type::Pat { .. } => next_shape(nested),
+            Type::DynTrait(_) | Type::ImplTrait(_) => TypeShape::Bounds(nested.collect()),
+            Type::Generic(name) if name == "Self" || self.blanket_param.as_ref() == Some(name) => {
+                self.self_type.clone().unwrap_or_else(|| self.var(name))
+            }
+            Type::Generic(name) => self.var(name),
+            Type::Primitive(name) => TypeShape::named(name),
+            Type::FunctionPointer(pointer) => {
+                let mut inputs: Vec<_> = nested.collect();
+                let output = match pointer.sig.output {
+                    Some(_) => inputs.pop().unwrap_or(TypeShape::Any),
+                    None => TypeShape::unit(),
+                };
+                TypeShape::Fn {
+                    inputs,
+                    output: Box::new(output),
+                }
+            }
+            Type::Tuple(_) => TypeShape::Tuple(nested.collect()),
+            Type::Slice(_) => TypeShape::Slice(Box::new(next_shape(nested))),
+            Type::Array { .. } => TypeShape::Array(Box::new(next_shape(nested))),
+            Type::Infer => TypeShape::Any,
+            Type::RawPointer { is_mutable, .. } => TypeShape::Ptr {
+                mutable: *is_mutable,
+                inner: Box::new(next_shape(nested)),
+            },
+            Type::BorrowedRef { is_mutable, .. } => TypeShape::Ref {
+                mutable: *is_mutable,
+                inner: Box::new(next_shape(nested)),
+            },
+            // The self type is walked first; the trait and its arguments are not part of the
+            // shape.
+            Type::QualifiedPath { name, .. } => TypeShape::Projection {
+                base: Box::new(next_shape(nested)),
+                name: name.clone(),
+            },
+        }
+    }
+
+    /// Assemble the shape of a path from the shapes of its generic arguments.
+    fn fold_path(&self, path: &Path, nested: &mut vec::IntoIter<TypeShape>) -> TypeShape {
+        let (args, bindings) = match path.args.as_deref() {
+            Some(args) => fold_generic_args(args, nested),
+            None => (Vec::new(), Vec::new()),
+        };
+        TypeShape::Path {
+            segments: path_segments(self.crate_data, path),
+            args,
+            bindings,
+        }
+    }
+}
+
+/// Split the shapes of generic arguments into positional types and associated type bindings.
+///
+/// `Fn(A, B) -> C` becomes the arguments `A, B` with the binding `Output = C`. Shapes for the
+/// arguments of generic associated types and for associated type bounds are consumed and
+/// dropped.
+fn fold_generic_args(
+    args: &GenericArgs,
+    nested: &mut vec::IntoIter<TypeShape>,
+) -> (Vec<TypeShape>, Vec<(String, TypeShape)>) {
+    match args {
+        GenericArgs::AngleBracketed { args, constraints } => {
+            let types = args
+                .iter()
+                .filter_map(|arg| match arg {
+                    GenericArg::Type(_) => Some(next_shape(nested)),
+                    GenericArg::Infer => Some(TypeShape::Any),
+                    _ => None,
+                })
+                .collect();
+            let mut bindings = Vec::new();
+            for constraint in constraints {
+                if let Some(args) = &constraint.args {
+                    fold_generic_args(args, nested);
+                }
+                match &constraint.binding {
+                    AssocItemConstraintKind::Equality(Term::Type(_)) => {
+                        bindings.push((constraint.name.clone(), next_shape(nested)));
+                    }
+                    AssocItemConstraintKind::Equality(Term::Constant(_)) => {}
+                    AssocItemConstraintKind::Constraint(bounds) => {
+                        let traits = bounds
+                            .iter()
+                            .filter(|bound| matches!(bound, GenericBound::TraitBound { .. }))
+                            .count();
+                        nested.take(traits).for_each(drop);
+                    }
+                }
+            }
+            (types, bindings)
+        }
+        GenericArgs::Parenthesized { inputs, output } => {
+            let inputs = nested.take(inputs.len()).collect();
+            let output = match output {
+                Some(_) => next_shape(nested),
+                None => TypeShape::unit(),
+            };
+            (inputs, vec![("Output".to_string(), output)])
+        }
+        GenericArgs::ReturnTypeNotation => (Vec::new(), Vec::new()),
+    }
+}
+
+/// The next nested shape, or `_` should the walk have produced fewer than expected.
+fn next_shape(nested: &mut vec::IntoIter<TypeShape>) -> TypeShape {
+    nested.next().unwrap_or(TypeShape::Any)
+}
+
+/// Folds the types and paths visited by the shared type walker into shapes, bottom up.
+struct ShapeFolder<'b, 'a> {
+    /// Generic scope the shapes are built in.
+    builder: &'b ShapeBuilder<'a>,
+    /// Completed shapes not yet claimed by an enclosing type or path.
+    shapes: Vec<TypeShape>,
+    /// Length of `shapes` when each type or path still being walked was entered.
+    marks: Vec<usize>,
+}
+
+impl<'b, 'a> ShapeFolder<'b, 'a> {
+    /// Create a folder building shapes within the scope of `builder`.
+    fn new(builder: &'b ShapeBuilder<'a>) -> Self {
+        Self {
+            builder,
+            shapes: Vec::new(),
+            marks: Vec::new(),
+        }
+    }
+
+    /// Claim the shapes completed since the innermost open type or path was entered.
+    fn nested(&mut self) -> vec::IntoIter<TypeShape> {
+        let mark = self.marks.pop().unwrap_or_default();
+        self.shapes.split_off(mark).into_iter()
+    }
+}
+
+impl<'t> TypeVisitor<'t> for ShapeFolder<'_, '_> {
+    fn visit_type(&mut self, _ty: &'t Type) {
+        self.marks.push(self.shapes.len());
+    }
+
+    fn leave_type(&mut self, ty: &'t Type) {
+        let mut nested = self.nested();
+        let shape = self.builder.fold_type(ty, &mut nested);
+        self.shapes.push(shape);
+    }
+
+    fn visit_path(&mut self, _path: &'t Path) {
+        self.marks.push(self.shapes.len());
+    }
+
+    fn leave_path(&mut self, path: &'t Path) {
+        let mut nested = self.nested();
+        let shape = self.builder.fold_path(path, &mut nested);
+        self.shapes.push(shape);
+    }
+}
+
+/// Parsed signature query.
+#[derive(Debug, Clone)]
+pub struct TypeQuery {
+    /// Input types, or `None` when the inputs are unconstrained.
+    inputs: Option<Vec<TypeShape>>,
+    /// Output type, or `None` when the output is unconstrained.
+    output: Option<TypeShape>,
+}
+
+impl TypeQuery {
+    /// Parse a signature query, returning `None` when it is empty.
+    pub fn parse(query: &str) -> Result<Option<Self>> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Ok(None);
+        }
+        let sides = split_top_level(query, "->");
+        let (inputs, output) = match sides.as_slice() {
+            [output] => (None, Some(parse_type(output)?)),
+            [inputs, output] => {
+                let inputs = if inputs.trim().is_empty() {
+                    None
+                } else {
+                    Some(
+                        split_top_level(inputs, ",")
+                            .into_iter()
+                            .map(parse_type)
+                            .collect::<Result<Vec<_>>>()?,
+                    )
+                };
+                let output = if output.trim().is_empty() {
+                    None
+                } else {
+                    Some(parse_type(output)?)
+                };
+                (inputs, output)
+            }
+            _ => {
+                return Err(invalid(format!(
+                    "signature query '{query}' has more than one top-level '->'"
+                )));
+            }
+        };
+        Ok(Some(Self { inputs, output }))
+    }
+}
+
+/// Build an invalid search error.
+fn invalid(message: String) -> RuskelError {
+    RuskelError::InvalidSearch(message)
+}
+
+/// Split `text` at every occurrence of `separator` outside brackets.
+///
+/// The `>` of a `->` arrow never closes an angle bracket, and an arrow directly after the
+/// arguments of `Fn(..)` or `fn(..)` belongs to that type.
+fn split_top_level<'a>(text: &'a str, separator: &str) -> Vec<&'a str> {
+    let bytes = text.as_bytes();
+    let mut parts = Vec::new();
+    // Open brackets, each recording whether it starts the argument list of a function type.
+    let mut open: Vec<bool> = Vec::new();
+    let mut closed_fn_args = false;
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i..].starts_with(b"->") {
+            if open.is_empty() && !closed_fn_args && separator == "->" {
+                parts.push(&text[start..i]);
+                start = i + 2;
+            }
+            closed_fn_args = false;
+            i += 2;
+            continue;
+        }
+        match bytes[i] {
+            b'(' => {
+                let preceding = text[..i].trim_end().as_bytes().last();
+                open.push(preceding.is_some_and(|c| c.is_ascii_alphanumeric()));
+            }
+            b'<' | b'[' => open.push(false),
+            b')' => closed_fn_args = open.pop().unwrap_or(false),
+            b'>' | b']' => {
+                open.pop();
+            }
+            b',' if open.is_empty() && separator == "," => {
+                parts.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+        if !bytes[i].is_ascii_whitespace() && bytes[i] != b')' {
+            closed_fn_args = false;
+        }
+        i += 1;
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+/// Parse one type of a signature query.
+fn parse_type(text: &str) -> Result<TypeShape> {
+    let text = text.trim();
+    let ty = syn::parse_str::<syn::Type>(text)
+        .map_err(|err| invalid(format!("cannot parse type '{text}': {err}")))?;
+    query_shape(&ty)
+}
+
+/// Whether `name` is a query type variable: one uppercase letter, optionally numbered.
+fn is_type_variable(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_uppercase()) && chars.all(|c| c.is_ascii_digit())
+}
+
+/// Shape of a type parsed from a query.
+fn query_shape(ty: &syn::Type) -> Result<TypeShape> {
+    Ok(match ty {
+        syn::Type::Path(type_path) => query_path(type_path.qself.as_ref(), &type_path.path)?,
+        syn::Type::Reference(reference) => TypeShape::Ref {
+            mutable: reference.mutability.is_some(),
+            inner: Box::new(query_shape(&reference.elem)?),
+        },
+        syn::Type::Ptr(pointer) => TypeShape::Ptr {
+            mutable: pointer.mutability.is_some(),
+            inner: Box::new(query_shape(&pointer.elem)?),
+        },
+        syn::Type::Slice(slice) => TypeShape::Slice(Box::new(query_shape(&slice.elem)?)),
+        syn::Type::Array(array) => TypeShape::Array(Box::new(query_shape(&array.elem)?)),
+        syn::Type::Tuple(tuple) => {
+            TypeShape::Tuple(tuple.elems.iter().map(query_shape).collect::<Result<_>>()?)
+        }
+        syn::Type::Infer(_) => TypeShape::Any,
+        syn::Type::Never(_) => TypeShape::named("never"),
+        syn::Type::Paren(paren) => query_shape(&paren.elem)?,
+        syn::Type::Group(group) => query_shape(&group.elem)?,
+        syn::Type::ImplTrait(impl_trait) => TypeShape::Bounds(query_bounds(&impl_trait.bounds)?),
+        syn::Type::TraitObject(object) => TypeShape::Bounds(query_bounds(&object.bounds)?),
+        syn::Type::BareFn(function) => TypeShape::Fn {
+            inputs: function
+                .inputs
+                .iter()
+                .map(|arg| query_shape(&arg.ty))
+                .collect::<Result<_>>()?,
+            output: Box::new(query_return(&function.output)?),
+        },
+        _ => return Err(invalid("unsupported type in signature query".to_string())),
+    })
+}
+
+/// Shape of a query's return type, `()` when omitted.
+fn query_return(output: &syn::ReturnType) -> Result<TypeShape> {
+    match output {
+        syn::ReturnType::Default => Ok(TypeShape::unit()),
+        syn::ReturnType::Type(_, ty) => query_shape(ty),
+    }
+}
+
+/// Shapes of the traits named by query bounds, skipping lifetimes.
+fn query_bounds<'a>(
+    bounds: impl IntoIterator<Item = &'a syn::TypeParamBound>,
+) -> Result<Vec<TypeShape>> {
+    bounds
+        .into_iter()
+        .filter_map(|bound| match bound {
+            syn::TypeParamBound::Trait(trait_bound) => Some(query_path(None, &trait_bound.path)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Shape of a query path, where a lone uppercase letter is a type variable and `T::Name` is a
+/// projection.
+fn query_path(qself: Option<&syn::QSelf>, path: &syn::Path) -> Result<TypeShape> {
+    let segments: Vec<&syn::PathSegment> = path.segments.iter().collect();
+    let Some(last) = segments.last() else {
+        return Err(invalid("empty path in signature query".to_string()));
+    };
+    if let Some(qself) = qself {
+        return Ok(TypeShape::Projection {
+            base: Box::new(query_shape(&qself.ty)?),
+            name: last.ident.to_string(),
+        });
+    }
+    let first = segments[0].ident.to_string();
+    if is_type_variable(&first) && segments[0].arguments.is_none() {
+        return Ok(match segments.as_slice() {
+            [_] => TypeShape::Var {
+                name: first,
+                bounds: Vec::new(),
+            },
+            _ => TypeShape::Projection {
+                base: Box::new(TypeShape::Var {
+                    name: first,
+                    bounds: Vec::new(),
+                }),
+                name: last.ident.to_string(),
+            },
+        });
+    }
+
+    let mut args = Vec::new();
+    let mut bindings = Vec::new();
+    match &last.arguments {
+        syn::PathArguments::None => {}
+        syn::PathArguments::AngleBracketed(angle) => {
+            for arg in &angle.args {
+                match arg {
+                    syn::GenericArgument::Type(ty) => args.push(query_shape(ty)?),
+                    syn::GenericArgument::AssocType(assoc) => {
+                        bindings.push((assoc.ident.to_string(), query_shape(&assoc.ty)?));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        syn::PathArguments::Parenthesized(paren) => {
+            for input in &paren.inputs {
+                args.push(query_shape(input)?);
+            }
+            bindings.push(("Output".to_string(), query_return(&paren.output)?));
+        }
+    }
+    Ok(TypeShape::Path {
+        segments: segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect(),
+        args,
+        bindings,
+    })
+}
+
+/// Split a user-supplied item path such as `crate::Config` or `http::Request<Body>` into
+/// segments, dropping generic arguments and a leading `crate`.
+pub fn parse_item_path(text: &str) -> Result<Vec<String>> {
+    let base = text.split('<').next().unwrap_or_default();
+    let mut segments: Vec<String> = base
+        .split("::")
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string)
+        .collect();
+    if segments.first().is_some_and(|segment| segment == "crate") {
+        segments.remove(0);
+    }
+    if segments.is_empty() {
+        return Err(invalid(format!("'{text}' does not name an item")));
+    }
+    Ok(segments)
+}
+
+/// Whether a query path names the candidate path: the final segments agree, and any qualifying
+/// segments of the query appear in order in the candidate.
+///
+/// The standard library roots `std`, `core` and `alloc` are interchangeable, so
+/// `std::fmt::Display` names `core::fmt::Display`.
+pub fn path_matches(query: &[String], candidate: &[String]) -> bool {
+    let is_std_root = |segment: &String| matches!(segment.as_str(), "std" | "core" | "alloc");
+    let query = match (query.split_first(), candidate.first()) {
+        (Some((first, rest)), Some(root))
+            if first != root && is_std_root(first) && is_std_root(root) =>
+        {
+            rest
+        }
+        _ => query,
+    };
+    let (Some((query_last, query_prefix)), Some((candidate_last, candidate_prefix))) =
+        (query.split_last(), candidate.split_last())
+    else {
+        return false;
+    };
+    if query_last != candidate_last {
+        return false;
+    }
+    let mut remaining = candidate_prefix.iter();
+    query_prefix
+        .iter()
+        .all(|segment| remaining.any(|candidate| candidate == segment))
+}
+
+/// Substitutions accumulated while unifying a query against one candidate.
+#[derive(Debug, Clone)]
+struct Unifier {
+    /// Whether references may match their referents.
+    ignore_references: bool,
+    /// Candidate shapes bound to query variables.
+    query_vars: HashMap<String, TypeShape>,
+    /// Query shapes bound to candidate generic parameters.
+    candidate_vars: HashMap<String, TypeShape>,
+}
+
+impl Unifier {
+    /// Create a unifier with no substitutions.
+    fn new(ignore_references: bool) -> Self {
+        Self {
+            ignore_references,
+            query_vars: HashMap::new(),
+            candidate_vars: HashMap::new(),
+        }
+    }
+
+    /// Run `step`, discarding its substitutions when it fails.
+    fn attempt(&mut self, step: impl FnOnce(&mut Self) -> Option<u32>) -> Option<u32> {
+        let saved = self.clone();
+        let cost = step(self);
+        if cost.is_none() {
+            *self = saved;
+        }
+        cost
+    }
+
+    /// Unify a query shape with a candidate shape, returning the cost of the match.
+    fn unify(&mut self, query: &TypeShape, candidate: &TypeShape) -> Option<u32> {
+        match (query, candidate) {
+            (TypeShape::Any, _) => Some(0),
+            (TypeShape::Var { name, .. }, _) => self.bind_query(name, candidate),
+            (_, TypeShape::Var { name, bounds }) => self.bind_candidate(name, bounds, query),
+            (_, TypeShape::Bounds(bounds)) => self.unify_bounds(query, bounds),
+            (
+                TypeShape::Ref {
+                    mutable: query_mut,
+                    inner: query_inner,
+                },
+                TypeShape::Ref {
+                    mutable: candidate_mut,
+                    inner: candidate_inner,
+                },
+            ) => {
+                let cost = if query_mut == candidate_mut {
+                    0
+                } else if self.ignore_references {
+                    COST_REFERENCE
+                } else {
+                    return None;
+                };
+                Some(cost + self.unify(query_inner, candidate_inner)?)
+            }
+            (TypeShape::Ref { inner, .. }, _) if self.ignore_references => {
+                Some(COST_REFERENCE + self.unify(inner, candidate)?)
+            }
+            (_, TypeShape::Ref { inner, .. }) if self.ignore_references => {
+                Some(COST_REFERENCE + self.unify(query, inner)?)
+            }
+            (
+                TypeShape::Ptr {
+                    mutable: query_mut,
+                    inner: query_inner,
+                },
+                TypeShape::Ptr {
+                    mutable: candidate_mut,
+                    inner: candidate_inner,
+                },
+            ) if query_mut == candidate_mut => self.unify(query_inner, candidate_inner),
+            (
+                TypeShape::Path {
+                    segments: query_segments,
+                    args: query_args,
+                    bindings: query_bindings,
+                },
+                TypeShape::Path {
+                    segments: candidate_segments,
+                    args: candidate_args,
+                    bindings: candidate_bindings,
+                },
+            ) => {
+                if !path_matches(query_segments, candidate_segments) {
+                    return None;
+                }
+                // A query path without arguments accepts any instantiation.
+                let mut cost = if query_args.is_empty() {
+                    0
+                } else {
+                    self.unify_all(query_args, candidate_args)?
+                };
+                for (name, query_binding) in query_bindings {
+                    let (_, candidate_binding) = candidate_bindings
+                        .iter()
+                        .find(|(candidate_name, _)| candidate_name == name)?;
+                    cost += self.unify(query_binding, candidate_binding)?;
+                }
+                Some(cost)
+            }
+            (TypeShape::Slice(query), TypeShape::Slice(candidate))
+            | (TypeShape::Array(query), TypeShape::Array(candidate)) => {
+                self.unify(query, candidate)
+            }
+            (TypeShape::Tuple(query), TypeShape::Tuple(candidate)) => {
+                self.unify_all(query, candidate)
+            }
+            (
+                TypeShape::Fn {
+                    inputs: query_inputs,
+                    output: query_output,
+                },
+                TypeShape::Fn {
+                    inputs: candidate_inputs,
+                    output: candidate_output,
+                },
+            ) => Some(
+                self.unify_all(query_inputs, candidate_inputs)?
+                    + self.unify(query_output, candidate_output)?,
+            ),
+            (
+                TypeShape::Projection {
+                    base: query_base,
+                    name: query_name,
+                },
+                TypeShape::Projection {
+                    base: candidate_base,
+                    name: candidate_name,
+                },
+            ) if query_name == candidate_name => self.unify(query_base, candidate_base),
+            _ => None,
+        }
+    }
+
+    /// Unify two equally long lists pairwise.
+    fn unify_all(&mut self, query: &[TypeShape], candidate: &[TypeShape]) -> Option<u32> {
+        if query.len() != candidate.len() {
+            return None;
+        }
+        query
+            .iter()
+            .zip(candidate)
+            .try_fold(0, |cost, (query, candidate)| {
+                Some(cost + self.unify(query, candidate)?)
+            })
+    }
+
+    /// Bind a query variable to a candidate shape, or check it against its earlier binding.
+    fn bind_query(&mut self, name: &str, candidate: &TypeShape) -> Option<u32> {
+        match self.query_vars.get(name) {
+            Some(bound) => (bound == candidate).then_some(0),
+            None => {
+                self.query_vars.insert(name.to_string(), candidate.clone());
+                Some(0)
+            }
+        }
+    }
+
+    /// Bind a candidate generic parameter to a query shape, costing more when the query does
+    /// not name one of its bounds.
+    fn bind_candidate(
+        &mut self,
+        name: &str,
+        bounds: &[TypeShape],
+        query: &TypeShape,
+    ) -> Option<u32> {
+        if let Some(bound) = self.candidate_vars.get(name) {
+            return (bound == query).then_some(0);
+        }
+        let cost = if bounds.is_empty() {
+            COST_GENERIC
+        } else {
+            // A query naming one of the bounds is a strong match; anything else may or may not
+            // satisfy them.
+            self.attempt(|unifier| unifier.unify_bounds(query, bounds))
+                .unwrap_or(COST_GENERIC + COST_BOUND)
+        };
+        self.candidate_vars.insert(name.to_string(), query.clone());
+        Some(cost)
+    }
+
+    /// Match a query trait, or every trait of a query `impl Trait`, against candidate bounds.
+    fn unify_bounds(&mut self, query: &TypeShape, bounds: &[TypeShape]) -> Option<u32> {
+        let wanted = match query {
+            TypeShape::Bounds(wanted) => wanted.as_slice(),
+            other => slice::from_ref(other),
+        };
+        let mut cost = COST_BOUND;
+        for want in wanted {
+            cost += bounds
+                .iter()
+                .find_map(|bound| self.attempt(|unifier| unifier.unify(want, bound)))?;
+        }
+        Some(cost)
+    }
+}
+
+/// Function signature of an index entry, prepared for matching.
+#[derive(Debug, Clone)]
+struct TypedSignature {
+    /// Position of the function in the search index entries.
+    entry: usize,
+    /// Shapes of the parameters, including the receiver.
+    inputs: Vec<TypeShape>,
+    /// Shape of the return type.
+    output: TypeShape,
+}
+
+impl TypedSignature {
+    /// Cost of matching `query` against this signature, or `None` when it does not match.
+    ///
+    /// Each query input is paired with the first unused candidate input it unifies with.
+    fn match_query(&self, query: &TypeQuery, ignore_references: bool) -> Option<u32> {
+        let mut unifier = Unifier::new(ignore_references);
+        let mut cost = 0;
+        if let Some(output) = &query.output {
+            cost += unifier.unify(output, &self.output)?;
+        }
+        if let Some(inputs) = &query.inputs {
+            let mut used = vec![false; self.inputs.len()];
+            for wanted in inputs {
+                let (index, step) = self
+                    .inputs
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| !used[*index])
+                    .find_map(|(index, candidate)| {
+                        unifier
+                            .attempt(|unifier| unifier.unify(wanted, candidate))
+                            .map(|step| (index, step))
+                    })?;
+                used[index] = true;
+                cost += step;
+            }
+            let extra = used.iter().filter(|used| !**used).count() as u32;
+            cost += COST_EXTRA_INPUT * extra;
+        }
+        Some(cost)
+    }
+}
+
+/// Whether a type usage query looks for functions returning or accepting a type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TypeUsage {
+    /// Functions and methods whose output mentions the type, such as constructors and builders.
+    Producers,
+    /// Functions and methods with a parameter mentioning the type, excluding `self` receivers.
+    Consumers,
+    /// Items whose signature, type, generics or bounds mention the type, such as functions,
+    /// fields, variants and traits. `self` receivers are excluded.
+    References,
+}
+
+/// A type named by a signature.
+#[derive(Debug, Clone, Copy)]
+struct Mention {
+    /// Id of the named type.
+    id: Id,
+    /// Whether the type is the parameter or output itself, ignoring references, rather than
+    /// nested within it as in `Option<T>`.
+    direct: bool,
+}
+
+/// Types named by the output and the non-receiver inputs of one signature.
+#[derive(Debug, Clone)]
+struct SignatureMentions {
+    /// Position of the function in the search index entries.
+    entry: usize,
+    /// Types named by the output.
+    produced: Vec<Mention>,
+    /// Types named by the parameters other than `self`.
+    consumed: Vec<Mention>,
+    /// Types named by the bounds of the function's generic parameters.
+    bounds: Vec<Mention>,
+}
+
+/// Types named by an indexed item other than through a function signature.
+#[derive(Debug, Clone)]
+struct ItemMentions {
+    /// Position of the item in the search index entries.
+    entry: usize,
+    /// Types the item names.
+    mentions: Vec<Mention>,
+}
+
+/// Collects the ids of the paths a type mentions, treating `Self` as the implementing type.
+struct MentionCollector<'a> {
+    /// Crate whose path table gives canonical path segments.
+    crate_data: &'a Crate,
+    /// Id of the implementing type that `Self` stands for, if known.
+    self_id: Option<Id>,
+    /// Types named so far.
+    mentions: Vec<Mention>,
+    /// Canonical path segments of every named type, shared across collectors.
+    names: &'a mut HashMap<Id, Vec<String>>,
+}
+
+impl MentionCollector<'_> {
+    /// Record the types `ty` names, marking the one it is as direct.
+    fn collect(&mut self, ty: &Type) {
+        let head = head_id(ty, self.self_id);
+        walk_type(ty, self);
+        for mention in &mut self.mentions {
+            mention.direct |= Some(mention.id) == head;
+        }
+    }
+}
+
+impl<'a> TypeVisitor<'a> for MentionCollector<'_> {
+    fn visit_type(&mut self, ty: &'a Type) {
+        if matches!(ty, Type::Generic(name) if name == "Self")
+            && let Some(id) = self.self_id
+        {
+            self.mentions.push(Mention { id, direct: false });
+        }
+    }
+
+    fn visit_path(&mut self, path: &'a Path) {
+        self.names
+            .entry(path.id)
+            .or_insert_with(|| path_segments(self.crate_data, path));
+        self.mentions.push(Mention {
+            id: path.id,
+            direct: false,
+        });
+    }
+}
+
+/// Canonical segments of a path, falling back to the path as written.
+fn path_segments(crate_data: &Crate, path: &Path) -> Vec<String> {
+    match crate_data.paths.get(&path.id) {
+        Some(summary) => summary.path.clone(),
+        None => path
+            .path
+            .split("::")
+            .filter(|segment| !segment.is_empty() && *segment != "$crate")
+            .map(str::to_string)
+            .collect(),
+    }
+}
+
+/// The id of the type a parameter or output is, looking through references and pointers.
+fn head_id(ty: &Type, self_id: Option<Id>) -> Option<Id> {
+    match ty {
+        Type::ResolvedPath(path) => Some(path.id),
+        Type::Generic(name) if name == "Self" => self_id,
+        Type::BorrowedRef { type_, .. } | Type::RawPointer { type_, .. } => head_id(type_, self_id),
+        _ => None,
+    }
+}
+
+/// Function signatures and type references of the search index, used for type-directed queries.
+#[derive(Debug, Default, Clone)]
+pub struct TypeIndex {
+    /// Shapes of every indexed function signature.
+    signatures: Vec<TypedSignature>,
+    /// Types named by every indexed function signature.
+    mentions: Vec<SignatureMentions>,
+    /// Types named by fields, variants, bounds, aliases and constants.
+    references: Vec<ItemMentions>,
+    /// Canonical path segments of every type named by a signature.
+    names: HashMap<Id, Vec<String>>,
+}
+
+impl TypeIndex {
+    /// Collect the signatures of every function and method among `entries`, and the types
+    /// referenced by every other entry.
+    pub fn build(crate_data: &Crate, entries: &[SearchEntry]) -> Self {
+        let mut index = Self::default();
+        for (position, entry) in entries.iter().enumerate() {
+            let Some(item) = crate_data.index.get(&entry.item_id) else {
+                continue;
+            };
+            let mut builder = ShapeBuilder::new(crate_data);
+            let mut self_id = None;
+            for ancestor in &entry.ancestors {
+                if let Some(ancestor) = crate_data.index.get(ancestor) {
+                    builder.enter(ancestor);
+                    if let ItemEnum::Impl(impl_) = &ancestor.inner {
+                        self_id = head_id(&impl_.for_, None);
+                        if let Type::ResolvedPath(path) = &impl_.for_ {
+                            index
+                                .names
+                                .entry(path.id)
+                                .or_insert_with(|| path_segments(crate_data, path));
+                        }
+                    }
+                }
+            }
+            let ItemEnum::Function(function) = &item.inner else {
+                index.collect_references(crate_data, position, item, self_id);
+                continue;
+            };
+            builder.add_generics(&function.generics);
+            index.signatures.push(TypedSignature {
+                entry: position,
+                inputs: function
+                    .sig
+                    .inputs
+                    .iter()
+                    .map(|(_, ty)| builder.shape(ty))
+                    .collect(),
+                output: builder.output(function.sig.output.as_ref()),
+            });
+
+            let mut collector = MentionCollector {
+                crate_data,
+                self_id,
+                mentions: Vec::new(),
+                names: &mut index.names,
+            };
+            if let Some(output) = &function.sig.output {
+                collector.collect(output);
+            }
+            let produced = mem::take(&mut collector.mentions);
+            for (name, ty) in &function.sig.inputs {
+                if name != "self" {
+                    collector.collect(ty);
+                }
+            }
+            let consumed = mem::take(&mut collector.mentions);
+            walk_generics(&function.generics, &mut collector);
+            let bounds = collector.mentions;
+            index.mentions.push(SignatureMentions {
+                entry: position,
+                produced,
+                consumed,
+                bounds,
+            });
+        }
+        index
+    }
+
+    /// Record the types named by a non-function item: its type, generics and bounds.
+    fn collect_references(
+        &mut self,
+        crate_data: &Crate,
+        position: usize,
+        item: &Item,
+        self_id: Option<Id>,
+    ) {
+        let mut collector = MentionCollector {
+            crate_data,
+            self_id,
+            mentions: Vec::new(),
+            names: &mut self.names,
+        };
+        match &item.inner {
+            ItemEnum::StructField(ty)
+            | ItemEnum::AssocConst { type_: ty, .. }
+            | ItemEnum::Constant { type_: ty, .. }
+            | ItemEnum::Static(rustdoc_types::Static { type_: ty, .. }) => collector.collect(ty),
+            ItemEnum::Struct(struct_) => walk_generics(&struct_.generics, &mut collector),
+            ItemEnum::Enum(enum_) => walk_generics(&enum_.generics, &mut collector),
+            ItemEnum::Union(union_) => walk_generics(&union_.generics, &mut collector),
+            ItemEnum::Trait(trait_) => {
+                walk_generics(&trait_.generics, &mut collector);
+                walk_bounds(&trait_.bounds, &mut collector);
+            }
+            ItemEnum::TraitAlias(alias) => {
+                walk_generics(&alias.generics, &mut collector);
+                walk_bounds(&alias.params, &mut collector);
+            }
+            ItemEnum::TypeAlias(alias) => {
+                walk_generics(&alias.generics, &mut collector);
+                collector.collect(&alias.type_);
+            }
+            ItemEnum::AssocType {
+                generics,
+                bounds,
+                type_,
+            } => {
+                walk_generics(generics, &mut collector);
+                walk_bounds(bounds, &mut collector);
+                if let Some(ty) = type_ {
+                    collector.collect(ty);
+                }
+            }
+            _ => {}
+        }
+        if !collector.mentions.is_empty() {
+            self.references.push(ItemMentions {
+                entry: position,
+                mentions: collector.mentions,
+            });
+        }
+    }
+
+    /// Match `query` against every signature, returning entry positions with their match cost.
+    pub fn search(&self, query: &TypeQuery, ignore_references: bool) -> Vec<(usize, u32)> {
+        self.signatures
+            .iter()
+            .filter_map(|signature| {
+                signature
+                    .match_query(query, ignore_references)
+                    .map(|cost| (signature.entry, cost))
+            })
+            .collect()
+    }
+
+    /// Find the signatures producing or consuming the type named by `type_path`, or every entry
+    /// referencing it, returning entry positions with a cost of 0 when the type appears directly
+    /// and 1 when it is nested.
+    ///
+    /// The path may be partially qualified, as in `Config` or `http::Request`; generic arguments
+    /// are ignored.
+    pub fn usage(&self, type_path: &str, usage: TypeUsage) -> Result<Vec<(usize, u32)>> {
+        let segments = parse_item_path(type_path)?;
+
+        let ids: HashSet<Id> = self
+            .names
+            .iter()
+            .filter(|(_, name)| path_matches(&segments, name))
+            .map(|(id, _)| *id)
+            .collect();
+        let cost = |mentions: &mut dyn Iterator<Item = &Mention>| {
+            mentions
+                .filter(|mention| ids.contains(&mention.id))
+                .map(|mention| mention.direct)
+                .reduce(|a, b| a || b)
+                .map(|direct| u32::from(!direct))
+        };
+        let mut found: Vec<(usize, u32)> = self
+            .mentions
+            .iter()
+            .filter_map(|signature| {
+                let cost = match usage {
+                    TypeUsage::Producers => cost(&mut signature.produced.iter()),
+                    TypeUsage::Consumers => cost(&mut signature.consumed.iter()),
+                    TypeUsage::References => cost(
+                        &mut signature
+                            .produced
+                            .iter()
+                            .chain(&signature.consumed)
+                            .chain(&signature.bounds),
+                    ),
+                }?;
+                Some((signature.entry, cost))
+            })
+            .collect();
+        if usage == TypeUsage::References {
+            found.extend(
+                self.references.iter().filter_map(|item| {
+                    cost(&mut item.mentions.iter()).map(|cost| (item.entry, cost))
+                }),
+            );
+        }
+        Ok(found)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shape(text: &str) -> TypeShape {
+        parse_type(text).expect("type parses")
+    }
+
+    fn signature(inputs: &[&str], output: &str) -> TypedSignature {
+        TypedSignature {
+            entry: 0,
+            inputs: inputs.iter().map(|input| shape(input)).collect(),
+            output: shape(output),
+        }
+    }
+
+    fn cost(query: &str, candidate: &TypedSignature, ignore_references: bool) -> Option<u32> {
+        let query = TypeQuery::parse(query)
+            .expect("query parses")
+            .expect("query is not empty");
+        candidate.match_query(&query, ignore_references)
+    }
+
+    /// A candidate generic parameter named `name` with the given bounds.
+    fn generic(name: &str, bounds: &[&str]) -> TypeShape {
+        TypeShape::Var {
+            name: name.to_string(),
+            bounds: bounds.iter().map(|bound| shape(bound)).collect(),
+        }
+    }
+
+    #[test]
+    fn splits_at_top_level_only() {
+        assert_eq!(
+            split_top_level("HashMap<K, V>, impl Fn(u8) -> u8 -> (u8)", "->"),
+            vec!["HashMap<K, V>, impl Fn(u8) -> u8 ", " (u8)"]
+        );
+        assert_eq!(
+            split_top_level("HashMap<K, V>, &str", ","),
+            vec!["HashMap<K, V>", " &str"]
+        );
+    }
+
+    #[test]
+    fn parses_query_sides() {
+        let query = TypeQuery::parse("&str, usize -> Result<_, _>")
+            .unwrap()
+            .unwrap();
+        assert_eq!(query.inputs.as_ref().map(Vec::len), Some(2));
+        assert!(query.output.is_some());
+
+        let output_only = TypeQuery::parse("Vec<u8>").unwrap().unwrap();
+        assert!(output_only.inputs.is_none());
+
+        let inputs_only = TypeQuery::parse("&str ->").unwrap().unwrap();
+        assert!(inputs_only.output.is_none());
+
+        assert!(TypeQuery::parse("  ").unwrap().is_none());
+        assert!(TypeQuery::parse("u8 -> u8 -> u8").is_err());
+        assert!(TypeQuery::parse("Vec<").is_err());
+    }
+
+    #[test]
+    fn matches_concrete_signatures() {
+        let to_text = signature(&["Vec<u8>"], "String");
+        assert_eq!(cost("Vec<u8> -> String", &to_text, false), Some(0));
+        assert_eq!(cost("Vec<u16> -> String", &to_text, false), None);
+        assert_eq!(cost("-> String", &to_text, false), Some(0));
+        assert_eq!(cost("Vec -> String", &to_text, false), Some(0));
+        assert_eq!(cost("std::string::String", &to_text, false), None);
+
+        let parse = signature(&["&str", "u32"], "Result<Vec<u8>, String>");
+        assert_eq!(cost("&str -> Result<_, _>", &parse, false), Some(1));
+    }
+
+    #[test]
+    fn references_are_optionally_ignored() {
+        let first = signature(&["&[u8]"], "Option<u8>");
+        assert_eq!(cost("[u8] -> Option<u8>", &first, false), None);
+        assert_eq!(cost("[u8] -> Option<u8>", &first, true), Some(1));
+        assert_eq!(cost("&[u8] -> Option<u8>", &first, true), Some(0));
+    }
+
+    #[test]
+    fn query_variables_bind_consistently() {
+        let wrap = signature(&["u8"], "Vec<u8>");
+        assert_eq!(cost("T -> Vec<T>", &wrap, false), Some(0));
+        assert_eq!(cost("T -> Option<T>", &wrap, false), None);
+        let convert = signature(&["u8"], "Vec<u16>");
+        assert_eq!(cost("T -> Vec<T>", &convert, false), None);
+    }
+
+    #[test]
+    fn generics_unify_through_bounds() {
+        let collect = TypedSignature {
+            entry: 0,
+            inputs: vec![generic("I", &["Iterator<Item = u32>"])],
+            output: shape("Vec<u32>"),
+        };
+        assert_eq!(
+            cost("Iterator<Item = T> -> Vec<T>", &collect, false),
+            Some(COST_BOUND)
+        );
+        assert_eq!(
+            cost("impl Iterator<Item = u32> -> Vec<u32>", &collect, false),
+            Some(COST_BOUND)
+        );
+        assert_eq!(
+            cost("String -> Vec<u32>", &collect, false),
+            Some(COST_GENERIC + COST_BOUND)
+        );
+
+        let identity = TypedSignature {
+            entry: 0,
+            inputs: vec![generic("T", &[])],
+            output: generic("T", &[]),
+        };
+        assert_eq!(cost("u8 -> u8", &identity, false), Some(COST_GENERIC));
+        assert_eq!(cost("u8 -> u16", &identity, false), None);
+    }
+
+    #[test]
+    fn unmentioned_inputs_add_cost() {
+        let parse = signature(&["&str", "u32"], "Result<Vec<u8>, String>");
+        let exact = cost("&str, u32 -> Result<_, _>", &parse, false);
+        let partial = cost("&str -> Result<_, _>", &parse, false);
+        assert!(exact < partial);
+        assert_eq!(cost("u32, &str -> _", &parse, false), Some(0));
+        assert_eq!(cost("u32, u32 -> _", &parse, false), None);
+    }
+}
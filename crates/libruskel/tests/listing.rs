@@ -3,7 +3,12 @@
 
 mod utils;
 
-use libruskel::{Ruskel, SearchDomain, SearchItemKind, SearchOptions};
+use std::collections::HashSet;
+
+use libruskel::{
+    ImplOrigin, ImplQuery, ImplResponse, ListOptions, Ruskel, SearchDomain, SearchItemKind,
+    SearchMode, SearchOptions,
+};
 use pretty_assertions::assert_eq;
 use utils::create_test_crate;
 
@@ -23,7 +28,7 @@ fn list_respects_visibility_flags() {
     let ruskel = Ruskel::new().with_offline(true).with_silent(true);
 
     let public_items = ruskel
-        .list(&target, false, false, Vec::new(), false, None)
+        .list(&target, false, false, Vec::new(), &ListOptions::all(false))
         .unwrap();
     let public_paths: Vec<String> = public_items.into_iter().map(|item| item.path).collect();
 
@@ -43,7 +48,7 @@ fn list_respects_visibility_flags() {
     );
 
     let items_with_private = ruskel
-        .list(&target, false, false, Vec::new(), true, None)
+        .list(&target, false, false, Vec::new(), &ListOptions::all(true))
         .unwrap();
     let private_paths: Vec<String> = items_with_private
         .iter()
@@ -75,7 +80,7 @@ fn list_omits_nameless_use_items() {
     let ruskel = Ruskel::new().with_offline(true).with_silent(true);
 
     let items = ruskel
-        .list(&target, false, false, Vec::new(), false, None)
+        .list(&target, false, false, Vec::new(), &ListOptions::all(false))
         .unwrap();
 
     assert!(items.iter().any(|item| item.path.ends_with("::exported")));
@@ -102,7 +107,13 @@ fn list_applies_search_filters() {
     options.include_private = false;
 
     let filtered = ruskel
-        .list(&target, false, false, Vec::new(), false, Some(&options))
+        .list(
+            &target,
+            false,
+            false,
+            Vec::new(),
+            &ListOptions::matching(options),
+        )
         .unwrap();
 
     let filtered_pairs: Vec<(String, String)> = filtered
@@ -118,3 +129,601 @@ fn list_applies_search_filters() {
         )]
     );
 }
+
+#[test]
+fn list_supports_regex_queries_with_highlights() {
+    let source = r#"
+        pub fn try_open() -> Result<(), std::io::Error> { Ok(()) }
+        pub fn retry() {}
+
+        pub struct Buffer;
+
+        impl Buffer {
+            pub fn as_mut(&mut self) -> &mut Self { self }
+            pub fn as_ref(&self) -> &Self { self }
+        }
+    "#;
+
+    let (_temp_dir, target) = create_test_crate(source, false);
+    let ruskel = Ruskel::new().with_offline(true).with_silent(true);
+
+    let mut options = SearchOptions::new("^try_");
+    options.domains = SearchDomain::NAMES;
+    options.mode = SearchMode::Regex;
+    let listed = ruskel
+        .list(
+            &target,
+            false,
+            false,
+            Vec::new(),
+            &ListOptions::matching(options),
+        )
+        .unwrap();
+    let try_open = listed
+        .iter()
+        .find(|item| item.path == "dummy_crate::try_open")
+        .expect("try_open should match");
+    assert_eq!(try_open.highlights, vec![13..17]);
+    assert!(!listed.iter().any(|item| item.path.ends_with("retry")));
+
+    let mut options = SearchOptions::new(r"regex:fn .*_mut\(");
+    options.domains = SearchDomain::SIGNATURES;
+    let listed = ruskel
+        .list(
+            &target,
+            false,
+            false,
+            Vec::new(),
+            &ListOptions::matching(options),
+        )
+        .unwrap();
+    let paths: Vec<&str> = listed.iter().map(|item| item.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "dummy_crate::Buffer::as_mut",
+            "dummy_crate::Buffer::BorrowMut<T>::borrow_mut",
+        ]
+    );
+    assert!(listed[0].highlights.is_empty());
+
+    let mut options = SearchOptions::new(r"regex:-> Result<.*io::Error");
+    options.domains = SearchDomain::SIGNATURES;
+    let listed = ruskel
+        .list(
+            &target,
+            false,
+            false,
+            Vec::new(),
+            &ListOptions::matching(options),
+        )
+        .unwrap();
+    let paths: Vec<&str> = listed.iter().map(|item| item.path.as_str()).collect();
+    assert_eq!(paths, vec!["dummy_crate::try_open"]);
+}
+
+#[test]
+fn list_supports_structured_queries() {
+    let source = r#"
+        pub mod sync {
+            /// A lock that is cancel safe.
+            pub struct Mutex;
+            /// A cancel safe channel.
+            pub enum Channel { Open }
+            /// Cancel safe lock.
+            #[deprecated]
+            pub fn lock() {}
+        }
+
+        /// Not in the sync module, but cancel safe.
+        pub struct Outside;
+    "#;
+
+    let (_temp_dir, target) = create_test_crate(source, false);
+    let ruskel = Ruskel::new().with_offline(true).with_silent(true);
+
+    let mut options = SearchOptions::new(r#"path:sync::* kind:struct,enum doc:"cancel safe""#);
+    options.mode = SearchMode::Structured;
+    let listed = ruskel
+        .list(
+            &target,
+            false,
+            false,
+            Vec::new(),
+            &ListOptions::matching(options.clone()),
+        )
+        .unwrap();
+    let mut paths: Vec<&str> = listed.iter().map(|item| item.path.as_str()).collect();
+    paths.sort_unstable();
+    assert_eq!(
+        paths,
+        vec!["dummy_crate::sync::Channel", "dummy_crate::sync::Mutex"]
+    );
+
+    options.query = "doc:cancel -deprecated -kind:struct".into();
+    let listed = ruskel
+        .list(
+            &target,
+            false,
+            false,
+            Vec::new(),
+            &ListOptions::matching(options),
+        )
+        .unwrap();
+    let paths: Vec<&str> = listed.iter().map(|item| item.path.as_str()).collect();
+    assert_eq!(paths, vec!["dummy_crate::sync::Channel"]);
+}
+
+#[test]
+fn list_filters_by_item_kind() {
+    let source = r#"
+        pub trait Reader {
+            fn read(&mut self) -> usize;
+        }
+
+        pub struct File;
+
+        impl File {
+            pub fn read(&mut self) -> usize { 0 }
+            pub fn open() -> Self { File }
+        }
+
+        pub fn read_all() {}
+    "#;
+
+    let (_temp_dir, target) = create_test_crate(source, false);
+    let ruskel = Ruskel::new().with_offline(true).with_silent(true);
+
+    let kinds = HashSet::from([SearchItemKind::Trait]);
+    let listed = ruskel
+        .list(
+            &target,
+            false,
+            false,
+            Vec::new(),
+            &ListOptions {
+                kinds,
+                ..ListOptions::all(false)
+            },
+        )
+        .unwrap();
+    let paths: Vec<&str> = listed.iter().map(|item| item.path.as_str()).collect();
+    assert_eq!(paths, vec!["dummy_crate::Reader"]);
+
+    let mut options = SearchOptions::new("read");
+    options.domains = SearchDomain::NAMES;
+    options.kinds = HashSet::from([SearchItemKind::Method, SearchItemKind::TraitMethod]);
+    let listed = ruskel
+        .list(
+            &target,
+            false,
+            false,
+            Vec::new(),
+            &ListOptions::matching(options),
+        )
+        .unwrap();
+    let mut paths: Vec<&str> = listed.iter().map(|item| item.path.as_str()).collect();
+    paths.sort_unstable();
+    assert_eq!(
+        paths,
+        vec!["dummy_crate::File::read", "dummy_crate::Reader::read"]
+    );
+}
+
+#[test]
+fn list_supports_type_queries() {
+    let source = r#"
+        pub struct Config;
+
+        pub fn parse_all(input: &str) -> Result<Vec<u8>, String> { Ok(input.bytes().collect()) }
+        pub fn collect<I: Iterator<Item = u32>>(iter: I) -> Vec<u32> { iter.collect() }
+        pub fn first<T: Clone>(items: &[T]) -> Option<T> { items.first().cloned() }
+        pub fn to_text(bytes: Vec<u8>) -> String { String::from_utf8(bytes).unwrap() }
+        pub fn label(name: &str) -> String { name.to_string() }
+
+        impl Config {
+            pub fn load(path: &str, strict: bool) -> Result<Self, String> { Ok(Config) }
+        }
+    "#;
+
+    let (_temp_dir, target) = create_test_crate(source, false);
+    let ruskel = Ruskel::new().with_offline(true).with_silent(true);
+    let search = |query: &str, ignore_references: bool| -> Vec<String> {
+        let mut options = SearchOptions::new(query);
+        options.mode = SearchMode::Types;
+        options.ignore_references = ignore_references;
+        ruskel
+            .list(
+                &target,
+                false,
+                false,
+                Vec::new(),
+                &ListOptions::matching(options),
+            )
+            .unwrap()
+            .into_iter()
+            .map(|item| item.path)
+            .collect()
+    };
+
+    // Blanket generics such as `TryFrom::try_from` also fit, but rank below concrete matches.
+    let fallible = search("&str -> Result<_, _>", false);
+    assert_eq!(
+        fallible[..2],
+        ["dummy_crate::parse_all", "dummy_crate::Config::load"]
+    );
+    assert!(fallible.contains(&"dummy_crate::Config::TryFrom<U>::try_from".to_string()));
+    assert_eq!(
+        search("Iterator<Item = T> -> Vec<T>", false),
+        vec!["dummy_crate::collect"]
+    );
+    assert_eq!(
+        search("Vec<u8> -> String", false),
+        vec!["dummy_crate::to_text"]
+    );
+    assert_eq!(
+        search("-> Result<Config, _>", false)[0],
+        "dummy_crate::Config::load"
+    );
+    assert!(search("[u32] -> Option<u32>", false).is_empty());
+    assert_eq!(
+        search("[u32] -> Option<u32>", true),
+        vec!["dummy_crate::first"]
+    );
+}
+
+#[test]
+fn list_finds_producers_and_consumers() {
+    let source = r#"
+        pub struct Config;
+        pub struct Server;
+
+        impl Config {
+            pub fn new() -> Self { Config }
+            pub fn strict(self) -> Self { self }
+        }
+
+        impl Default for Config {
+            fn default() -> Self { Config }
+        }
+
+        pub fn load(path: &str) -> Result<Config, String> { Ok(Config) }
+        pub fn serve(config: &Config) -> Server { Server }
+        pub fn serve_all(configs: Vec<Config>) -> Vec<Server> { Vec::new() }
+    "#;
+
+    let (_temp_dir, target) = create_test_crate(source, false);
+    let ruskel = Ruskel::new().with_offline(true).with_silent(true);
+    let list = |options: &SearchOptions| -> Vec<String> {
+        ruskel
+            .list(
+                &target,
+                false,
+                false,
+                Vec::new(),
+                &ListOptions::matching(options.clone()),
+            )
+            .unwrap()
+            .into_iter()
+            .map(|item| item.path)
+            .collect()
+    };
+
+    let mut producers = list(&SearchOptions::producers("Config"));
+    // Direct producers rank above `load`, which wraps the type in a `Result`.
+    assert_eq!(producers.pop().as_deref(), Some("dummy_crate::load"));
+    producers.sort_unstable();
+    assert_eq!(
+        producers,
+        vec![
+            "dummy_crate::Config::Default::default",
+            "dummy_crate::Config::new",
+            "dummy_crate::Config::strict",
+        ]
+    );
+
+    assert_eq!(
+        list(&SearchOptions::consumers("dummy_crate::Config")),
+        vec!["dummy_crate::serve", "dummy_crate::serve_all"]
+    );
+    assert_eq!(
+        list(&SearchOptions::producers("Server")),
+        vec!["dummy_crate::serve", "dummy_crate::serve_all"]
+    );
+}
+
+#[test]
+fn trait_impls_include_blanket_and_auto_impls() {
+    let source = r#"
+        pub trait Shape {
+            fn area(&self) -> f64;
+        }
+
+        pub trait Named {}
+
+        impl<T: Shape> Named for T {}
+
+        #[derive(Clone)]
+        pub struct Circle {
+            pub radius: f64,
+        }
+
+        impl Shape for Circle {
+            fn area(&self) -> f64 { self.radius }
+        }
+
+        pub struct Square;
+
+        impl Shape for u8 {
+            fn area(&self) -> f64 { 0.0 }
+        }
+
+        struct Hidden;
+
+        impl Shape for Hidden {
+            fn area(&self) -> f64 { 0.0 }
+        }
+    "#;
+
+    let (_temp_dir, target) = create_test_crate(source, false);
+    let ruskel = Ruskel::new().with_offline(true).with_silent(true);
+    let find = |private: bool, query: ImplQuery<'_>| {
+        ruskel
+            .trait_impls(&target, false, false, Vec::new(), private, query)
+            .unwrap()
+    };
+    let describe = |response: &ImplResponse| -> Vec<String> {
+        response
+            .impls
+            .iter()
+            .map(|found| format!("{} {}", found.origin.label(), found.type_path))
+            .collect()
+    };
+
+    let implementors = find(false, ImplQuery::Implementors("Shape"));
+    assert_eq!(
+        describe(&implementors),
+        vec!["impl dummy_crate::Circle", "impl u8"]
+    );
+    assert!(implementors.rendered.contains("impl Shape for Circle"));
+    assert!(implementors.rendered.contains("pub radius: f64"));
+    assert!(!implementors.rendered.contains("Square"));
+
+    let private = find(true, ImplQuery::Implementors("dummy_crate::Shape"));
+    assert!(describe(&private).contains(&"impl dummy_crate::Hidden".to_string()));
+
+    let traits = find(false, ImplQuery::TraitsOf("Circle"));
+    let paths: Vec<(ImplOrigin, &str)> = traits
+        .impls
+        .iter()
+        .map(|found| (found.origin, found.trait_path.as_str()))
+        .collect();
+    assert!(paths.contains(&(ImplOrigin::Explicit, "core::clone::Clone")));
+    assert!(paths.contains(&(ImplOrigin::Explicit, "dummy_crate::Shape")));
+    assert!(paths.contains(&(ImplOrigin::Blanket, "dummy_crate::Named")));
+    assert!(paths.contains(&(ImplOrigin::Auto, "core::marker::Send")));
+    assert!(traits.rendered.contains("impl Send for Circle"));
+
+    assert!(
+        ruskel
+            .trait_impls(
+                &target,
+                false,
+                false,
+                Vec::new(),
+                false,
+                ImplQuery::Implementors("Missing"),
+            )
+            .is_err()
+    );
+}
+
+#[test]
+fn methods_follow_deref_chains() {
+    let source = r#"
+        use std::ops::Deref;
+
+        pub trait Shape {
+            fn area(&self) -> f64;
+
+            fn describe(&self) -> String { String::new() }
+        }
+
+        pub struct Inner;
+
+        impl Inner {
+            pub fn inner_only(&self) {}
+            pub fn shared(&self) {}
+            pub fn build() -> Self { Inner }
+        }
+
+        impl Shape for Inner {
+            fn area(&self) -> f64 { 0.0 }
+        }
+
+        pub struct Outer(Inner);
+
+        impl Outer {
+            /// Shadows `Inner::shared`.
+            pub fn shared(&self) {}
+            fn hidden(&self) {}
+        }
+
+        impl Deref for Outer {
+            type Target = Inner;
+            fn deref(&self) -> &Inner { &self.0 }
+        }
+    "#;
+
+    let (_temp_dir, target) = create_test_crate(source, false);
+    let ruskel = Ruskel::new().with_offline(true).with_silent(true);
+    let response = ruskel
+        .methods(&target, false, false, Vec::new(), false, "Outer")
+        .unwrap();
+    let methods: Vec<(String, String)> = response
+        .methods
+        .iter()
+        .map(|method| (method.name.clone(), method.origin()))
+        .collect();
+
+    assert_eq!(response.type_path, "dummy_crate::Outer");
+    assert_eq!(
+        methods,
+        vec![
+            ("shared".to_string(), "inherent".to_string()),
+            ("deref".to_string(), "from trait Deref".to_string()),
+            (
+                "inner_only".to_string(),
+                "via Deref<Target = Inner>".to_string()
+            ),
+            (
+                "area".to_string(),
+                "from trait Shape via Deref<Target = Inner>".to_string()
+            ),
+            (
+                "describe".to_string(),
+                "from trait Shape via Deref<Target = Inner>".to_string()
+            ),
+        ]
+    );
+    assert!(response.rendered.contains("// via Deref<Target = Inner>"));
+    assert!(response.rendered.contains("pub fn inner_only(&self) {}"));
+
+    let private = ruskel
+        .methods(&target, false, false, Vec::new(), true, "Outer")
+        .unwrap();
+    assert!(private.methods.iter().any(|method| method.name == "hidden"));
+}
+
+#[test]
+fn list_finds_type_references() {
+    let source = r#"
+        use std::sync::Arc;
+
+        pub struct Runtime;
+        pub struct Inner;
+
+        pub struct Holder {
+            pub inner: Arc<Inner>,
+            pub count: u32,
+        }
+
+        pub enum State {
+            Idle,
+            Busy(Inner),
+        }
+
+        pub trait Spawn {
+            fn spawn(&self, runtime: &Runtime);
+        }
+
+        pub type Shared = Arc<Inner>;
+
+        pub fn run(runtime: &Runtime) {}
+        pub fn bounded<T: AsRef<Inner>>(value: T) {}
+
+        impl Runtime {
+            pub fn block_on(&self) {}
+        }
+    "#;
+
+    let (_temp_dir, target) = create_test_crate(source, false);
+    let ruskel = Ruskel::new().with_offline(true).with_silent(true);
+    let list = |options: &SearchOptions| -> Vec<String> {
+        let mut paths: Vec<String> = ruskel
+            .list(
+                &target,
+                false,
+                false,
+                Vec::new(),
+                &ListOptions::matching(options.clone()),
+            )
+            .unwrap()
+            .into_iter()
+            .map(|item| item.path)
+            .collect();
+        paths.sort_unstable();
+        paths
+    };
+
+    assert_eq!(
+        list(&SearchOptions::references("Runtime")),
+        vec!["dummy_crate::Spawn::spawn", "dummy_crate::run"]
+    );
+    assert_eq!(
+        list(&SearchOptions::references("dummy_crate::Inner")),
+        vec![
+            "dummy_crate::Holder::inner",
+            "dummy_crate::Shared",
+            "dummy_crate::State::Busy::0",
+            "dummy_crate::bounded",
+        ]
+    );
+}
+
+#[test]
+fn item_describes_docs_and_impls() {
+    let source = r#"
+        pub mod shapes {
+            /// Something with an area.
+            pub trait Shape {
+                fn area(&self) -> f64;
+            }
+
+            /// A round shape.
+            pub struct Circle {
+                pub radius: f64,
+            }
+
+            impl Shape for Circle {
+                fn area(&self) -> f64 { self.radius }
+            }
+
+            pub struct Square;
+        }
+    "#;
+
+    let (_temp_dir, target) = create_test_crate(source, false);
+    let ruskel = Ruskel::new()
+        .with_offline(true)
+        .with_silent(true)
+        .with_frontmatter(false);
+    let item = |path: &str| {
+        ruskel
+            .item(
+                &format!("{target}::{path}"),
+                false,
+                false,
+                Vec::new(),
+                false,
+            )
+            .unwrap()
+    };
+
+    let circle = item("shapes::Circle");
+    assert_eq!(circle.path, "dummy_crate::shapes::Circle");
+    assert_eq!(circle.kind, SearchItemKind::Struct);
+    assert_eq!(circle.docs.as_deref(), Some("A round shape."));
+    assert!(circle.rendered.contains("pub radius: f64"));
+    assert!(!circle.rendered.contains("Square"));
+    let traits: Vec<(ImplOrigin, &str)> = circle
+        .impls
+        .iter()
+        .map(|found| (found.origin, found.trait_path.as_str()))
+        .collect();
+    assert!(traits.contains(&(ImplOrigin::Explicit, "dummy_crate::shapes::Shape")));
+    assert!(traits.contains(&(ImplOrigin::Auto, "core::marker::Send")));
+
+    let shape = item("shapes::Shape");
+    let implementors: Vec<&str> = shape
+        .impls
+        .iter()
+        .map(|found| found.type_path.as_str())
+        .collect();
+    assert_eq!(implementors, vec!["dummy_crate::shapes::Circle"]);
+
+    let error = ruskel
+        .item(&target, false, false, Vec::new(), false)
+        .unwrap_err();
+    assert!(error.to_string().contains("does not name an item"));
+}
@@ -4,6 +4,9 @@ mod utils;
 
 #[cfg(test)]
 mod tests {
+    use libruskel::{FormatBackend, ListOptions, Renderer, Ruskel, SearchOptions};
+    use pretty_assertions::assert_eq;
+
     use super::utils::*;
     #[test]
     fn test_render_constant() {
@@ -376,4 +379,223 @@ mod tests {
             "#,
         );
     }
+
+    #[test]
+    fn test_prettyplease_backend_renders_without_rustfmt() {
+        render(
+            &Renderer::default().with_format_backend(FormatBackend::PrettyPlease),
+            r#"
+                /// Documented function.
+                pub fn documented(value: u32) -> u32 {
+                    value
+                }
+
+                pub struct Point {
+                    pub x: i32,
+                    pub y: i32,
+                }
+            "#,
+            r#"
+                /// Documented function.
+                pub fn documented(value: u32) -> u32 {}
+
+                pub struct Point {
+                    pub x: i32,
+                    pub y: i32,
+                }
+            "#,
+            false,
+        );
+    }
+
+    #[test]
+    fn test_streaming_render_matches_buffered_render() {
+        let crate_data = inspect_crate(
+            r#"
+                //! Crate docs.
+
+                /// Documented function.
+                pub fn documented(value: u32) -> u32 {
+                    value
+                }
+
+                pub struct Point {
+                    pub x: i32,
+                    pub y: i32,
+                }
+
+                pub mod nested {
+                    pub trait Shape {
+                        fn area(&self) -> f64;
+                    }
+                }
+            "#,
+            false,
+            false,
+        );
+        let renderer = Renderer::default();
+
+        let mut streamed = Vec::new();
+        renderer.render_to(&crate_data, &mut streamed).unwrap();
+
+        assert_eq!(
+            String::from_utf8(streamed).unwrap(),
+            renderer.render(&crate_data).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_source_locations_annotate_rendered_and_listed_items() {
+        let (_temp_dir, target) = create_test_crate(
+            "/// Documented function.\npub fn documented() {}\n\npub struct Point;\n\nimpl Point {\n    pub fn origin() -> Self {\n        Point\n    }\n}\n",
+            false,
+        );
+        let ruskel = Ruskel::new()
+            .with_offline(true)
+            .with_silent(true)
+            .with_frontmatter(false)
+            .with_source_locations(true);
+
+        let rendered = ruskel
+            .render(&target, false, false, Vec::new(), false)
+            .unwrap();
+        assert!(rendered.contains("    // src/lib.rs:2\n    /// Documented function.\n"));
+        assert!(rendered.contains("    // src/lib.rs:4\n    pub struct Point;"));
+        assert!(rendered.contains("        // src/lib.rs:7\n        pub fn origin()"));
+
+        let listing = ruskel
+            .list(&target, false, false, Vec::new(), &ListOptions::all(false))
+            .unwrap();
+        let point = listing
+            .iter()
+            .find(|item| item.path == "dummy_crate::Point")
+            .expect("Point should be listed");
+        assert_eq!(
+            point.location.as_ref().map(ToString::to_string).as_deref(),
+            Some("src/lib.rs:4")
+        );
+    }
+
+    #[test]
+    fn test_with_source_embeds_bodies_of_targeted_functions() {
+        let (_temp_dir, target) = create_test_crate(
+            "pub fn untouched() -> u32 {\n    1\n}\n\npub struct Point;\n\nimpl Point {\n    pub fn origin() -> Self {\n        let point = Point;\n        point\n    }\n}\n",
+            false,
+        );
+        let ruskel = Ruskel::new()
+            .with_offline(true)
+            .with_silent(true)
+            .with_frontmatter(false)
+            .with_item_source(true);
+
+        let rendered = ruskel
+            .render(
+                &format!("{target}::Point::origin"),
+                false,
+                false,
+                Vec::new(),
+                false,
+            )
+            .unwrap();
+        assert!(rendered.contains("// ruskel: original source from src/lib.rs:8"));
+        assert!(rendered.contains("let point = Point;"));
+
+        let whole = ruskel
+            .render(&target, false, false, Vec::new(), false)
+            .unwrap();
+        assert!(!whole.contains("ruskel: original source"));
+        assert!(whole.contains("pub fn untouched() -> u32 {}"));
+    }
+
+    #[test]
+    fn test_with_source_embeds_bodies_of_trait_default_methods() {
+        let (_temp_dir, target) = create_test_crate(
+            "pub trait Shape {\n    fn sides(&self) -> u32;\n\n    fn corners(&self) -> u32 {\n        let sides = self.sides();\n        sides\n    }\n}\n",
+            false,
+        );
+        let ruskel = Ruskel::new()
+            .with_offline(true)
+            .with_silent(true)
+            .with_frontmatter(false)
+            .with_item_source(true);
+
+        let response = ruskel
+            .search(
+                &target,
+                false,
+                false,
+                Vec::new(),
+                &SearchOptions::new("corners"),
+            )
+            .unwrap();
+        assert!(
+            response
+                .rendered
+                .contains("// ruskel: original source from src/lib.rs:4")
+        );
+        assert!(response.rendered.contains("let sides = self.sides();"));
+    }
+
+    #[test]
+    fn test_closure_includes_mentioned_types() {
+        let source = r#"
+            pub struct Request {
+                pub body: Body,
+            }
+
+            pub enum Body {
+                Empty,
+                Chunked(Chunk),
+            }
+
+            pub struct Chunk;
+
+            pub struct Response;
+
+            pub struct Unrelated;
+
+            pub struct Client;
+
+            impl Client {
+                pub fn new() -> Self { Client }
+                pub fn execute(&self, request: Request) -> Response { Response }
+            }
+        "#;
+        let (_temp_dir, target) = create_test_crate(source, false);
+        let ruskel = Ruskel::new()
+            .with_offline(true)
+            .with_silent(true)
+            .with_frontmatter(false);
+        let closure = |depth: Option<usize>| {
+            ruskel
+                .render_closure(
+                    &format!("{target}::Client::execute"),
+                    false,
+                    false,
+                    Vec::new(),
+                    false,
+                    depth,
+                )
+                .unwrap()
+        };
+
+        let full = closure(None);
+        assert!(full.contains("pub fn execute(&self, request: Request) -> Response {}"));
+        assert!(full.contains("pub body: Body,"));
+        assert!(full.contains("Chunked(Chunk),"));
+        assert!(full.contains("pub struct Chunk;"));
+        assert!(full.contains("pub struct Response;"));
+        assert!(!full.contains("Unrelated"));
+        assert!(!full.contains("pub fn new()"));
+
+        let shallow = closure(Some(1));
+        assert!(shallow.contains("pub struct Request"));
+        assert!(!shallow.contains("enum Body"));
+
+        assert!(
+            ruskel
+                .render_closure(&target, false, false, Vec::new(), false, None)
+                .is_err()
+        );
+    }
 }
@@ -0,0 +1,209 @@
+//! Integration tests covering paged skeletons and search results.
+#![allow(clippy::tests_outside_test_module)]
+
+mod utils;
+
+use libruskel::{PageRequest, Ruskel, SearchDomain, SearchItemKind, SearchOptions};
+use pretty_assertions::assert_eq;
+use utils::create_test_crate;
+
+#[test]
+fn render_pages_split_at_top_level_items() {
+    let source = r#"
+        pub mod first {
+            pub fn inner() {}
+        }
+
+        pub fn second() {}
+
+        pub struct Third {
+            pub field: u32,
+        }
+    "#;
+
+    let (_temp_dir, target) = create_test_crate(source, false);
+    let ruskel = Ruskel::new()
+        .with_offline(true)
+        .with_silent(true)
+        .with_frontmatter(false);
+    let request = PageRequest {
+        max_items: Some(2),
+        ..PageRequest::default()
+    };
+
+    let first = ruskel
+        .render_page(&target, false, false, Vec::new(), false, &request)
+        .unwrap();
+    let paths: Vec<&str> = first.items.iter().map(|item| item.path.as_str()).collect();
+    assert_eq!(paths, ["dummy_crate::first", "dummy_crate::second"]);
+    assert_eq!(first.items[0].kind, SearchItemKind::Module);
+    assert_eq!(first.remaining.len(), 1);
+    assert_eq!(first.next(), Some(2));
+    assert_eq!(first.total(), 3);
+    assert_eq!(
+        first.rendered,
+        "pub mod dummy_crate {\n    pub mod first {\n        pub fn inner() {}\n    }\n\n    pub fn second() {}\n}\n"
+    );
+
+    let second = ruskel
+        .render_page(
+            &target,
+            false,
+            false,
+            Vec::new(),
+            false,
+            &PageRequest {
+                start: 2,
+                ..request
+            },
+        )
+        .unwrap();
+    assert_eq!(second.items[0].path, "dummy_crate::Third");
+    assert_eq!(second.next(), None);
+    assert_eq!(
+        second.rendered,
+        "pub mod dummy_crate {\n    pub struct Third {\n        pub field: u32,\n    }\n}\n"
+    );
+
+    // A page always holds one item, even when it alone exceeds the character limit.
+    let tiny = ruskel
+        .render_page(
+            &target,
+            false,
+            false,
+            Vec::new(),
+            false,
+            &PageRequest {
+                max_chars: Some(1),
+                ..PageRequest::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(tiny.items.len(), 1);
+    assert_eq!(tiny.next(), Some(1));
+    assert_eq!(tiny.total(), 3);
+
+    // Character-limited pages walk through every item once.
+    let mut request = PageRequest {
+        max_chars: Some(80),
+        ..PageRequest::default()
+    };
+    let mut paths = Vec::new();
+    loop {
+        let page = ruskel
+            .render_page(&target, false, false, Vec::new(), false, &request)
+            .unwrap();
+        assert_eq!(page.total(), 3);
+        paths.extend(page.items.iter().map(|item| item.path.clone()));
+        match page.next() {
+            Some(start) => request.start = start,
+            None => break,
+        }
+    }
+    assert_eq!(
+        paths,
+        [
+            "dummy_crate::first",
+            "dummy_crate::second",
+            "dummy_crate::Third"
+        ]
+    );
+}
+
+#[test]
+fn render_pages_of_a_module_target_split_within_the_module() {
+    let source = r#"
+        pub mod outer {
+            pub mod inner {
+                pub struct Alpha;
+                pub struct Beta;
+            }
+        }
+
+        pub struct Elsewhere;
+    "#;
+
+    let (_temp_dir, target) = create_test_crate(source, false);
+    let ruskel = Ruskel::new()
+        .with_offline(true)
+        .with_silent(true)
+        .with_frontmatter(false);
+    let page = ruskel
+        .render_page(
+            &format!("{target}::outer::inner"),
+            false,
+            false,
+            Vec::new(),
+            false,
+            &PageRequest {
+                max_items: Some(1),
+                ..PageRequest::default()
+            },
+        )
+        .unwrap();
+
+    assert_eq!(page.items[0].path, "dummy_crate::outer::inner::Alpha");
+    assert_eq!(page.remaining[0].path, "dummy_crate::outer::inner::Beta");
+    assert_eq!(
+        page.rendered,
+        "pub mod dummy_crate {\n    pub mod outer {\n        pub mod inner {\n            pub struct Alpha;\n        }\n    }\n}\n"
+    );
+}
+
+#[test]
+fn search_pages_split_between_matches() {
+    let source = r#"
+        pub struct WidgetOne;
+        pub struct WidgetTwo;
+        pub struct WidgetThree;
+        pub struct Gadget;
+    "#;
+
+    let (_temp_dir, target) = create_test_crate(source, false);
+    let ruskel = Ruskel::new()
+        .with_offline(true)
+        .with_silent(true)
+        .with_frontmatter(false);
+    let mut options = SearchOptions::new("widget");
+    options.domains = SearchDomain::NAMES;
+
+    let request = PageRequest {
+        max_items: Some(2),
+        ..PageRequest::default()
+    };
+    let first = ruskel
+        .search_page(&target, false, false, Vec::new(), &options, &request)
+        .unwrap();
+    assert_eq!(first.items.len(), 2);
+    assert_eq!(first.remaining.len(), 1);
+    assert_eq!(first.next(), Some(2));
+    for result in &first.items {
+        assert!(
+            first
+                .rendered
+                .contains(&format!("pub struct {};", result.raw_name))
+        );
+    }
+    assert!(
+        !first
+            .rendered
+            .contains(&first.remaining[0].path["dummy_crate::".len()..])
+    );
+
+    let second = ruskel
+        .search_page(
+            &target,
+            false,
+            false,
+            Vec::new(),
+            &options,
+            &PageRequest {
+                start: 2,
+                ..request
+            },
+        )
+        .unwrap();
+    assert_eq!(second.items[0].path_string, first.remaining[0].path);
+    assert_eq!(second.next(), None);
+    assert!(!second.rendered.contains("Gadget"));
+}
@@ -0,0 +1,113 @@
+//! Integration tests for reading crates through custom sources.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use libruskel::{
+    CrateRead, CrateReadOptions, CrateSource, FixtureSource, ResolvedTarget, Result, Ruskel,
+};
+use tempfile::tempdir;
+
+/// Directory holding the fixture crates, with their rustdoc JSON in `json/`.
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../../fixtures")
+}
+
+/// A source counting the crates it reads before passing them on to the fixtures.
+#[derive(Debug)]
+struct CountingSource {
+    /// Source that actually reads the crates.
+    inner: FixtureSource,
+    /// Number of reads so far.
+    reads: AtomicUsize,
+}
+
+impl CrateSource for CountingSource {
+    fn read_crate(&self, target: &ResolvedTarget, options: &CrateReadOptions) -> Result<CrateRead> {
+        self.reads.fetch_add(1, Ordering::SeqCst);
+        self.inner.read_crate(target, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixture_source_renders_without_building() -> Result<()> {
+        let target = fixtures_dir().join("widgets");
+        let target = target.to_str().expect("utf-8 path");
+        let ruskel = Ruskel::new()
+            .with_silent(true)
+            .with_offline(true)
+            .with_source(Arc::new(FixtureSource::new(fixtures_dir().join("json"))));
+
+        let public = ruskel.render(target, false, false, Vec::new(), false)?;
+        assert!(public.contains("pub fn build(self) -> Widget"), "{public}");
+        assert!(!public.contains("mod layout"), "{public}");
+
+        let private = ruskel.render(target, false, false, Vec::new(), true)?;
+        assert!(
+            private.contains("fn grow(&mut self, amount: u32)"),
+            "{private}"
+        );
+
+        let info = ruskel.crate_info(target, false, false, Vec::new())?;
+        assert_eq!(info.name, "widgets");
+        assert_eq!(info.version.as_deref(), Some("0.3.1"));
+        Ok(())
+    }
+
+    #[test]
+    fn fixture_source_reports_missing_json() -> Result<()> {
+        let temp_dir = tempdir()?;
+        fs::create_dir_all(temp_dir.path().join("src"))?;
+        fs::write(temp_dir.path().join("src/lib.rs"), "pub struct Gadget;")?;
+        fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"gadget-kit\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        )?;
+
+        let ruskel = Ruskel::new()
+            .with_silent(true)
+            .with_source(Arc::new(FixtureSource::new(fixtures_dir().join("json"))));
+        let target = temp_dir.path().to_str().expect("utf-8 path");
+        let error = ruskel
+            .render(target, false, false, Vec::new(), false)
+            .expect_err("no fixture exists for the crate");
+
+        assert!(
+            error
+                .to_string()
+                .contains("no rustdoc JSON for 'gadget_kit'"),
+            "{error}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn crate_cache_reads_through_custom_sources() -> Result<()> {
+        let source = Arc::new(CountingSource {
+            inner: FixtureSource::new(fixtures_dir().join("json")),
+            reads: AtomicUsize::new(0),
+        });
+        let ruskel = Ruskel::new()
+            .with_silent(true)
+            .with_source(source.clone())
+            .with_crate_cache(2);
+        let target = format!("{}::shapes", fixtures_dir().join("widgets").display());
+
+        for _ in 0..2 {
+            let output = ruskel.render(&target, false, false, Vec::new(), false)?;
+            assert!(output.contains("pub enum Shape {"), "{output}");
+        }
+        assert_eq!(source.reads.load(Ordering::SeqCst), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,636 @@
+//! Streamable HTTP transport for the MCP server.
+//!
+//! Clients POST JSON-RPC messages to [`MCP_PATH`]. An `initialize` request opens a session,
+//! named by the `Mcp-Session-Id` response header, which every later request sends back. Each
+//! session runs its own server connection over an in-memory pipe, so sessions keep their own
+//! client state, such as roots and in-flight requests, while sharing the crate cache.
+//!
+//! Requests are answered with a server-sent event stream carrying the response and the messages
+//! the server sends while handling it. `GET` opens a stream for messages outside of requests, and
+//! `DELETE` ends the session. Idle sessions are closed periodically, and new sessions are refused
+//! while [`MAX_SESSIONS`] are open.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    io,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    Json, Router,
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, header},
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
+    routing::post,
+};
+use futures::{
+    StreamExt,
+    channel::mpsc::{UnboundedReceiver, UnboundedSender, unbounded},
+};
+use libruskel::Ruskel;
+use serde_json::Value;
+use tmcp::Server;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream, WriteHalf, duplex, split},
+    net::TcpListener,
+    sync::Mutex as AsyncMutex,
+    task::JoinHandle,
+    time::interval,
+};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::server::{RuskelServer, RuskelServerDefaults};
+
+/// Path of the MCP endpoint.
+pub const MCP_PATH: &str = "/mcp";
+
+/// Header naming the session a request belongs to.
+const SESSION_HEADER: &str = "mcp-session-id";
+
+/// Sessions left idle this long, with no request or stream open, are closed.
+const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// How often idle sessions are looked for.
+const SESSION_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Most sessions open at once; `initialize` requests beyond it are refused.
+const MAX_SESSIONS: usize = 64;
+
+/// Buffer size of the pipe between the HTTP layer and a session's server connection.
+const PIPE_CAPACITY: usize = 1024 * 1024;
+
+/// Access control for the streamable HTTP transport.
+#[derive(Debug, Clone, Default)]
+pub struct HttpOptions {
+    /// Bearer token clients must send in the `Authorization` header. Any client may connect
+    /// when it is `None`.
+    pub token: Option<String>,
+    /// Browser origins allowed to call the server, e.g. `http://localhost:3000`. Requests
+    /// carrying any other `Origin` header are rejected; requests without one are allowed.
+    pub allowed_origins: Vec<String>,
+}
+
+/// Serve MCP over streamable HTTP on `listener` until `shutdown` completes.
+///
+/// Fails without serving when `listener` is bound to a non-loopback address and `options` sets
+/// no bearer token, since anyone on the network could then use the server.
+pub async fn serve_http(
+    ruskel: Ruskel,
+    defaults: RuskelServerDefaults,
+    options: HttpOptions,
+    listener: TcpListener,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> io::Result<()> {
+    let addr = listener.local_addr()?;
+    if options.token.is_none() && !addr.ip().is_loopback() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("refusing to serve MCP on non-loopback address {addr} without a bearer token"),
+        ));
+    }
+    let cors = CorsLayer::new()
+        .allow_origin(AllowOrigin::list(
+            options
+                .allowed_origins
+                .iter()
+                .filter_map(|origin| HeaderValue::from_str(origin).ok()),
+        ))
+        .allow_methods([Method::GET, Method::POST, Method::DELETE])
+        .allow_headers([
+            header::AUTHORIZATION,
+            header::CONTENT_TYPE,
+            HeaderName::from_static(SESSION_HEADER),
+            HeaderName::from_static("mcp-protocol-version"),
+            HeaderName::from_static("last-event-id"),
+        ])
+        .expose_headers([HeaderName::from_static(SESSION_HEADER)]);
+    let state = HttpState {
+        ruskel,
+        defaults,
+        options: Arc::new(options),
+        sessions: Arc::default(),
+    };
+    let router = Router::new()
+        .route(
+            MCP_PATH,
+            post(handle_post).get(handle_get).delete(handle_delete),
+        )
+        .layer(cors)
+        .with_state(state.clone());
+    let sweeper = tokio::spawn(sweep_sessions(state));
+    let served = axum::serve(listener, router)
+        .with_graceful_shutdown(shutdown)
+        .await;
+    sweeper.abort();
+    served
+}
+
+/// Close idle sessions every [`SESSION_SWEEP_INTERVAL`], until aborted.
+async fn sweep_sessions(state: HttpState) {
+    let mut ticks = interval(SESSION_SWEEP_INTERVAL);
+    loop {
+        ticks.tick().await;
+        state
+            .lock_sessions()
+            .retain(|_, session| !session.is_idle());
+    }
+}
+
+/// State shared by the HTTP handlers.
+#[derive(Clone)]
+struct HttpState {
+    /// Renderer cloned into each session, sharing its crate cache.
+    ruskel: Ruskel,
+    /// Request defaults of every session.
+    defaults: RuskelServerDefaults,
+    /// Access control settings.
+    options: Arc<HttpOptions>,
+    /// Open sessions by id.
+    sessions: Arc<Mutex<HashMap<String, Arc<Session>>>>,
+}
+
+impl HttpState {
+    /// Lock the session table; a panic while it was held cannot leave it inconsistent.
+    fn lock_sessions(&self) -> MutexGuard<'_, HashMap<String, Arc<Session>>> {
+        self.sessions.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Reject requests from origins that are not allowed, or without the bearer token.
+    fn check_access(&self, headers: &HeaderMap) -> Result<(), Rejection> {
+        if let Some(origin) = headers.get(header::ORIGIN) {
+            let allowed = origin.to_str().is_ok_and(|origin| {
+                self.options
+                    .allowed_origins
+                    .iter()
+                    .any(|allowed| allowed == origin)
+            });
+            if !allowed {
+                return Err(Rejection::Origin);
+            }
+        }
+        if let Some(token) = &self.options.token {
+            let presented = headers
+                .get(header::AUTHORIZATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "));
+            if !presented.is_some_and(|presented| tokens_match(presented, token)) {
+                return Err(Rejection::Token);
+            }
+        }
+        Ok(())
+    }
+
+    /// The session a request names in its `Mcp-Session-Id` header.
+    fn session(&self, headers: &HeaderMap) -> Result<(String, Arc<Session>), Rejection> {
+        let Some(id) = headers
+            .get(SESSION_HEADER)
+            .and_then(|value| value.to_str().ok())
+        else {
+            return Err(Rejection::MissingSession);
+        };
+        match self.lock_sessions().get(id) {
+            Some(session) => Ok((id.to_string(), session.clone())),
+            None => Err(Rejection::UnknownSession),
+        }
+    }
+
+    /// Open a session with its own server connection, closing sessions left idle.
+    ///
+    /// Fails when [`MAX_SESSIONS`] sessions are still open after that.
+    fn open_session(&self) -> Result<(String, Arc<Session>), Rejection> {
+        let mut sessions = self.lock_sessions();
+        sessions.retain(|_, session| !session.is_idle());
+        if sessions.len() >= MAX_SESSIONS {
+            warn!(
+                "Refused a new MCP session: {} sessions open",
+                sessions.len()
+            );
+            return Err(Rejection::TooManySessions);
+        }
+
+        let (client_side, server_side) = duplex(PIPE_CAPACITY);
+        let (server_reader, server_writer) = split(server_side);
+        let (client_reader, client_writer) = split(client_side);
+
+        let ruskel = self.ruskel.clone();
+        let defaults = self.defaults;
+        let server = Server::new(move || RuskelServer::with_defaults(ruskel.clone(), defaults));
+        let serve = tokio::spawn(async move {
+            if let Err(e) = server.serve_stream(server_reader, server_writer).await {
+                error!("MCP session stopped: {}", e);
+            }
+        });
+
+        let streams = Arc::new(Mutex::new(Streams::default()));
+        let routed = streams.clone();
+        let read = tokio::spawn(async move {
+            let mut lines = BufReader::new(client_reader).lines();
+            loop {
+                match lines.next_line().await {
+                    Ok(Some(line)) => match serde_json::from_str(&line) {
+                        Ok(message) => lock_streams(&routed).route(message),
+                        Err(e) => warn!("Dropped malformed server message: {}", e),
+                    },
+                    Ok(None) => break,
+                    Err(e) => {
+                        error!("Failed to read server message: {}", e);
+                        break;
+                    }
+                }
+            }
+            *lock_streams(&routed) = Streams::default();
+        });
+
+        let session = Arc::new(Session {
+            writer: AsyncMutex::new(client_writer),
+            streams,
+            last_active: Mutex::new(Instant::now()),
+            tasks: [serve, read],
+        });
+        let id = Uuid::new_v4().to_string();
+        sessions.insert(id.clone(), session.clone());
+        Ok((id, session))
+    }
+}
+
+/// Why a request was turned away before reaching a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Rejection {
+    /// The `Origin` header names an origin that is not allowed.
+    Origin,
+    /// The bearer token is missing or wrong.
+    Token,
+    /// The request names no session.
+    MissingSession,
+    /// The named session does not exist or has ended.
+    UnknownSession,
+    /// A new session would exceed [`MAX_SESSIONS`].
+    TooManySessions,
+}
+
+impl IntoResponse for Rejection {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Origin => (StatusCode::FORBIDDEN, "origin not allowed").into_response(),
+            Self::Token => (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, "Bearer")],
+                "missing or invalid bearer token",
+            )
+                .into_response(),
+            Self::MissingSession => {
+                (StatusCode::BAD_REQUEST, "missing Mcp-Session-Id header").into_response()
+            }
+            Self::UnknownSession => (StatusCode::NOT_FOUND, "unknown session").into_response(),
+            Self::TooManySessions => {
+                (StatusCode::SERVICE_UNAVAILABLE, "too many open sessions").into_response()
+            }
+        }
+    }
+}
+
+/// A client session and its server connection.
+struct Session {
+    /// Writing end of the pipe into the server connection.
+    writer: AsyncMutex<WriteHalf<DuplexStream>>,
+    /// Open event streams that server messages are routed to.
+    streams: Arc<Mutex<Streams>>,
+    /// When the client last sent a request.
+    last_active: Mutex<Instant>,
+    /// The server connection and the task routing its messages.
+    tasks: [JoinHandle<()>; 2],
+}
+
+impl Session {
+    /// Record client activity.
+    fn touch(&self) {
+        *self
+            .last_active
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Instant::now();
+    }
+
+    /// Whether the session has been unused for longer than [`SESSION_IDLE_TIMEOUT`].
+    fn is_idle(&self) -> bool {
+        let last_active = *self
+            .last_active
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        last_active.elapsed() > SESSION_IDLE_TIMEOUT && lock_streams(&self.streams).is_unused()
+    }
+
+    /// Pass a client message to the server connection.
+    async fn send(&self, message: &Value) -> io::Result<()> {
+        let mut line = serde_json::to_vec(message)?;
+        line.push(b'\n');
+        let mut writer = self.writer.lock().await;
+        writer.write_all(&line).await?;
+        writer.flush().await
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+/// A client request waiting for its response.
+struct PendingRequest {
+    /// JSON-RPC id of the request.
+    id: Value,
+    /// Progress token the request carries, if any.
+    progress_token: Option<Value>,
+    /// Stream of the request's response.
+    sender: UnboundedSender<Value>,
+}
+
+/// Event streams open on a session.
+#[derive(Default)]
+struct Streams {
+    /// Requests awaiting responses, oldest first.
+    requests: Vec<PendingRequest>,
+    /// Stream opened with `GET`, if any.
+    standalone: Option<UnboundedSender<Value>>,
+}
+
+impl Streams {
+    /// Whether no request is pending and no `GET` stream is open.
+    fn is_unused(&self) -> bool {
+        self.requests.is_empty()
+            && self
+                .standalone
+                .as_ref()
+                .is_none_or(UnboundedSender::is_closed)
+    }
+
+    /// Deliver a server message to the stream it belongs on.
+    ///
+    /// Responses end the stream of their request. Progress notifications go to the request that
+    /// asked for them, and other messages to the `GET` stream, or the latest request's stream
+    /// when no `GET` stream is open.
+    fn route(&mut self, message: Value) {
+        if message.get("method").is_none() {
+            let Some(id) = message.get("id") else {
+                return;
+            };
+            if let Some(index) = self.requests.iter().position(|request| request.id == *id) {
+                let request = self.requests.remove(index);
+                // The client may have disconnected; the response has nowhere to go.
+                request.sender.unbounded_send(message).ok();
+            }
+            return;
+        }
+
+        let token = message.pointer("/params/progressToken");
+        let target = token
+            .and_then(|token| {
+                self.requests
+                    .iter()
+                    .find(|request| request.progress_token.as_ref() == Some(token))
+            })
+            .map(|request| &request.sender)
+            .or(self
+                .standalone
+                .as_ref()
+                .filter(|standalone| !standalone.is_closed()))
+            .or(self.requests.last().map(|request| &request.sender));
+        match target {
+            Some(sender) => {
+                sender.unbounded_send(message).ok();
+            }
+            None => warn!("Dropped server message with no open stream"),
+        }
+    }
+}
+
+/// Lock a session's streams; a panic while they were held cannot leave them inconsistent.
+fn lock_streams(streams: &Mutex<Streams>) -> MutexGuard<'_, Streams> {
+    streams.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Handle a JSON-RPC message posted by the client.
+async fn handle_post(State(state): State<HttpState>, headers: HeaderMap, body: Bytes) -> Response {
+    if let Err(rejection) = state.check_access(&headers) {
+        return rejection.into_response();
+    }
+    let message: Value = match serde_json::from_slice(&body) {
+        Ok(message @ Value::Object(_)) => message,
+        Ok(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                "batched messages are not supported",
+            )
+                .into_response();
+        }
+        Err(e) => return (StatusCode::BAD_REQUEST, format!("invalid JSON: {e}")).into_response(),
+    };
+
+    let initialize = message.get("method").and_then(Value::as_str) == Some("initialize");
+    let opened = if initialize && !headers.contains_key(SESSION_HEADER) {
+        state.open_session()
+    } else {
+        state.session(&headers)
+    };
+    let (session_id, session) = match opened {
+        Ok(session) => session,
+        Err(rejection) => return rejection.into_response(),
+    };
+    session.touch();
+
+    let id = message
+        .get("id")
+        .filter(|_| message.get("method").is_some())
+        .cloned();
+    let Some(id) = id else {
+        return match session.send(&message).await {
+            Ok(()) => StatusCode::ACCEPTED.into_response(),
+            Err(e) => server_unavailable(&e),
+        };
+    };
+
+    let (sender, mut receiver) = unbounded();
+    lock_streams(&session.streams)
+        .requests
+        .push(PendingRequest {
+            id: id.clone(),
+            progress_token: message.pointer("/params/_meta/progressToken").cloned(),
+            sender,
+        });
+    if let Err(e) = session.send(&message).await {
+        lock_streams(&session.streams)
+            .requests
+            .retain(|request| request.id != id);
+        return server_unavailable(&e);
+    }
+
+    let mut response = if accepts_event_stream(&headers) {
+        event_stream(receiver)
+    } else {
+        // Without an event stream, only the response reaches the client.
+        loop {
+            match receiver.next().await {
+                Some(message) if message.get("method").is_none() => {
+                    break Json(message).into_response();
+                }
+                Some(_) => {}
+                None => return server_unavailable(&io::ErrorKind::BrokenPipe.into()),
+            }
+        }
+    };
+    if initialize && let Ok(value) = HeaderValue::from_str(&session_id) {
+        response.headers_mut().insert(SESSION_HEADER, value);
+    }
+    response
+}
+
+/// Open a stream for server messages sent outside of requests.
+async fn handle_get(State(state): State<HttpState>, headers: HeaderMap) -> Response {
+    if let Err(rejection) = state.check_access(&headers) {
+        return rejection.into_response();
+    }
+    if !accepts_event_stream(&headers) {
+        return StatusCode::NOT_ACCEPTABLE.into_response();
+    }
+    let (_, session) = match state.session(&headers) {
+        Ok(session) => session,
+        Err(rejection) => return rejection.into_response(),
+    };
+    session.touch();
+    let (sender, receiver) = unbounded();
+    lock_streams(&session.streams).standalone = Some(sender);
+    event_stream(receiver)
+}
+
+/// End a session and its server connection.
+async fn handle_delete(State(state): State<HttpState>, headers: HeaderMap) -> Response {
+    if let Err(rejection) = state.check_access(&headers) {
+        return rejection.into_response();
+    }
+    match state.session(&headers) {
+        Ok((id, _)) => {
+            state.lock_sessions().remove(&id);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(rejection) => rejection.into_response(),
+    }
+}
+
+/// Whether the client accepts a server-sent event stream in response.
+fn accepts_event_stream(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/event-stream"))
+}
+
+/// A server-sent event response carrying each message `receiver` yields.
+fn event_stream(receiver: UnboundedReceiver<Value>) -> Response {
+    Sse::new(receiver.map(|message| Event::default().json_data(message)))
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+/// The response to a request whose session's server connection has gone away.
+fn server_unavailable(error: &io::Error) -> Response {
+    error!("Failed to reach MCP session: {}", error);
+    (StatusCode::INTERNAL_SERVER_ERROR, "session closed").into_response()
+}
+
+/// Compare bearer tokens in time independent of where they differ.
+fn tokens_match(presented: &str, expected: &str) -> bool {
+    presented.len() == expected.len()
+        && presented
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures::channel::mpsc::unbounded;
+    use libruskel::Ruskel;
+    use serde_json::json;
+
+    use super::{
+        HttpOptions, HttpState, MAX_SESSIONS, PendingRequest, Rejection, Streams, tokens_match,
+    };
+    use crate::server::RuskelServerDefaults;
+
+    #[test]
+    fn server_messages_follow_their_request() {
+        let (first, mut first_rx) = unbounded();
+        let (second, mut second_rx) = unbounded();
+        let mut streams = Streams {
+            requests: vec![
+                PendingRequest {
+                    id: json!(1),
+                    progress_token: Some(json!("load")),
+                    sender: first,
+                },
+                PendingRequest {
+                    id: json!(2),
+                    progress_token: None,
+                    sender: second,
+                },
+            ],
+            standalone: None,
+        };
+
+        let progress = json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/progress",
+            "params": { "progressToken": "load", "progress": 1 }
+        });
+        let roots = json!({ "jsonrpc": "2.0", "id": 0, "method": "roots/list" });
+        streams.route(progress.clone());
+        streams.route(roots.clone());
+        streams.route(json!({ "jsonrpc": "2.0", "id": 1, "result": {} }));
+
+        assert_eq!(first_rx.try_recv().ok(), Some(progress));
+        assert!(first_rx.try_recv().is_ok());
+        assert!(first_rx.try_recv().is_err());
+        assert_eq!(second_rx.try_recv().ok(), Some(roots));
+        assert_eq!(streams.requests.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn sessions_past_the_cap_are_refused() {
+        let state = HttpState {
+            ruskel: Ruskel::new(),
+            defaults: RuskelServerDefaults::default(),
+            options: Arc::new(HttpOptions::default()),
+            sessions: Arc::default(),
+        };
+        let opened: Vec<_> = (0..MAX_SESSIONS)
+            .map(|_| state.open_session().expect("session under the cap"))
+            .collect();
+
+        assert!(matches!(
+            state.open_session(),
+            Err(Rejection::TooManySessions)
+        ));
+        state.lock_sessions().remove(&opened[0].0);
+        assert!(state.open_session().is_ok());
+    }
+
+    #[test]
+    fn bearer_tokens_must_match_exactly() {
+        assert!(tokens_match("secret", "secret"));
+        assert!(!tokens_match("secreT", "secret"));
+        assert!(!tokens_match("secret2", "secret"));
+    }
+}
@@ -1,11 +1,24 @@
 //! MCP server integration for the `ruskel` CLI.
 
+/// Streamable HTTP transport serving many clients from one process.
+mod http;
+/// Paging of tool output into size-bounded pages.
+mod pagination;
+/// Progress notifications and cancellation for MCP requests.
+mod progress;
+/// Crate skeletons exposed as MCP resources.
+mod resources;
+/// Client workspace roots that relative targets resolve against.
+mod roots;
 /// Tools for exposing ruskel functionality via the Model Context Protocol.
 mod server;
 
 // Kept public for integration tests but hidden from generated docs.
 #[doc(hidden)]
+pub use http::serve_http;
+pub use http::{HttpOptions, MCP_PATH};
+#[doc(hidden)]
 pub use server::RuskelServer;
 #[doc(hidden)]
 pub use server::RuskelServerDefaults;
-pub use server::run_mcp_server;
+pub use server::{McpTransport, run_mcp_server};
@@ -0,0 +1,144 @@
+//! Paging of `ruskel`, `search` and `render_module` tool output through `max_chars`, `max_items`
+//! and opaque cursors.
+//!
+//! A cursor records where the next page starts. It is only meaningful for a repeat of the call
+//! that returned it, with the same target and query.
+
+use std::collections::BTreeMap;
+
+use libruskel::{Page, PageRequest, SearchItemKind};
+
+/// Prefix marking a string as a ruskel page cursor.
+const CURSOR_PREFIX: &str = "ruskel-page:";
+
+/// Encode the start of the next page as a cursor.
+pub fn encode_cursor(start: usize) -> String {
+    format!("{CURSOR_PREFIX}{start}")
+}
+
+/// Decode a cursor returned by [`encode_cursor`].
+pub fn decode_cursor(cursor: &str) -> Result<usize, String> {
+    cursor
+        .strip_prefix(CURSOR_PREFIX)
+        .and_then(|start| start.parse().ok())
+        .ok_or_else(|| format!("invalid cursor '{cursor}': pass a cursor returned by ruskel"))
+}
+
+/// The page requested by a tool call, or `None` when the call does not ask for paging.
+pub fn page_request(
+    max_chars: Option<usize>,
+    max_items: Option<usize>,
+    cursor: Option<&str>,
+) -> Result<Option<PageRequest>, String> {
+    if max_chars.is_none() && max_items.is_none() && cursor.is_none() {
+        return Ok(None);
+    }
+    Ok(Some(PageRequest {
+        start: cursor.map(decode_cursor).transpose()?.unwrap_or(0),
+        max_chars,
+        max_items,
+    }))
+}
+
+/// Describe a page and what follows it: the position of the page, the number of remaining
+/// items by kind, the modules left, and the cursor of the next page.
+///
+/// `noun` names the paged things, e.g. `items` or `matches`.
+pub fn page_summary<T>(page: &Page<T>, noun: &str) -> String {
+    let total = page.total();
+    let mut summary = if page.items.is_empty() {
+        format!("Page: no {noun} past this cursor ({total} in total).\n")
+    } else {
+        format!(
+            "Page: {noun} {}-{} of {total}.\n",
+            page.start + 1,
+            page.start + page.items.len()
+        )
+    };
+    let Some(next) = page.next() else {
+        return summary;
+    };
+
+    let mut kinds: BTreeMap<&str, usize> = BTreeMap::new();
+    for item in &page.remaining {
+        *kinds.entry(item.kind.label()).or_default() += 1;
+    }
+    let kinds: Vec<String> = kinds
+        .iter()
+        .map(|(kind, count)| format!("{kind}: {count}"))
+        .collect();
+    summary.push_str(&format!(
+        "Remaining: {} {noun} ({}).\n",
+        page.remaining.len(),
+        kinds.join(", ")
+    ));
+    let modules: Vec<&str> = page
+        .remaining
+        .iter()
+        .filter(|item| item.kind == SearchItemKind::Module)
+        .map(|item| item.path.as_str())
+        .collect();
+    if !modules.is_empty() {
+        summary.push_str(&format!("Modules left: {}.\n", modules.join(", ")));
+    }
+    summary.push_str(&format!(
+        "Next page: pass cursor=\"{}\".\n",
+        encode_cursor(next)
+    ));
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use libruskel::{Page, PageItem, PageRequest, SearchItemKind};
+
+    use super::{decode_cursor, encode_cursor, page_request, page_summary};
+
+    #[test]
+    fn cursors_round_trip_and_reject_foreign_strings() {
+        assert_eq!(decode_cursor(&encode_cursor(40)), Ok(40));
+        assert!(decode_cursor("40").is_err());
+        assert!(decode_cursor("ruskel-page:-1").is_err());
+
+        assert_eq!(page_request(None, None, None), Ok(None));
+        assert_eq!(
+            page_request(Some(2000), None, Some("ruskel-page:3")),
+            Ok(Some(PageRequest {
+                start: 3,
+                max_chars: Some(2000),
+                max_items: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn page_summary_describes_the_remaining_items() {
+        let item = |path: &str, kind| PageItem {
+            path: path.to_string(),
+            kind,
+        };
+        let page = Page {
+            rendered: String::new(),
+            start: 2,
+            items: vec![item("std::any", SearchItemKind::Module)],
+            remaining: vec![
+                item("std::io", SearchItemKind::Module),
+                item("std::net", SearchItemKind::Module),
+                item("std::println", SearchItemKind::Macro),
+            ],
+        };
+        assert_eq!(
+            page_summary(&page, "items"),
+            "Page: items 3-3 of 6.\n\
+             Remaining: 3 items (macro: 1, module: 2).\n\
+             Modules left: std::io, std::net.\n\
+             Next page: pass cursor=\"ruskel-page:3\".\n"
+        );
+
+        let last = Page {
+            remaining: Vec::new(),
+            ..page
+        };
+        assert_eq!(page_summary(&last, "items"), "Page: items 3-3 of 3.\n");
+    }
+}
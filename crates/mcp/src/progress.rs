@@ -0,0 +1,167 @@
+//! MCP progress notifications and cancellation for requests that load crates.
+//!
+//! Clients opt in per request by sending a `progressToken` in the request's `_meta`. Such a
+//! request reports each load stage as a `notifications/progress` message, and a
+//! `notifications/cancelled` naming its request id stops the load. MCP SDKs use the request id
+//! as the progress token, which is how a cancellation finds its request.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex, MutexGuard, PoisonError,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use libruskel::{LoadStage, ProgressHook};
+use tmcp::{
+    ServerCtx,
+    schema::{ClientRequest, ProgressToken, RequestId, ServerNotification},
+};
+use tracing::warn;
+
+/// Progress hook of a single request, forwarding stages to the client.
+pub struct RequestProgress {
+    /// Context of the request, used to send notifications.
+    context: ServerCtx,
+    /// Token the client attached to the request.
+    token: ProgressToken,
+    /// Whether the client cancelled the request.
+    cancelled: AtomicBool,
+}
+
+impl ProgressHook for RequestProgress {
+    fn stage(&self, stage: LoadStage) {
+        let notification = ServerNotification::progress(
+            self.token.clone(),
+            stage.step() as f64,
+            Some(LoadStage::ALL.len() as f64),
+            Some(stage.label().to_string()),
+        );
+        if let Err(e) = self.context.notify(notification) {
+            warn!("Failed to send progress notification: {}", e);
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Requests currently reporting progress, keyed by progress token.
+#[derive(Clone, Default)]
+pub struct InFlight {
+    /// Progress hooks of the running requests.
+    requests: Arc<Mutex<HashMap<String, Arc<RequestProgress>>>>,
+}
+
+impl InFlight {
+    /// Lock the registry; a panic while it was held cannot leave it inconsistent.
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Arc<RequestProgress>>> {
+        self.requests.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Start tracking `request` when it carries a progress token.
+    ///
+    /// The request stays cancellable until the returned guard is dropped.
+    pub fn track(&self, context: &ServerCtx, request: &ClientRequest) -> Option<TrackedRequest> {
+        let token = progress_token(request)?.clone();
+        let key = token_key(&token);
+        let progress = Arc::new(RequestProgress {
+            context: context.clone(),
+            token,
+            cancelled: AtomicBool::new(false),
+        });
+        self.lock().insert(key.clone(), Arc::clone(&progress));
+        Some(TrackedRequest {
+            in_flight: self.clone(),
+            key,
+            progress,
+        })
+    }
+
+    /// Cancel the tracked request with this id, if it is still running.
+    pub fn cancel(&self, request_id: &RequestId) {
+        if let Some(progress) = self.lock().get(&request_key(request_id)) {
+            progress.cancelled.store(true, Ordering::SeqCst);
+        }
+    }
+}
+
+/// A request registered in [`InFlight`], removed again when dropped.
+pub struct TrackedRequest {
+    /// Registry holding the request.
+    in_flight: InFlight,
+    /// Key of the request in the registry.
+    key: String,
+    /// Progress hook of the request.
+    progress: Arc<RequestProgress>,
+}
+
+impl TrackedRequest {
+    /// The hook to attach to the request's crate loads.
+    pub fn hook(&self) -> Arc<dyn ProgressHook> {
+        self.progress.clone()
+    }
+}
+
+impl Drop for TrackedRequest {
+    fn drop(&mut self) {
+        self.in_flight.lock().remove(&self.key);
+    }
+}
+
+/// The progress token of a request that may load a crate.
+fn progress_token(request: &ClientRequest) -> Option<&ProgressToken> {
+    match request {
+        ClientRequest::CallTool { _meta: meta, .. }
+        | ClientRequest::ReadResource { _meta: meta, .. } => meta.as_ref()?.progress_token.as_ref(),
+        _ => None,
+    }
+}
+
+/// Registry key of a progress token.
+fn token_key(token: &ProgressToken) -> String {
+    match token {
+        ProgressToken::String(token) => token.clone(),
+        ProgressToken::Number(token) => token.to_string(),
+    }
+}
+
+/// Registry key of a request id, matching the key of an equal progress token.
+fn request_key(request_id: &RequestId) -> String {
+    match request_id {
+        RequestId::String(id) => id.clone(),
+        RequestId::Number(id) => id.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tmcp::schema::{ClientRequest, ProgressToken, RequestId, RequestMeta};
+
+    use super::{progress_token, request_key, token_key};
+
+    #[test]
+    fn progress_tokens_match_request_ids() {
+        let request = ClientRequest::ReadResource {
+            uri: "ruskel://serde".to_string(),
+            _meta: Some(RequestMeta {
+                progress_token: Some(ProgressToken::Number(7)),
+                ..RequestMeta::default()
+            }),
+        };
+        let token = progress_token(&request).expect("progress token");
+        assert_eq!(token_key(token), request_key(&RequestId::Number(7)));
+        assert_eq!(
+            token_key(&ProgressToken::String("call-3".to_string())),
+            request_key(&RequestId::String("call-3".to_string()))
+        );
+
+        let untracked = ClientRequest::ReadResource {
+            uri: "ruskel://serde".to_string(),
+            _meta: None,
+        };
+        assert!(progress_token(&untracked).is_none());
+    }
+}
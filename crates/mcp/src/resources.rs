@@ -0,0 +1,154 @@
+//! MCP resources addressing crate, module and item skeletons through `ruskel://` URIs.
+//!
+//! A URI names a crate, optionally pinned to a version, followed by a `/` separated path within
+//! it: `ruskel://serde/de` is the target `serde::de` and `ruskel://tokio@1.40.0/sync/mpsc` is
+//! `tokio@1.40.0::sync::mpsc`.
+
+use libruskel::ProjectCrate;
+use tmcp::schema::{Resource, ResourceContents, ResourceTemplate, TextResourceContents};
+
+/// Scheme prefix shared by every ruskel resource URI.
+const SCHEME: &str = "ruskel://";
+
+/// MIME type of rendered skeletons.
+const SKELETON_MIME_TYPE: &str = "text/x-rust";
+
+/// Templates for the skeleton of any crate, module or item.
+pub fn resource_templates() -> Vec<ResourceTemplate> {
+    vec![
+        ResourceTemplate::new("Crate API", format!("{SCHEME}{{crate}}/{{path}}"))
+            .with_description(
+                "Skeleton of a module or item in a crate, e.g. ruskel://serde/de. The crate \
+                 resolves like a ruskel target: workspace members and dependencies first, then \
+                 the latest release on crates.io.",
+            )
+            .with_mime_type(SKELETON_MIME_TYPE),
+        ResourceTemplate::new(
+            "Versioned crate API",
+            format!("{SCHEME}{{crate}}@{{version}}/{{path}}"),
+        )
+        .with_description(
+            "Skeleton of a module or item in a specific published crate version, e.g. \
+             ruskel://tokio@1.40.0/sync/mpsc.",
+        )
+        .with_mime_type(SKELETON_MIME_TYPE),
+    ]
+}
+
+/// Concrete resources for the whole API of each workspace member and direct dependency.
+pub fn project_resources(crates: &[ProjectCrate]) -> Vec<Resource> {
+    crates
+        .iter()
+        .map(|krate| {
+            let role = if krate.member {
+                "Workspace member"
+            } else {
+                "Dependency"
+            };
+            Resource::new(&krate.name, format!("{SCHEME}{}", krate.name))
+                .with_description(format!("{role} {} {}", krate.name, krate.version))
+                .with_mime_type(SKELETON_MIME_TYPE)
+        })
+        .collect()
+}
+
+/// The ruskel target addressed by a resource URI, or `None` when the URI is malformed.
+pub fn resource_target(uri: &str) -> Option<String> {
+    let rest = uri.strip_prefix(SCHEME)?;
+    let (krate, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let valid_crate = krate.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_')
+        && krate
+            .split_once('@')
+            .is_none_or(|(name, version)| !name.is_empty() && !version.is_empty())
+        && krate
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '@' | '.' | '+'));
+    if !valid_crate {
+        return None;
+    }
+
+    let mut target = krate.to_string();
+    for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+        if !segment.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return None;
+        }
+        target.push_str("::");
+        target.push_str(segment);
+    }
+    Some(target)
+}
+
+/// Wrap a rendered skeleton as the contents of the resource at `uri`.
+pub fn skeleton_contents(uri: &str, skeleton: String) -> ResourceContents {
+    ResourceContents::Text(
+        TextResourceContents::new(uri, skeleton).with_mime_type(SKELETON_MIME_TYPE),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use libruskel::ProjectCrate;
+
+    use super::{project_resources, resource_target};
+
+    #[test]
+    fn resource_target_maps_uris_to_targets() {
+        assert_eq!(resource_target("ruskel://serde").as_deref(), Some("serde"));
+        assert_eq!(resource_target("ruskel://serde/").as_deref(), Some("serde"));
+        assert_eq!(
+            resource_target("ruskel://serde/de/Deserialize").as_deref(),
+            Some("serde::de::Deserialize")
+        );
+        assert_eq!(
+            resource_target("ruskel://tokio@1.40.0/sync/mpsc").as_deref(),
+            Some("tokio@1.40.0::sync::mpsc")
+        );
+        assert_eq!(
+            resource_target("ruskel://serde-json").as_deref(),
+            Some("serde-json")
+        );
+    }
+
+    #[test]
+    fn resource_target_rejects_malformed_uris() {
+        for uri in [
+            "file:///tmp/serde",
+            "ruskel://",
+            "ruskel:///de",
+            "ruskel://serde@/de",
+            "ruskel://@1.0.0",
+            "ruskel://serde/de::Deserialize",
+            "ruskel://../etc",
+        ] {
+            assert_eq!(resource_target(uri), None, "{uri}");
+        }
+    }
+
+    #[test]
+    fn project_resources_describe_members_and_dependencies() {
+        let crates = [
+            ProjectCrate {
+                name: "app".to_string(),
+                version: "0.1.0".to_string(),
+                member: true,
+            },
+            ProjectCrate {
+                name: "serde".to_string(),
+                version: "1.0.219".to_string(),
+                member: false,
+            },
+        ];
+        let resources = project_resources(&crates);
+        let summary: Vec<(&str, Option<&str>)> = resources
+            .iter()
+            .map(|resource| (resource.uri.as_str(), resource.description.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("ruskel://app", Some("Workspace member app 0.1.0")),
+                ("ruskel://serde", Some("Dependency serde 1.0.219")),
+            ]
+        );
+    }
+}
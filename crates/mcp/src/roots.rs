@@ -0,0 +1,205 @@
+//! MCP roots: the client's workspace directories, which relative targets resolve against.
+//!
+//! A client declaring the `roots` capability is asked for its roots by the first request that
+//! needs them, and again after it sends `notifications/roots/list_changed`. Requests resolve
+//! against the root their `root` parameter names, or the first root. Without roots, targets
+//! resolve against the server's working directory.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use tmcp::{ServerCtx, schema::Root};
+use tracing::warn;
+use url::Url;
+
+/// A client root on the local filesystem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientRoot {
+    /// Name the client gave the root.
+    pub name: Option<String>,
+    /// URI of the root, as the client declared it.
+    pub uri: String,
+    /// Directory the root points at.
+    pub path: PathBuf,
+}
+
+impl ClientRoot {
+    /// Convert a declared root, or `None` when it is not a `file://` URI.
+    fn from_root(root: Root) -> Option<Self> {
+        let path = Url::parse(&root.uri).ok()?.to_file_path().ok()?;
+        Some(Self {
+            name: root.name,
+            uri: root.uri,
+            path,
+        })
+    }
+
+    /// Whether `selector` names this root by name, URI or path.
+    fn matches(&self, selector: &str) -> bool {
+        self.name.as_deref() == Some(selector)
+            || self.uri == selector
+            || self.path == Path::new(selector)
+    }
+
+    /// Describe the root for error messages, e.g. `web (/home/me/web)`.
+    fn describe(&self) -> String {
+        match &self.name {
+            Some(name) => format!("{name} ({})", self.path.display()),
+            None => self.path.display().to_string(),
+        }
+    }
+}
+
+/// What the server knows about the client's roots.
+#[derive(Debug, Default)]
+struct RootsState {
+    /// Whether the client declared the roots capability.
+    supported: bool,
+    /// Roots fetched from the client, or `None` until fetched and after they change.
+    roots: Option<Arc<Vec<ClientRoot>>>,
+    /// Number of changes announced by the client, so a fetch racing a change is not kept.
+    generation: u64,
+}
+
+/// The roots of the client on one connection, shared by its request handlers.
+#[derive(Clone, Default)]
+pub struct ClientRoots {
+    /// Capability, fetched roots and change count.
+    state: Arc<Mutex<RootsState>>,
+}
+
+impl ClientRoots {
+    /// Lock the state; a panic while it was held cannot leave it inconsistent.
+    fn lock(&self) -> MutexGuard<'_, RootsState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Record whether the client can list its roots.
+    pub fn set_supported(&self, supported: bool) {
+        let mut state = self.lock();
+        state.supported = supported;
+        state.roots = None;
+    }
+
+    /// Forget the fetched roots, so the next request asks the client again.
+    pub fn invalidate(&self) {
+        let mut state = self.lock();
+        state.roots = None;
+        state.generation += 1;
+    }
+
+    /// The client's roots, fetched on first use. Empty when the client has no roots capability
+    /// or fails to list them.
+    pub async fn list(&self, context: &ServerCtx) -> Arc<Vec<ClientRoot>> {
+        let generation = {
+            let state = self.lock();
+            if !state.supported {
+                return Arc::default();
+            }
+            if let Some(roots) = &state.roots {
+                return roots.clone();
+            }
+            state.generation
+        };
+
+        let roots = match context.list_roots().await {
+            Ok(result) => result.roots,
+            Err(e) => {
+                warn!("Failed to list client roots: {}", e);
+                return Arc::default();
+            }
+        };
+        let roots: Arc<Vec<ClientRoot>> = Arc::new(
+            roots
+                .into_iter()
+                .filter_map(ClientRoot::from_root)
+                .collect(),
+        );
+        let mut state = self.lock();
+        if state.generation == generation {
+            state.roots = Some(roots.clone());
+        }
+        roots
+    }
+
+    /// The directory a request resolves targets against: the root `selector` names, or the
+    /// first root. `None` stands for the server's working directory.
+    pub async fn select(
+        &self,
+        context: &ServerCtx,
+        selector: Option<&str>,
+    ) -> Result<Option<PathBuf>, String> {
+        select_root(&self.list(context).await, selector)
+    }
+}
+
+/// Pick the root `selector` names by name, URI or path, or the first root without a selector.
+fn select_root(roots: &[ClientRoot], selector: Option<&str>) -> Result<Option<PathBuf>, String> {
+    let Some(selector) = selector.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(roots.first().map(|root| root.path.clone()));
+    };
+    if let Some(root) = roots.iter().find(|root| root.matches(selector)) {
+        return Ok(Some(root.path.clone()));
+    }
+    if roots.is_empty() {
+        return Err(format!(
+            "unknown root '{selector}': the client declared no roots"
+        ));
+    }
+    let known: Vec<String> = roots.iter().map(ClientRoot::describe).collect();
+    Err(format!(
+        "unknown root '{selector}'. Client roots: {}",
+        known.join(", ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use tmcp::schema::Root;
+
+    use super::{ClientRoot, select_root};
+
+    fn root(uri: &str, name: Option<&str>) -> Option<ClientRoot> {
+        ClientRoot::from_root(Root {
+            uri: uri.to_string(),
+            name: name.map(ToString::to_string),
+            _meta: None,
+        })
+    }
+
+    #[test]
+    fn roots_are_selected_by_name_uri_or_path() {
+        assert_eq!(root("https://example.com/repo", None), None);
+        let roots = vec![
+            root("file:///work/web%20app", Some("web")).expect("file root"),
+            root("file:///work/api", None).expect("file root"),
+        ];
+        assert_eq!(roots[0].path, PathBuf::from("/work/web app"));
+
+        assert_eq!(
+            select_root(&roots, None),
+            Ok(Some(PathBuf::from("/work/web app")))
+        );
+        for selector in ["file:///work/api", "/work/api"] {
+            assert_eq!(
+                select_root(&roots, Some(selector)),
+                Ok(Some(PathBuf::from("/work/api")))
+            );
+        }
+        assert_eq!(
+            select_root(&roots, Some("web")),
+            Ok(Some(PathBuf::from("/work/web app")))
+        );
+        assert_eq!(
+            select_root(&roots, Some("docs")),
+            Err("unknown root 'docs'. Client roots: web (/work/web app), /work/api".to_string())
+        );
+
+        assert_eq!(select_root(&[], None), Ok(None));
+        assert!(select_root(&[], Some("web")).is_err());
+    }
+}
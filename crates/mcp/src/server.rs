@@ -1,12 +1,45 @@
-use std::{env, io::stdout, result::Result as StdResult};
+use std::{
+    collections::{BTreeMap, HashSet},
+    env,
+    io::stdout,
+    path::PathBuf,
+    result::Result as StdResult,
+    sync::Arc,
+};
 
-use libruskel::{Ruskel, SearchDomain, SearchOptions, describe_domains, parse_domain_token};
+use async_trait::async_trait;
+use libruskel::{
+    CrateInfo, ImplQuery, ListItem, ListOptions, PackageInfo, Page, PageRequest, ProgressHook,
+    Result as RuskelResult, Ruskel, RuskelError, SearchDomain, SearchItemKind, SearchMode,
+    SearchOptions, SearchResult, TraitImpl, TypeUsage, describe_domains, parse_domain_token,
+    parse_kind_token,
+};
 use serde::{Deserialize, Serialize};
-use tmcp::{Result, Server, ServerCtx, mcp_server, schema::CallToolResult, tool};
-use tokio::signal::ctrl_c;
+use serde_json::Value;
+use tmcp::{
+    Arguments, Error, Result, Server, ServerCtx, ServerHandler, mcp_server,
+    schema::{
+        CallToolResult, ClientCapabilities, ClientNotification, ClientRequest, Cursor,
+        Implementation, InitializeResult, ListResourceTemplatesResult, ListResourcesResult,
+        ListToolsResult, ReadResourceResult, TaskMetadata,
+    },
+    tool,
+};
+use tokio::{net::TcpListener, signal::ctrl_c, task};
 use tracing::error;
 use tracing_subscriber::filter::LevelFilter;
 
+use crate::{
+    http::{HttpOptions, MCP_PATH, serve_http},
+    pagination::{encode_cursor, page_request, page_summary},
+    progress::InFlight,
+    resources::{project_resources, resource_target, resource_templates, skeleton_contents},
+    roots::ClientRoots,
+};
+
+/// Number of loaded crates the server keeps cached between requests.
+const CRATE_CACHE_CAPACITY: usize = 8;
+
 /// Default request values applied by the MCP server when a tool call omits them.
 #[derive(Debug, Clone, Copy)]
 pub struct RuskelServerDefaults {
@@ -39,247 +72,2163 @@ pub struct RuskelSkeletonTool {
     #[serde(default)]
     pub search: Option<String>,
 
+    /// Find the functions and methods returning this type, e.g. `Config` or `http::Request`.
+    #[serde(default)]
+    pub producers: Option<String>,
+
+    /// Find the functions and methods taking this type as a parameter.
+    #[serde(default)]
+    pub consumers: Option<String>,
+
+    /// Find every item whose signature, fields, variants or bounds mention this type.
+    #[serde(default)]
+    pub references: Option<String>,
+
+    /// List every type implementing this trait, including blanket and auto trait impls.
+    #[serde(default)]
+    pub implementors: Option<String>,
+
+    /// List every trait implemented by this type, including blanket and auto trait impls.
+    #[serde(default)]
+    pub traits_of: Option<String>,
+
+    /// List every method callable on this type, including trait methods and methods reached
+    /// through `Deref`.
+    #[serde(default)]
+    pub methods: Option<String>,
+
+    /// Render the target item together with every type of the same crate it mentions,
+    /// transitively. Requires a path target.
+    #[serde(default)]
+    pub closure: bool,
+
+    /// Limit `closure` to types within this many hops of the target.
+    #[serde(default)]
+    pub closure_depth: Option<usize>,
+
+    /// Render a binary target as a library, with private items included.
+    #[serde(default)]
+    pub bin: Option<String>,
+
+    /// Limit search to specific domains (name, doc, signature, path). Defaults to name, doc, signature.
+    #[serde(default)]
+    pub search_spec: Option<Vec<String>>,
+
+    /// Include comment frontmatter. Defaults to the server's configured setting when omitted.
+    #[serde(default)]
+    pub frontmatter: Option<bool>,
+
+    /// Require case-sensitive matches.
+    #[serde(default)]
+    pub search_case_sensitive: bool,
+
+    /// Treat the search query as a regular expression (a `regex:` prefix does the same). Ignored
+    /// by structured and type signature queries.
+    #[serde(default)]
+    pub search_regex: bool,
+
+    /// Also match item names within a small edit distance of the query, tolerating typos.
+    #[serde(default)]
+    pub fuzzy: bool,
+
+    /// Parse the search query as a structured query such as
+    /// `kind:trait name:Async doc:"cancel safe" -deprecated`.
+    #[serde(default)]
+    pub search_structured: bool,
+
+    /// Treat the search query as a type signature such as `&str -> Result<_, _>` and match it
+    /// against function inputs and outputs.
+    #[serde(default)]
+    pub search_types: bool,
+
+    /// Let references match their referents in type signature searches.
+    #[serde(default)]
+    pub ignore_references: bool,
+
+    /// Restrict search results to item kinds (e.g. trait, fn, struct, method).
+    #[serde(default)]
+    pub kind: Option<Vec<String>>,
+
+    /// Only render direct matches, not expanded containers.
+    #[serde(default)]
+    pub direct_match_only: bool,
+
+    /// Disable the crate's default Cargo features.
+    #[serde(default)]
+    pub no_default_features: bool,
+
+    /// Enable every optional Cargo feature.
+    #[serde(default)]
+    pub all_features: bool,
+
+    /// Exact list of Cargo features to enable (ignored if all_features=true).
+    #[serde(default)]
+    pub features: Vec<String>,
+
+    /// Annotate each rendered item with a `// file:line` comment pointing at its definition.
+    #[serde(default)]
+    pub source_locations: bool,
+
+    /// Embed the original source body of matched or targeted functions in place of `{}`.
+    #[serde(default)]
+    pub with_source: bool,
+
+    /// Split skeletons and search results into pages of at most this many characters.
+    #[serde(default)]
+    pub max_chars: Option<usize>,
+
+    /// Split skeletons into pages of at most this many top-level items, and search results into
+    /// pages of at most this many matches.
+    #[serde(default)]
+    pub max_items: Option<usize>,
+
+    /// Cursor of the page to return, as given at the end of the previous page.
+    #[serde(default)]
+    pub cursor: Option<String>,
+
+    /// Client root that relative paths and workspace crate names resolve against: its name, URI
+    /// or path. Defaults to the client's first root.
+    #[serde(default)]
+    pub root: Option<String>,
+}
+
+/// MCP tool parameters with the server defaults applied to their optional flags.
+#[derive(Debug)]
+struct ResolvedRuskelSkeletonTool {
+    /// Parameters as sent by the client.
+    params: RuskelSkeletonTool,
+    /// Whether private items should be included.
+    private: bool,
+    /// Whether rendered output should include frontmatter comments.
+    frontmatter: bool,
+}
+
+impl RuskelSkeletonTool {
+    /// Resolve optional request fields against the server defaults.
+    fn resolve(self, defaults: RuskelServerDefaults) -> ResolvedRuskelSkeletonTool {
+        ResolvedRuskelSkeletonTool {
+            private: self.private.unwrap_or(defaults.private),
+            frontmatter: self.frontmatter.unwrap_or(defaults.frontmatter),
+            params: self,
+        }
+    }
+
+    /// The non-empty query selecting search mode, with the way it is matched.
+    fn search_query(&self) -> StdResult<Option<(&str, SearchMode)>, String> {
+        let text_mode = if self.search_types {
+            SearchMode::Types
+        } else if self.search_structured {
+            SearchMode::Structured
+        } else if self.search_regex {
+            SearchMode::Regex
+        } else {
+            SearchMode::Literal
+        };
+        let queries = [
+            (self.search.as_deref(), text_mode),
+            (
+                self.producers.as_deref(),
+                SearchMode::Usage(TypeUsage::Producers),
+            ),
+            (
+                self.consumers.as_deref(),
+                SearchMode::Usage(TypeUsage::Consumers),
+            ),
+            (
+                self.references.as_deref(),
+                SearchMode::Usage(TypeUsage::References),
+            ),
+        ];
+        let mut present = queries
+            .into_iter()
+            .filter_map(|(query, mode)| Some((query?.trim(), mode)))
+            .filter(|(query, _)| !query.is_empty());
+        let query = present.next();
+        if present.next().is_some() {
+            return Err(
+                "search, producers, consumers and references cannot be combined".to_string(),
+            );
+        }
+        Ok(query)
+    }
+
+    /// The page to return, or `None` when the call does not ask for paging.
+    fn page(&self) -> StdResult<Option<PageRequest>, String> {
+        page_request(self.max_chars, self.max_items, self.cursor.as_deref())
+    }
+
+    /// The trait implementation query named by `implementors` or `traits_of`, if any.
+    fn impl_query(&self) -> StdResult<Option<ImplQuery<'_>>, String> {
+        let implementors = self.implementors.as_deref().map(str::trim);
+        let traits_of = self.traits_of.as_deref().map(str::trim);
+        match (implementors, traits_of) {
+            (Some(_), Some(_)) => Err("implementors and traits_of cannot be combined".to_string()),
+            (Some(path), None) | (None, Some(path)) if path.is_empty() => {
+                Err("implementors and traits_of require a path".to_string())
+            }
+            (Some(path), None) => Ok(Some(ImplQuery::Implementors(path))),
+            (None, Some(path)) => Ok(Some(ImplQuery::TraitsOf(path))),
+            (None, None) => Ok(None),
+        }
+    }
+}
+
+/// Crate loading options shared by the dedicated tools.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct CrateOptions {
+    /// Include private items. Defaults to the server's configured setting when omitted.
+    #[serde(default)]
+    pub private: Option<bool>,
+
     /// Render a binary target as a library, with private items included.
     #[serde(default)]
     pub bin: Option<String>,
 
+    /// Disable the crate's default Cargo features.
+    #[serde(default)]
+    pub no_default_features: bool,
+
+    /// Enable every optional Cargo feature.
+    #[serde(default)]
+    pub all_features: bool,
+
+    /// Exact list of Cargo features to enable (ignored if all_features=true).
+    #[serde(default)]
+    pub features: Vec<String>,
+
+    /// Client root that relative paths and workspace crate names resolve against: its name, URI
+    /// or path. Defaults to the client's first root.
+    #[serde(default)]
+    pub root: Option<String>,
+}
+
+impl CrateOptions {
+    /// Whether private items are included, falling back on the server default.
+    fn private(&self, defaults: RuskelServerDefaults) -> bool {
+        self.private.unwrap_or(defaults.private)
+    }
+}
+
+/// Parameters accepted by the `list_items` tool.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct ListItemsTool {
+    /// Crate or module to list: crate, module path, or crate@version.
+    pub target: String,
+
+    /// Only list items of these kinds (e.g. trait, fn, struct, method).
+    #[serde(default)]
+    pub kind: Option<Vec<String>>,
+
+    /// Only list items whose name, docs or signature match this query, most relevant first.
+    #[serde(default)]
+    pub query: Option<String>,
+
+    /// Crate loading options.
+    #[serde(flatten)]
+    pub options: CrateOptions,
+}
+
+/// Parameters accepted by the `search` tool.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct SearchTool {
+    /// Crate or module to search: crate, module path, or crate@version.
+    pub target: String,
+
+    /// Search query.
+    pub query: String,
+
     /// Limit search to specific domains (name, doc, signature, path). Defaults to name, doc, signature.
     #[serde(default)]
     pub search_spec: Option<Vec<String>>,
 
+    /// Restrict results to item kinds (e.g. trait, fn, struct, method).
+    #[serde(default)]
+    pub kind: Option<Vec<String>>,
+
+    /// Require case-sensitive matches.
+    #[serde(default)]
+    pub case_sensitive: bool,
+
+    /// Treat the query as a regular expression (a `regex:` prefix does the same). Ignored by
+    /// structured and type signature queries.
+    #[serde(default)]
+    pub regex: bool,
+
+    /// Also match item names within a small edit distance of the query, tolerating typos.
+    #[serde(default)]
+    pub fuzzy: bool,
+
+    /// Parse the query as a structured query such as
+    /// `kind:trait name:Async doc:"cancel safe" -deprecated`.
+    #[serde(default)]
+    pub structured: bool,
+
+    /// Treat the query as a type signature such as `&str -> Result<_, _>`.
+    #[serde(default)]
+    pub types: bool,
+
+    /// Let references match their referents in type signature searches.
+    #[serde(default)]
+    pub ignore_references: bool,
+
+    /// Treat the query as a type path and find the items that produce, consume or reference it.
+    #[serde(default)]
+    pub usage: Option<SearchUsage>,
+
+    /// Only render direct matches, not expanded containers.
+    #[serde(default)]
+    pub direct_match_only: bool,
+
+    /// Include comment frontmatter. Defaults to the server's configured setting when omitted.
+    #[serde(default)]
+    pub frontmatter: Option<bool>,
+
+    /// Annotate each rendered item with a `// file:line` comment pointing at its definition.
+    #[serde(default)]
+    pub source_locations: bool,
+
+    /// Embed the original source body of matched functions in place of `{}`.
+    #[serde(default)]
+    pub with_source: bool,
+
+    /// Split the matches into pages of at most this many characters of skeleton.
+    #[serde(default)]
+    pub max_chars: Option<usize>,
+
+    /// Split the matches into pages of at most this many matches.
+    #[serde(default)]
+    pub max_items: Option<usize>,
+
+    /// Cursor of the page to return, as given at the end of the previous page.
+    #[serde(default)]
+    pub cursor: Option<String>,
+
+    /// Crate loading options.
+    #[serde(flatten)]
+    pub options: CrateOptions,
+}
+
+/// How the items found by a `usage` search relate to the queried type.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchUsage {
+    /// Functions and methods returning the type.
+    Producers,
+    /// Functions and methods taking the type as a parameter.
+    Consumers,
+    /// Items whose signature, fields, variants or bounds mention the type.
+    References,
+}
+
+impl From<SearchUsage> for TypeUsage {
+    fn from(usage: SearchUsage) -> Self {
+        match usage {
+            SearchUsage::Producers => Self::Producers,
+            SearchUsage::Consumers => Self::Consumers,
+            SearchUsage::References => Self::References,
+        }
+    }
+}
+
+impl SearchTool {
+    /// The search mode selected by the `usage`, `types`, `structured` and `regex` switches, in
+    /// that order of precedence.
+    fn mode(&self) -> SearchMode {
+        if let Some(usage) = self.usage {
+            SearchMode::Usage(usage.into())
+        } else if self.types {
+            SearchMode::Types
+        } else if self.structured {
+            SearchMode::Structured
+        } else if self.regex {
+            SearchMode::Regex
+        } else {
+            SearchMode::Literal
+        }
+    }
+}
+
+/// Parameters accepted by the `get_item` tool.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct GetItemTool {
+    /// Path of the item, e.g. `serde::de::Deserialize` or `mycrate::Struct::method`.
+    pub path: String,
+
+    /// Include comment frontmatter. Defaults to the server's configured setting when omitted.
+    #[serde(default)]
+    pub frontmatter: Option<bool>,
+
+    /// Annotate each rendered item with a `// file:line` comment pointing at its definition.
+    #[serde(default)]
+    pub source_locations: bool,
+
+    /// Embed the original source body of the item's functions in place of `{}`.
+    #[serde(default)]
+    pub with_source: bool,
+
+    /// Render the item together with every type of the same crate it mentions, transitively.
+    #[serde(default)]
+    pub closure: bool,
+
+    /// Limit `closure` to types within this many hops of the item.
+    #[serde(default)]
+    pub closure_depth: Option<usize>,
+
+    /// Crate loading options.
+    #[serde(flatten)]
+    pub options: CrateOptions,
+}
+
+/// Parameters accepted by the `render_module` tool.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct RenderModuleTool {
+    /// Crate or module to render: crate, module path, or crate@version.
+    pub target: String,
+
+    /// Include comment frontmatter. Defaults to the server's configured setting when omitted.
+    #[serde(default)]
+    pub frontmatter: Option<bool>,
+
+    /// Annotate each rendered item with a `// file:line` comment pointing at its definition.
+    #[serde(default)]
+    pub source_locations: bool,
+
+    /// Split the skeleton into pages of at most this many characters.
+    #[serde(default)]
+    pub max_chars: Option<usize>,
+
+    /// Split the skeleton into pages of at most this many top-level items.
+    #[serde(default)]
+    pub max_items: Option<usize>,
+
+    /// Cursor of the page to return, as given at the end of the previous page.
+    #[serde(default)]
+    pub cursor: Option<String>,
+
+    /// Crate loading options.
+    #[serde(flatten)]
+    pub options: CrateOptions,
+}
+
+/// Parameters accepted by the `trait_impls` tool.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct TraitImplsTool {
+    /// Crate or module to look in: crate, module path, or crate@version.
+    pub target: String,
+
+    /// List every type implementing this trait, including blanket and auto trait impls.
+    #[serde(default)]
+    pub implementors: Option<String>,
+
+    /// List every trait implemented by this type, including blanket and auto trait impls.
+    #[serde(default)]
+    pub traits_of: Option<String>,
+
     /// Include comment frontmatter. Defaults to the server's configured setting when omitted.
     #[serde(default)]
     pub frontmatter: Option<bool>,
 
-    /// Require case-sensitive matches.
-    #[serde(default)]
-    pub search_case_sensitive: bool,
+    /// Annotate each rendered impl with a `// file:line` comment pointing at its definition.
+    #[serde(default)]
+    pub source_locations: bool,
+
+    /// Crate loading options.
+    #[serde(flatten)]
+    pub options: CrateOptions,
+}
+
+impl TraitImplsTool {
+    /// The query named by exactly one of `implementors` and `traits_of`.
+    fn query(&self) -> StdResult<ImplQuery<'_>, String> {
+        let implementors = self.implementors.as_deref().map(str::trim);
+        let traits_of = self.traits_of.as_deref().map(str::trim);
+        match (implementors, traits_of) {
+            (Some(_), Some(_)) => Err("implementors and traits_of cannot be combined".to_string()),
+            (Some(path), None) | (None, Some(path)) if path.is_empty() => {
+                Err("implementors and traits_of require a path".to_string())
+            }
+            (Some(path), None) => Ok(ImplQuery::Implementors(path)),
+            (None, Some(path)) => Ok(ImplQuery::TraitsOf(path)),
+            (None, None) => Err("trait_impls requires implementors or traits_of".to_string()),
+        }
+    }
+}
+
+/// Parameters accepted by the `methods` tool.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct MethodsTool {
+    /// Crate or module to look in: crate, module path, or crate@version.
+    pub target: String,
+
+    /// Type whose callable methods are listed, e.g. `String` or `mycrate::Config`.
+    pub type_path: String,
+
+    /// Include comment frontmatter. Defaults to the server's configured setting when omitted.
+    #[serde(default)]
+    pub frontmatter: Option<bool>,
+
+    /// Annotate each rendered method with a `// file:line` comment pointing at its definition.
+    #[serde(default)]
+    pub source_locations: bool,
+
+    /// Crate loading options.
+    #[serde(flatten)]
+    pub options: CrateOptions,
+}
+
+/// Parameters accepted by the `crate_overview` tool.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct CrateOverviewTool {
+    /// Crate or module to summarize: crate, module path, or crate@version.
+    pub target: String,
+
+    /// Crate loading options.
+    #[serde(flatten)]
+    pub options: CrateOptions,
+}
+
+/// Parameters accepted by the `invalidate` tool.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct InvalidateTool {
+    /// Crate to drop from the cache: a path or name of a local crate. Clears the whole cache
+    /// when omitted.
+    #[serde(default)]
+    pub target: Option<String>,
+
+    /// Client root that relative paths and workspace crate names resolve against: its name, URI
+    /// or path. Defaults to the client's first root.
+    #[serde(default)]
+    pub root: Option<String>,
+}
+
+/// Parameters accepted by the `deps` tool.
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct DepsTool {
+    /// Client root whose project is listed: its name, URI or path. Defaults to the client's
+    /// first root.
+    #[serde(default)]
+    pub root: Option<String>,
+}
+
+/// An item in a `list_items`, `search` or `ruskel` result.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct ItemEntry {
+    /// Fully qualified path of the item.
+    path: String,
+    /// Kind label, e.g. `struct` or `trait method`.
+    kind: &'static str,
+    /// Where the item is defined, as `file:line`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    location: Option<String>,
+    /// Relevance score when the items were matched by a query.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score: Option<u32>,
+    /// Search domains that matched the item.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    matched: Vec<&'static str>,
+    /// Compact signature of a matched item.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signature: Option<String>,
+    /// First paragraph of a matched item's documentation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    docs: Option<String>,
+}
+
+impl ItemEntry {
+    /// Build the entry for a listed item.
+    fn listed(item: &ListItem) -> Self {
+        Self {
+            path: item.path.clone(),
+            kind: item.kind.label(),
+            location: item.location.as_ref().map(ToString::to_string),
+            score: item.score,
+            matched: Vec::new(),
+            signature: None,
+            docs: None,
+        }
+    }
+
+    /// Build the entry for a search match.
+    fn matched(result: &SearchResult) -> Self {
+        Self {
+            path: result.path_string.clone(),
+            kind: result.kind.label(),
+            location: result.location.as_ref().map(ToString::to_string),
+            score: Some(result.score),
+            matched: describe_domains(result.matched),
+            signature: result.signature.clone(),
+            docs: result.docs.as_deref().and_then(docs_summary),
+        }
+    }
+}
+
+/// Structured result of the `list_items` tool.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct ListItemsResult {
+    /// Listed crate or module.
+    target: String,
+    /// Listed items.
+    items: Vec<ItemEntry>,
+}
+
+/// Structured result of the `search` tool.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct SearchToolResult {
+    /// Searched crate or module.
+    target: String,
+    /// Crate the search loaded.
+    #[serde(rename = "crate", skip_serializing_if = "Option::is_none")]
+    krate: Option<CrateEntry>,
+    /// Search query.
+    query: String,
+    /// Matched items, most relevant first.
+    matches: Vec<ItemEntry>,
+    /// Skeleton restricted to the matched items.
+    rendered: String,
+    /// Position of the page, for paged searches.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page: Option<PageEntry>,
+}
+
+/// A trait implementation in a `get_item`, `trait_impls` or `ruskel` result.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct ImplEntry {
+    /// Implemented trait.
+    trait_path: String,
+    /// Implementing type.
+    type_path: String,
+    /// Whether the impl is explicit (`impl`), `blanket` or `auto`.
+    origin: &'static str,
+    /// Where the impl is written, as `file:line`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    location: Option<String>,
+}
+
+impl ImplEntry {
+    /// Build the entry for a found trait implementation.
+    fn of(found: &TraitImpl) -> Self {
+        Self {
+            trait_path: found.trait_path.clone(),
+            type_path: found.type_path.clone(),
+            origin: found.origin.label(),
+            location: found.location.as_ref().map(ToString::to_string),
+        }
+    }
+}
+
+/// Structured result of the `get_item` tool.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct GetItemResult {
+    /// Fully qualified path of the item.
+    path: String,
+    /// Kind label of the item.
+    kind: &'static str,
+    /// Compact signature of the item.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signature: Option<String>,
+    /// Documentation comment of the item.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    docs: Option<String>,
+    /// Where the item is defined, as `file:line`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    location: Option<String>,
+    /// Traits implemented by a type, or implementors of a trait.
+    impls: Vec<ImplEntry>,
+    /// Rendered skeleton of the item.
+    rendered: String,
+}
+
+/// Structured result of the `render_module` tool.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct RenderModuleResult {
+    /// Rendered crate or module.
+    target: String,
+    /// Rendered skeleton.
+    rendered: String,
+    /// Position of the page, for paged skeletons.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page: Option<PageEntry>,
+}
+
+/// Structured result of the `trait_impls` tool.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct TraitImplsResult {
+    /// Crate or module looked in.
+    target: String,
+    /// Found trait implementations.
+    impls: Vec<ImplEntry>,
+    /// Skeleton of the impls written in the crate.
+    rendered: String,
+}
+
+/// Structured result of the `methods` tool.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct MethodsResult {
+    /// Crate or module looked in.
+    target: String,
+    /// Fully qualified path of the queried type.
+    type_path: String,
+    /// Impl block listing the callable methods, each annotated with its origin.
+    rendered: String,
+}
+
+/// A module in a `crate_overview` result.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct ModuleSummary {
+    /// Fully qualified path of the module.
+    path: String,
+    /// Number of items directly inside the module, by kind label.
+    items: BTreeMap<&'static str, usize>,
+}
+
+/// Structured result of the `crate_overview` tool.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct CrateOverviewResult {
+    /// Summarized crate or module.
+    target: String,
+    /// Modules in listing order, starting with the crate root.
+    modules: Vec<ModuleSummary>,
+}
+
+/// The crate a tool call loaded.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct CrateEntry {
+    /// Crate name, as used in paths.
+    name: String,
+    /// Crate version, when known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    /// Cargo features enabled for the build.
+    features: Vec<String>,
+}
+
+impl From<CrateInfo> for CrateEntry {
+    fn from(info: CrateInfo) -> Self {
+        Self {
+            name: info.name,
+            version: info.version,
+            features: info.features,
+        }
+    }
+}
+
+/// Position of a paged `ruskel`, `search` or `render_module` result.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct PageEntry {
+    /// Zero-based position of the page's first item.
+    start: usize,
+    /// Number of items on the page.
+    items: usize,
+    /// Number of items across all pages.
+    total: usize,
+    /// Cursor of the next page, absent on the last page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_cursor: Option<String>,
+}
+
+impl PageEntry {
+    /// Describe the position of `page`.
+    fn of<T>(page: &Page<T>) -> Self {
+        Self {
+            start: page.start,
+            items: page.items.len(),
+            total: page.total(),
+            next_cursor: page.next().map(encode_cursor),
+        }
+    }
+}
+
+/// Structured result of the `ruskel` tool.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct RuskelToolResult {
+    /// Requested crate, module or item.
+    target: String,
+    /// Crate the call loaded.
+    #[serde(rename = "crate", skip_serializing_if = "Option::is_none")]
+    krate: Option<CrateEntry>,
+    /// Search query, for searches.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    query: Option<String>,
+    /// Matched items, most relevant first, for searches.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    matches: Option<Vec<ItemEntry>>,
+    /// Found trait implementations, for `implementors` and `traits_of` queries.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    impls: Option<Vec<ImplEntry>>,
+    /// Rendered skeleton.
+    rendered: String,
+    /// Position of the page, for paged skeletons and searches.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page: Option<PageEntry>,
+}
+
+impl RuskelToolResult {
+    /// A result holding only the rendered skeleton of `params.target`.
+    fn rendered(ruskel: &Ruskel, params: &RuskelSkeletonTool, rendered: String) -> Self {
+        Self {
+            target: params.target.clone(),
+            krate: loaded_crate(ruskel, params),
+            query: None,
+            matches: None,
+            impls: None,
+            rendered,
+            page: None,
+        }
+    }
+}
+
+/// A crate in a `deps` result.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct PackageEntry {
+    /// Package name.
+    name: String,
+    /// Package version; for dependencies, the version cargo resolved.
+    version: String,
+    /// Whether the crate is a workspace member rather than a dependency.
+    member: bool,
+    /// Cargo features the project enables for the package.
+    features: Vec<String>,
+    /// Where cargo gets the package from: `registry`, `path` or `git`.
+    source: &'static str,
+    /// Whether rustdoc JSON from an earlier build is on disk, making the crate quick to load.
+    docs_cached: bool,
+    /// Target to pass to the other tools to load the crate.
+    target: String,
+}
+
+impl From<PackageInfo> for PackageEntry {
+    fn from(info: PackageInfo) -> Self {
+        Self {
+            name: info.name,
+            version: info.version,
+            member: info.member,
+            features: info.features,
+            source: info.source.label(),
+            docs_cached: info.docs_cached,
+            target: info.target,
+        }
+    }
+}
+
+/// Structured result of the `deps` tool.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct DepsResult {
+    /// Workspace members, then the packages they depend on directly.
+    packages: Vec<PackageEntry>,
+}
+
+/// Structured result of the `invalidate` tool.
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct InvalidateResult {
+    /// Invalidated crate, absent when the whole cache was cleared.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<String>,
+    /// Number of cached crates dropped.
+    dropped: usize,
+}
+
+#[derive(Clone)]
+/// MCP server implementation that forwards requests to an underlying `Ruskel` instance.
+///
+/// Tool calls are handled by [`RuskelTools`]; resources are served here.
+pub struct RuskelServer {
+    /// Tool handlers, which also own the renderer and request defaults.
+    tools: RuskelTools,
+    /// Requests reporting progress, so cancellations can reach them.
+    in_flight: InFlight,
+}
+
+impl RuskelServer {
+    /// Create a new server wrapper around the provided `Ruskel` renderer.
+    pub fn new(ruskel: Ruskel) -> Self {
+        Self::with_defaults(ruskel, RuskelServerDefaults::default())
+    }
+
+    /// Create a new server wrapper with explicit request defaults.
+    pub fn with_defaults(ruskel: Ruskel, defaults: RuskelServerDefaults) -> Self {
+        Self {
+            tools: RuskelTools {
+                ruskel,
+                defaults,
+                roots: ClientRoots::default(),
+            },
+            in_flight: InFlight::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl ServerHandler for RuskelServer {
+    async fn handle_request(&self, context: &ServerCtx, request: ClientRequest) -> Result<Value> {
+        let tracked = self.in_flight.track(context, &request);
+        let tools = match &tracked {
+            Some(tracked) => self.tools.with_progress(tracked.hook()),
+            None => self.tools.clone(),
+        };
+        match request {
+            ClientRequest::ListResources { cursor, .. } => Ok(serde_json::to_value(
+                self.list_resources(context, cursor).await?,
+            )?),
+            ClientRequest::ListResourceTemplates { cursor, .. } => Ok(serde_json::to_value(
+                self.list_resource_templates(context, cursor).await?,
+            )?),
+            ClientRequest::ReadResource { uri, .. } => Ok(serde_json::to_value(
+                tools.read_skeleton(context, uri).await?,
+            )?),
+            request => tools.handle_request(context, request).await,
+        }
+    }
+
+    async fn notification(
+        &self,
+        _context: &ServerCtx,
+        notification: ClientNotification,
+    ) -> Result<()> {
+        match notification {
+            ClientNotification::Cancelled {
+                request_id: Some(request_id),
+                ..
+            } => self.in_flight.cancel(&request_id),
+            ClientNotification::RootsListChanged { .. } => self.tools.roots.invalidate(),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    async fn initialize(
+        &self,
+        context: &ServerCtx,
+        protocol_version: String,
+        capabilities: ClientCapabilities,
+        client_info: Implementation,
+    ) -> Result<InitializeResult> {
+        self.tools.roots.set_supported(capabilities.roots.is_some());
+        let init = self
+            .tools
+            .initialize(context, protocol_version, capabilities, client_info)
+            .await?;
+        Ok(init.with_resources(false, false))
+    }
+
+    async fn list_tools(
+        &self,
+        context: &ServerCtx,
+        cursor: Option<Cursor>,
+    ) -> Result<ListToolsResult> {
+        self.tools.list_tools(context, cursor).await
+    }
+
+    async fn call_tool(
+        &self,
+        context: &ServerCtx,
+        name: String,
+        arguments: Option<Arguments>,
+        task: Option<TaskMetadata>,
+    ) -> Result<CallToolResult> {
+        self.tools.call_tool(context, name, arguments, task).await
+    }
+
+    async fn list_resources(
+        &self,
+        context: &ServerCtx,
+        _cursor: Option<Cursor>,
+    ) -> Result<ListResourcesResult> {
+        let root = self
+            .tools
+            .roots
+            .select(context, None)
+            .await
+            .map_err(Error::InternalError)?;
+        let ruskel = self.tools.ruskel.clone().with_root(root);
+        let crates = run_blocking(move || ruskel.project_crates())
+            .await
+            .map_err(|e| {
+                error!("Failed to list project crates: {}", e);
+                Error::InternalError(format!("Failed to list project crates: {e}"))
+            })?;
+        Ok(ListResourcesResult::new().with_resources(project_resources(&crates)))
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _context: &ServerCtx,
+        _cursor: Option<Cursor>,
+    ) -> Result<ListResourceTemplatesResult> {
+        Ok(ListResourceTemplatesResult::new().with_resource_templates(resource_templates()))
+    }
+
+    async fn read_resource(&self, context: &ServerCtx, uri: String) -> Result<ReadResourceResult> {
+        self.tools.read_skeleton(context, uri).await
+    }
+}
+
+#[derive(Clone)]
+/// Tool handlers of the MCP server.
+struct RuskelTools {
+    /// Code skeleton renderer shared across tool invocations.
+    ruskel: Ruskel,
+    /// Default request values applied when tool calls omit optional flags.
+    defaults: RuskelServerDefaults,
+    /// Roots of the connected client, which targets resolve against.
+    roots: ClientRoots,
+}
+
+#[mcp_server(name = "ruskel_server")]
+impl RuskelTools {
+    #[tool(output_schema = RuskelToolResult)]
+    /// **ruskel** returns a Rust API skeleton with implementation stripped. Useful for looking up
+    /// signatures, derives, APIs, and doc-comments.
+    ///
+    /// # When a model should call this tool
+    /// 1. It needs to look up a function/trait/struct signature.
+    /// 2. It wants an overview of a public or private API.
+    /// 3. The user asks for examples or docs from a crate.
+    ///
+    /// The dedicated `list_items`, `search`, `get_item`, `render_module`, `crate_overview`,
+    /// `trait_impls` and `methods` tools cover the same ground with simpler parameters and
+    /// structured results; this tool remains for compatibility.
+    ///
+    /// # Target syntax examples
+    /// - `mycrate::Struct` →  struct in current crate
+    /// - `mycrate::Struct::method` →  method on struct in current crate
+    /// - `std::vec::Vec` →  Vec from std lib
+    /// - `serde` →  latest serde on crates.io
+    /// - `serde@1.0.160` →  specific published version
+    /// - `serde::de::Deserialize` →  narrow to one module/type
+    /// - `/path/to/crate` or `/path/to/crate::submod` →  local workspace paths
+    ///
+    /// # Output format
+    /// Valid Rust code with implementation omitted.
+    ///
+    /// # Tips for LLMs
+    /// - Request deep module paths (e.g. `tokio::sync::mpsc`) to reduce output size.
+    /// - Pass `all_features=true` or `features=[…]` when a symbol is behind a feature gate.
+    /// - Pass `private=true` for private items in local codebases. **Caution:** Avoid using
+    ///   `private=true` on entire crates since output can be extremely large. Prefer targeting
+    ///   specific modules or items.
+    /// - Pass `search="pattern"` to restrict output to matched items.
+    /// - Pass `search_regex=true` (or prefix the query with `regex:`) for patterns like `^try_`.
+    /// - Matches are listed most relevant first; pass `fuzzy=true` to tolerate typos in names.
+    /// - Pass `search_structured=true` to combine fields and operators, e.g.
+    ///   `kind:trait name:Async doc:"cancel safe" -deprecated` or `kind:fn OR kind:method`.
+    /// - Pass `search_types=true` to find functions by signature, e.g. `&str -> Result<_, _>`
+    ///   or `Iterator<Item = T> -> Vec<T>`; add `ignore_references=true` to treat `&T` as `T`.
+    /// - Pass `producers="Config"` or `consumers="Config"` to find the functions and methods that
+    ///   return or accept a type, or `references="Runtime"` to find every item mentioning it.
+    /// - Pass `implementors="Display"` or `traits_of="Config"` to list trait implementations,
+    ///   including blanket and auto trait impls.
+    /// - Pass `methods="String"` to see everything callable on a type, including trait methods
+    ///   and methods reached through `Deref`.
+    /// - Pass `closure=true` with an item path to include every type the item mentions, e.g.
+    ///   `reqwest::Client::execute`; limit it with `closure_depth`.
+    /// - Pass `kind=["trait"]` or `kind=["method"]` with a search to keep only items of those
+    ///   kinds.
+    /// - Pass `direct_match_only=true` to show only exact matches.
+    /// - Pass `frontmatter=false` to omit the leading comment block.
+    /// - Pass `source_locations=true` to annotate items with `// file:line` comments.
+    /// - Pass `with_source=true` with a search or item path to see the matched functions' bodies.
+    /// - Pass `max_chars` or `max_items` to split large skeletons and searches into pages; each
+    ///   page ends with a summary of what remains and the `cursor` for the next page.
+    async fn ruskel(&self, ctx: &ServerCtx, params: RuskelSkeletonTool) -> Result<CallToolResult> {
+        let params = params.resolve(self.defaults);
+        let search_domains = match resolve_search_domains(params.params.search_spec.as_deref()) {
+            Ok(domains) => domains,
+            Err(error) => {
+                return Ok(CallToolResult::new()
+                    .with_text_content(error)
+                    .mark_as_error());
+            }
+        };
+        let item_kinds = match resolve_item_kinds(params.params.kind.as_deref()) {
+            Ok(kinds) => kinds,
+            Err(error) => {
+                return Ok(CallToolResult::new()
+                    .with_text_content(error)
+                    .mark_as_error());
+            }
+        };
+        let page = match params.params.page() {
+            Ok(page) => page,
+            Err(error) => return Ok(error_result(error)),
+        };
+
+        let root = match self.root(ctx, params.params.root.as_deref()).await {
+            Ok(root) => root,
+            Err(result) => return Ok(result),
+        };
+
+        let tools = Self {
+            ruskel: self.ruskel.clone().with_root(root),
+            ..self.clone()
+        };
+        let result = task::spawn_blocking(move || {
+            tools.run_skeleton_tool(&params, search_domains, item_kinds, page)
+        })
+        .await;
+        Ok(result.unwrap_or_else(|e| error_result(format!("ruskel task failed: {e}"))))
+    }
+
+    #[tool(read_only, output_schema = ListItemsResult)]
+    /// **list_items** lists the items of a crate or module, one per line with its kind and
+    /// path, without rendering any code. Use it to discover what exists before asking for
+    /// details.
+    ///
+    /// # Tips for LLMs
+    /// - Pass `kind=["trait"]` or `kind=["struct", "enum"]` to list only items of those kinds.
+    /// - Pass `query="builder"` to list only matching items, most relevant first.
+    /// - Follow up with `get_item` for one item or `render_module` for a whole module.
+    async fn list_items(&self, ctx: &ServerCtx, params: ListItemsTool) -> Result<CallToolResult> {
+        let private = params.options.private(self.defaults);
+        let kinds = match resolve_item_kinds(params.kind.as_deref()) {
+            Ok(kinds) => kinds,
+            Err(error) => return Ok(error_result(error)),
+        };
+        let root = match self.root(ctx, params.options.root.as_deref()).await {
+            Ok(root) => root,
+            Err(result) => return Ok(result),
+        };
+
+        let ruskel = self
+            .ruskel
+            .clone()
+            .with_root(root)
+            .with_bin_target(params.options.bin.clone());
+        let query = params
+            .query
+            .as_deref()
+            .map(str::trim)
+            .filter(|query| !query.is_empty());
+        let search = query.map(|query| {
+            SearchOptions::configured(query, SearchDomain::default(), false, private, false)
+        });
+        let list_options = ListOptions {
+            include_private: private,
+            kinds,
+            search,
+        };
+        let target = params.target.clone();
+        let options = params.options.clone();
+        let listed = run_blocking(move || {
+            ruskel.list(
+                &target,
+                options.no_default_features,
+                options.all_features,
+                options.features,
+                &list_options,
+            )
+        })
+        .await;
+        let items = match listed {
+            Ok(items) => items,
+            Err(e) => {
+                error!("Failed to list items: {}", e);
+                return Ok(error_result(format!(
+                    "Failed to list items in '{}': {}",
+                    params.target, e
+                )));
+            }
+        };
+
+        let text = if items.is_empty() {
+            match query {
+                Some(query) => format!("No matches found for \"{query}\"."),
+                None => "No items found.".to_string(),
+            }
+        } else {
+            let width = items
+                .iter()
+                .map(|item| item.kind.label().len())
+                .max()
+                .unwrap_or(0);
+            let mut text = String::new();
+            for item in &items {
+                text.push_str(&format!("{:<width$} {}", item.kind.label(), item.path));
+                if let Some(location) = &item.location {
+                    text.push_str(&format!(" ({location})"));
+                }
+                text.push('\n');
+            }
+            text
+        };
+        Ok(structured_result(
+            text,
+            &ListItemsResult {
+                target: params.target,
+                items: items.iter().map(ItemEntry::listed).collect(),
+            },
+        ))
+    }
+
+    #[tool(read_only, output_schema = SearchToolResult)]
+    /// **search** finds items in a crate by name, docs, signature or path and returns the
+    /// matches, most relevant first, followed by a skeleton restricted to them.
+    ///
+    /// # Tips for LLMs
+    /// - Pass `regex=true` (or prefix the query with `regex:`) for patterns like `^try_`.
+    /// - Pass `fuzzy=true` to tolerate typos in names.
+    /// - Pass `structured=true` to combine fields and operators, e.g.
+    ///   `kind:trait name:Async doc:"cancel safe" -deprecated`.
+    /// - Pass `types=true` to find functions by signature, e.g. `&str -> Result<_, _>`; add
+    ///   `ignore_references=true` to treat `&T` as `T`.
+    /// - Pass `usage="producers"` or `usage="consumers"` with a type as the query to find the
+    ///   functions and methods that return or accept it, or `usage="references"` to find every
+    ///   item mentioning it.
+    /// - Pass `kind=["method"]` to keep only items of those kinds.
+    /// - Pass `direct_match_only=true` to avoid expanding matched containers.
+    /// - Pass `max_chars` or `max_items` to split many matches into pages; each page ends with a
+    ///   summary of what remains and the `cursor` for the next page.
+    async fn search(&self, ctx: &ServerCtx, params: SearchTool) -> Result<CallToolResult> {
+        let private = params.options.private(self.defaults);
+        let domains = match resolve_search_domains(params.search_spec.as_deref()) {
+            Ok(domains) => domains,
+            Err(error) => return Ok(error_result(error)),
+        };
+        let kinds = match resolve_item_kinds(params.kind.as_deref()) {
+            Ok(kinds) => kinds,
+            Err(error) => return Ok(error_result(error)),
+        };
+        let query = params.query.trim();
+        if query.is_empty() {
+            return Ok(error_result("search requires a non-empty query"));
+        }
+        let page = match page_request(params.max_chars, params.max_items, params.cursor.as_deref())
+        {
+            Ok(page) => page,
+            Err(error) => return Ok(error_result(error)),
+        };
+        let root = match self.root(ctx, params.options.root.as_deref()).await {
+            Ok(root) => root,
+            Err(result) => return Ok(result),
+        };
+
+        let ruskel = self
+            .ruskel
+            .clone()
+            .with_root(root)
+            .with_frontmatter(params.frontmatter.unwrap_or(self.defaults.frontmatter))
+            .with_bin_target(params.options.bin.clone())
+            .with_source_locations(params.source_locations)
+            .with_item_source(params.with_source);
+        let mut options = SearchOptions::configured(
+            query,
+            domains,
+            params.case_sensitive,
+            private,
+            !params.direct_match_only,
+        );
+        options.mode = params.mode();
+        options.fuzzy = params.fuzzy;
+        options.ignore_references = params.ignore_references;
+        options.kinds = kinds;
+
+        let target = params.target.clone();
+        let crate_options = params.options.clone();
+        let searched = run_blocking(move || {
+            let found = match page {
+                Some(page) => {
+                    let page = ruskel.search_page(
+                        &target,
+                        crate_options.no_default_features,
+                        crate_options.all_features,
+                        crate_options.features.clone(),
+                        &options,
+                        &page,
+                    )?;
+                    let total = page.total();
+                    let position = (PageEntry::of(&page), page_summary(&page, "matches"));
+                    (page.items, page.rendered, total, Some(position))
+                }
+                None => {
+                    let response = ruskel.search(
+                        &target,
+                        crate_options.no_default_features,
+                        crate_options.all_features,
+                        crate_options.features.clone(),
+                        &options,
+                    )?;
+                    let total = response.results.len();
+                    (response.results, response.rendered, total, None)
+                }
+            };
+            let info = ruskel
+                .crate_info(
+                    &target,
+                    crate_options.no_default_features,
+                    crate_options.all_features,
+                    crate_options.features,
+                )
+                .ok();
+            Ok((found, info))
+        })
+        .await;
+        let ((results, rendered, total, position), info) = match searched {
+            Ok(response) => response,
+            Err(e) => {
+                error!("Failed to generate search results: {}", e);
+                return Ok(error_result(format!(
+                    "Failed to search '{}' with query '{}': {}",
+                    params.target, query, e
+                )));
+            }
+        };
+
+        let (page, summary) = position.unzip();
+        let result = structured_result(
+            search_summary(query, &results, total, &rendered),
+            &SearchToolResult {
+                target: params.target.clone(),
+                krate: info.map(CrateEntry::from),
+                query: query.to_string(),
+                matches: results.iter().map(ItemEntry::matched).collect(),
+                rendered,
+                page,
+            },
+        );
+        Ok(match summary {
+            Some(summary) => result.with_text_content(summary),
+            None => result,
+        })
+    }
+
+    #[tool(read_only, output_schema = GetItemResult)]
+    /// **get_item** describes a single item by path: its signature, docs and location, the
+    /// traits it implements (for types) or its implementors (for traits), and its rendered
+    /// skeleton with members.
+    ///
+    /// # Path examples
+    /// - `serde::de::Deserialize` →  a trait and its implementors
+    /// - `mycrate::Config` →  a struct with its fields, methods and trait impls
+    /// - `/path/to/crate::module::function` →  an item in a local crate
+    ///
+    /// # Tips for LLMs
+    /// - Pass `closure=true` to also render every type the item mentions, e.g.
+    ///   `reqwest::Client::execute`; limit it with `closure_depth`.
+    async fn get_item(&self, ctx: &ServerCtx, params: GetItemTool) -> Result<CallToolResult> {
+        let private = params.options.private(self.defaults);
+        let root = match self.root(ctx, params.options.root.as_deref()).await {
+            Ok(root) => root,
+            Err(result) => return Ok(result),
+        };
+
+        let ruskel = self
+            .ruskel
+            .clone()
+            .with_root(root)
+            .with_frontmatter(params.frontmatter.unwrap_or(self.defaults.frontmatter))
+            .with_bin_target(params.options.bin.clone())
+            .with_source_locations(params.source_locations)
+            .with_item_source(params.with_source);
+        let path = params.path.clone();
+        let options = params.options.clone();
+        let (closure, closure_depth) = (params.closure, params.closure_depth);
+        let described = run_blocking(move || {
+            let mut response = ruskel.item(
+                &path,
+                options.no_default_features,
+                options.all_features,
+                options.features.clone(),
+                private,
+            )?;
+            if closure {
+                response.rendered = ruskel.render_closure(
+                    &path,
+                    options.no_default_features,
+                    options.all_features,
+                    options.features,
+                    private,
+                    closure_depth,
+                )?;
+            }
+            Ok(response)
+        })
+        .await;
+        let response = match described {
+            Ok(response) => response,
+            Err(e) => {
+                error!("Failed to describe item: {}", e);
+                return Ok(error_result(format!(
+                    "Failed to describe item '{}': {}",
+                    params.path, e
+                )));
+            }
+        };
+
+        let mut text = format!("{} {}", response.kind.label(), response.path);
+        if let Some(location) = &response.location {
+            text.push_str(&format!(" ({location})"));
+        }
+        text.push_str("\n\n");
+        text.push_str(&response.rendered);
+        if !response.impls.is_empty() {
+            text.push_str(&format!(
+                "\n{} trait implementations:\n",
+                response.impls.len()
+            ));
+            for found in &response.impls {
+                text.push_str(&impl_summary_line(found));
+            }
+        }
+
+        Ok(structured_result(
+            text,
+            &GetItemResult {
+                path: response.path,
+                kind: response.kind.label(),
+                signature: response.signature,
+                docs: response.docs,
+                location: response.location.as_ref().map(ToString::to_string),
+                impls: response.impls.iter().map(ImplEntry::of).collect(),
+                rendered: response.rendered,
+            },
+        ))
+    }
+
+    #[tool(read_only, output_schema = RenderModuleResult)]
+    /// **render_module** renders the skeleton of a crate or module: every item with its
+    /// signature and docs, implementations stripped.
+    ///
+    /// # Tips for LLMs
+    /// - Request deep module paths (e.g. `tokio::sync::mpsc`) to reduce output size.
+    /// - Use `crate_overview` first to find the module worth rendering.
+    /// - Pass `private=true` for private items in local codebases, preferably on one module.
+    /// - Pass `max_chars` or `max_items` to split large skeletons into pages at top-level items;
+    ///   each page ends with a summary of what remains and the `cursor` for the next page.
+    async fn render_module(
+        &self,
+        ctx: &ServerCtx,
+        params: RenderModuleTool,
+    ) -> Result<CallToolResult> {
+        let private = params.options.private(self.defaults);
+        let page = match page_request(params.max_chars, params.max_items, params.cursor.as_deref())
+        {
+            Ok(page) => page,
+            Err(error) => return Ok(error_result(error)),
+        };
+        let root = match self.root(ctx, params.options.root.as_deref()).await {
+            Ok(root) => root,
+            Err(result) => return Ok(result),
+        };
+
+        let ruskel = self
+            .ruskel
+            .clone()
+            .with_root(root)
+            .with_frontmatter(params.frontmatter.unwrap_or(self.defaults.frontmatter))
+            .with_bin_target(params.options.bin.clone())
+            .with_source_locations(params.source_locations);
+        let target = params.target.clone();
+        let options = params.options.clone();
+        let rendered = run_blocking(move || match page {
+            Some(page) => ruskel
+                .render_page(
+                    &target,
+                    options.no_default_features,
+                    options.all_features,
+                    options.features,
+                    private,
+                    &page,
+                )
+                .map(|page| {
+                    let position = (PageEntry::of(&page), page_summary(&page, "items"));
+                    (page.rendered, Some(position))
+                }),
+            None => ruskel
+                .render(
+                    &target,
+                    options.no_default_features,
+                    options.all_features,
+                    options.features,
+                    private,
+                )
+                .map(|rendered| (rendered, None)),
+        })
+        .await;
+        match rendered {
+            Ok((rendered, position)) => {
+                let (page, summary) = position.unzip();
+                let result = structured_result(
+                    rendered.clone(),
+                    &RenderModuleResult {
+                        target: params.target,
+                        rendered,
+                        page,
+                    },
+                );
+                Ok(match summary {
+                    Some(summary) => result.with_text_content(summary),
+                    None => result,
+                })
+            }
+            Err(e) => {
+                error!("Failed to generate skeleton: {}", e);
+                Ok(error_result(format!(
+                    "Failed to generate skeleton for '{}': {}",
+                    params.target, e
+                )))
+            }
+        }
+    }
+
+    #[tool(read_only, output_schema = TraitImplsResult)]
+    /// **trait_impls** lists trait implementations, including blanket and auto trait impls,
+    /// followed by the skeleton of the impls written in the crate. Pass exactly one of
+    /// `implementors` (a trait, e.g. `Display`) to list the types implementing it, or
+    /// `traits_of` (a type, e.g. `mycrate::Config`) to list the traits it implements.
+    async fn trait_impls(&self, ctx: &ServerCtx, params: TraitImplsTool) -> Result<CallToolResult> {
+        let private = params.options.private(self.defaults);
+        if let Err(error) = params.query() {
+            return Ok(error_result(error));
+        }
+        let root = match self.root(ctx, params.options.root.as_deref()).await {
+            Ok(root) => root,
+            Err(result) => return Ok(result),
+        };
+
+        let ruskel = self
+            .ruskel
+            .clone()
+            .with_root(root)
+            .with_frontmatter(params.frontmatter.unwrap_or(self.defaults.frontmatter))
+            .with_bin_target(params.options.bin.clone())
+            .with_source_locations(params.source_locations);
+        let target = params.target.clone();
+        let found = run_blocking(move || {
+            let query = params.query().map_err(RuskelError::Generate)?;
+            ruskel.trait_impls(
+                &params.target,
+                params.options.no_default_features,
+                params.options.all_features,
+                params.options.features.clone(),
+                private,
+                query,
+            )
+        })
+        .await;
+        let response = match found {
+            Ok(response) => response,
+            Err(e) => {
+                error!("Failed to find trait implementations: {}", e);
+                return Ok(error_result(format!(
+                    "Failed to find trait implementations in '{}': {}",
+                    target, e
+                )));
+            }
+        };
+
+        let text = if response.impls.is_empty() {
+            "No implementations found.".to_string()
+        } else {
+            let mut text = format!("Found {} implementations:\n", response.impls.len());
+            for found in &response.impls {
+                text.push_str(&impl_summary_line(found));
+            }
+            if !response.rendered.is_empty() {
+                text.push('\n');
+                text.push_str(&response.rendered);
+            }
+            text
+        };
+        Ok(structured_result(
+            text,
+            &TraitImplsResult {
+                target,
+                impls: response.impls.iter().map(ImplEntry::of).collect(),
+                rendered: response.rendered,
+            },
+        ))
+    }
+
+    #[tool(read_only, output_schema = MethodsResult)]
+    /// **methods** lists every method callable on a type: its inherent methods, the methods of
+    /// the traits it implements, and methods reached through `Deref`, each annotated with where
+    /// it comes from.
+    ///
+    /// # Examples
+    /// - `target="std", type_path="String"` →  `String` methods, including those of `str`
+    /// - `target="mycrate", type_path="mycrate::Config"` →  a type in a local crate
+    async fn methods(&self, ctx: &ServerCtx, params: MethodsTool) -> Result<CallToolResult> {
+        let private = params.options.private(self.defaults);
+        let type_path = params.type_path.trim().to_string();
+        if type_path.is_empty() {
+            return Ok(error_result("methods requires a non-empty type_path"));
+        }
+        let root = match self.root(ctx, params.options.root.as_deref()).await {
+            Ok(root) => root,
+            Err(result) => return Ok(result),
+        };
+
+        let ruskel = self
+            .ruskel
+            .clone()
+            .with_root(root)
+            .with_frontmatter(params.frontmatter.unwrap_or(self.defaults.frontmatter))
+            .with_bin_target(params.options.bin.clone())
+            .with_source_locations(params.source_locations);
+        let target = params.target.clone();
+        let queried = type_path.clone();
+        let options = params.options.clone();
+        let collected = run_blocking(move || {
+            ruskel.methods(
+                &target,
+                options.no_default_features,
+                options.all_features,
+                options.features,
+                private,
+                &queried,
+            )
+        })
+        .await;
+        let response = match collected {
+            Ok(response) => response,
+            Err(e) => {
+                error!("Failed to collect methods: {}", e);
+                return Ok(error_result(format!(
+                    "Failed to collect methods of '{}' in '{}': {}",
+                    type_path, params.target, e
+                )));
+            }
+        };
+
+        let text = if response.methods.is_empty() {
+            format!("No methods found for {}.", response.type_path)
+        } else {
+            response.rendered.clone()
+        };
+        Ok(structured_result(
+            text,
+            &MethodsResult {
+                target: params.target,
+                type_path: response.type_path,
+                rendered: response.rendered,
+            },
+        ))
+    }
+
+    #[tool(read_only, output_schema = CrateOverviewResult)]
+    /// **crate_overview** summarizes a crate's layout: each module with the number of structs,
+    /// traits, functions and other items it directly contains. Call it first on an unfamiliar
+    /// crate, then narrow down with `list_items`, `render_module` or `get_item`.
+    async fn crate_overview(
+        &self,
+        ctx: &ServerCtx,
+        params: CrateOverviewTool,
+    ) -> Result<CallToolResult> {
+        let private = params.options.private(self.defaults);
+        let root = match self.root(ctx, params.options.root.as_deref()).await {
+            Ok(root) => root,
+            Err(result) => return Ok(result),
+        };
+
+        let ruskel = self
+            .ruskel
+            .clone()
+            .with_root(root)
+            .with_bin_target(params.options.bin.clone());
+        let target = params.target.clone();
+        let options = params.options.clone();
+        let listed = run_blocking(move || {
+            ruskel.list(
+                &target,
+                options.no_default_features,
+                options.all_features,
+                options.features,
+                &ListOptions::all(private),
+            )
+        })
+        .await;
+        let items = match listed {
+            Ok(items) => items,
+            Err(e) => {
+                error!("Failed to list items: {}", e);
+                return Ok(error_result(format!(
+                    "Failed to summarize '{}': {}",
+                    params.target, e
+                )));
+            }
+        };
 
-    /// Only render direct matches, not expanded containers.
-    #[serde(default)]
-    pub direct_match_only: bool,
+        let modules = summarize_modules(&items);
+        let item_count: usize = modules
+            .iter()
+            .map(|module| module.items.values().sum::<usize>())
+            .sum();
+        let mut text = format!(
+            "{}: {} modules, {} items\n",
+            params.target,
+            modules.len(),
+            item_count
+        );
+        for module in &modules {
+            let counts: Vec<String> = module
+                .items
+                .iter()
+                .map(|(kind, count)| format!("{kind} {count}"))
+                .collect();
+            text.push_str(&format!(" - {}: {}\n", module.path, counts.join(", ")));
+        }
 
-    /// Disable the crate's default Cargo features.
-    #[serde(default)]
-    pub no_default_features: bool,
+        Ok(structured_result(
+            text,
+            &CrateOverviewResult {
+                target: params.target,
+                modules,
+            },
+        ))
+    }
 
-    /// Enable every optional Cargo feature.
-    #[serde(default)]
-    pub all_features: bool,
+    #[tool(read_only, output_schema = DepsResult)]
+    /// **deps** lists the crates the current project builds: its workspace members, then the
+    /// packages they depend on directly, with the resolved version, enabled features, source
+    /// (`registry`, `path` or `git`) and whether their docs are already built. Call it before
+    /// querying a dependency to learn which version the project uses; each entry's `target` can
+    /// be passed straight to the other tools.
+    async fn deps(&self, ctx: &ServerCtx, params: DepsTool) -> Result<CallToolResult> {
+        let root = match self.root(ctx, params.root.as_deref()).await {
+            Ok(root) => root,
+            Err(result) => return Ok(result),
+        };
 
-    /// Exact list of Cargo features to enable (ignored if all_features=true).
-    #[serde(default)]
-    pub features: Vec<String>,
-}
+        let ruskel = self.ruskel.clone().with_root(root);
+        let packages = match run_blocking(move || ruskel.project_packages()).await {
+            Ok(packages) => packages,
+            Err(e) => {
+                error!("Failed to list project packages: {}", e);
+                return Ok(error_result(format!("Failed to list dependencies: {e}")));
+            }
+        };
 
-/// Fully resolved MCP tool parameters after applying server defaults.
-#[derive(Debug, Clone)]
-struct ResolvedRuskelSkeletonTool {
-    /// Target to skeletonize.
-    target: String,
-    /// Whether private items should be included.
-    private: bool,
-    /// Optional query used for search mode.
-    search: Option<String>,
-    /// Optional binary target override.
-    bin: Option<String>,
-    /// Optional list of search domains.
-    search_spec: Option<Vec<String>>,
-    /// Whether rendered output should include frontmatter comments.
-    frontmatter: bool,
-    /// Whether search matching should be case sensitive.
-    search_case_sensitive: bool,
-    /// Whether search results should avoid expanding matched containers.
-    direct_match_only: bool,
-    /// Whether Cargo default features should be disabled.
-    no_default_features: bool,
-    /// Whether all Cargo features should be enabled.
-    all_features: bool,
-    /// Explicit Cargo feature list.
-    features: Vec<String>,
-}
+        let packages: Vec<PackageEntry> = packages.into_iter().map(PackageEntry::from).collect();
+        let text = if packages.is_empty() {
+            "No Cargo project found.".to_string()
+        } else {
+            packages.iter().map(package_line).collect()
+        };
+        Ok(structured_result(text, &DepsResult { packages }))
+    }
 
-impl RuskelSkeletonTool {
-    /// Resolve optional request fields against the server defaults.
-    fn resolve(self, defaults: RuskelServerDefaults) -> ResolvedRuskelSkeletonTool {
-        ResolvedRuskelSkeletonTool {
-            target: self.target,
-            private: self.private.unwrap_or(defaults.private),
-            search: self.search,
-            bin: self.bin,
-            search_spec: self.search_spec,
-            frontmatter: self.frontmatter.unwrap_or(defaults.frontmatter),
-            search_case_sensitive: self.search_case_sensitive,
-            direct_match_only: self.direct_match_only,
-            no_default_features: self.no_default_features,
-            all_features: self.all_features,
-            features: self.features,
+    #[tool(output_schema = InvalidateResult)]
+    /// **invalidate** drops crates from the server's cache of loaded crates, so the next request
+    /// regenerates their documentation. Call it after editing a local crate.
+    ///
+    /// Pass `target` with a local crate path or name to drop only that crate, or omit it to clear
+    /// the whole cache.
+    async fn invalidate(&self, ctx: &ServerCtx, params: InvalidateTool) -> Result<CallToolResult> {
+        let target = params
+            .target
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty());
+        let root = match self.root(ctx, params.root.as_deref()).await {
+            Ok(root) => root,
+            Err(result) => return Ok(result),
+        };
+
+        let ruskel = self.ruskel.clone().with_root(root);
+        let owned_target = target.map(ToString::to_string);
+        match run_blocking(move || ruskel.invalidate(owned_target.as_deref())).await {
+            Ok(dropped) => Ok(structured_result(
+                format!(
+                    "Dropped {dropped} cached {} for {}.",
+                    if dropped == 1 { "crate" } else { "crates" },
+                    target.map_or_else(|| "all targets".to_string(), |t| format!("'{t}'")),
+                ),
+                &InvalidateResult {
+                    target: target.map(ToString::to_string),
+                    dropped,
+                },
+            )),
+            Err(e) => Ok(error_result(format!(
+                "Failed to invalidate '{}': {}",
+                target.unwrap_or_default(),
+                e
+            ))),
         }
     }
-}
 
-#[derive(Clone)]
-/// MCP server implementation that forwards requests to an underlying `Ruskel` instance.
-pub struct RuskelServer {
-    /// Code skeleton renderer shared across tool invocations.
-    ruskel: Ruskel,
-    /// Default request values applied when tool calls omit optional flags.
-    defaults: RuskelServerDefaults,
-}
+    /// Render the skeleton addressed by a `ruskel://` resource URI.
+    async fn read_skeleton(&self, context: &ServerCtx, uri: String) -> Result<ReadResourceResult> {
+        let Some(target) = resource_target(&uri) else {
+            return Err(Error::ResourceNotFound { uri });
+        };
 
-#[mcp_server]
-impl RuskelServer {
-    /// Create a new server wrapper around the provided `Ruskel` renderer.
-    pub fn new(ruskel: Ruskel) -> Self {
-        Self::with_defaults(ruskel, RuskelServerDefaults::default())
+        let defaults = self.defaults;
+        let root = self
+            .roots
+            .select(context, None)
+            .await
+            .map_err(Error::InternalError)?;
+        let ruskel = self
+            .ruskel
+            .clone()
+            .with_root(root)
+            .with_frontmatter(defaults.frontmatter);
+        let rendered_target = target.clone();
+        let skeleton = run_blocking(move || {
+            ruskel.render(&rendered_target, false, false, Vec::new(), defaults.private)
+        })
+        .await
+        .map_err(|e| {
+            error!("Failed to generate skeleton: {}", e);
+            Error::InternalError(format!("Failed to generate skeleton for '{target}': {e}"))
+        })?;
+        Ok(ReadResourceResult::new().with_content(skeleton_contents(&uri, skeleton)))
     }
 
-    /// Create a new server wrapper with explicit request defaults.
-    pub fn with_defaults(ruskel: Ruskel, defaults: RuskelServerDefaults) -> Self {
-        Self { ruskel, defaults }
+    /// The directory a request's targets resolve against: the client root named by `root`, or
+    /// the first one. Fails with an error result when the client has no such root.
+    async fn root(
+        &self,
+        context: &ServerCtx,
+        root: Option<&str>,
+    ) -> StdResult<Option<PathBuf>, CallToolResult> {
+        self.roots.select(context, root).await.map_err(error_result)
     }
 
-    #[tool]
-    /// **ruskel** returns a Rust API skeleton with implementation stripped. Useful for looking up
-    /// signatures, derives, APIs, and doc-comments.
-    ///
-    /// # When a model should call this tool
-    /// 1. It needs to look up a function/trait/struct signature.
-    /// 2. It wants an overview of a public or private API.
-    /// 3. The user asks for examples or docs from a crate.
-    ///
-    /// # Target syntax examples
-    /// - `mycrate::Struct` →  struct in current crate
-    /// - `mycrate::Struct::method` →  method on struct in current crate
-    /// - `std::vec::Vec` →  Vec from std lib
-    /// - `serde` →  latest serde on crates.io
-    /// - `serde@1.0.160` →  specific published version
-    /// - `serde::de::Deserialize` →  narrow to one module/type
-    /// - `/path/to/crate` or `/path/to/crate::submod` →  local workspace paths
-    ///
-    /// # Output format
-    /// Valid Rust code with implementation omitted.
-    ///
-    /// # Tips for LLMs
-    /// - Request deep module paths (e.g. `tokio::sync::mpsc`) to reduce output size.
-    /// - Pass `all_features=true` or `features=[…]` when a symbol is behind a feature gate.
-    /// - Pass `private=true` for private items in local codebases. **Caution:** Avoid using
-    ///   `private=true` on entire crates since output can be extremely large. Prefer targeting
-    ///   specific modules or items.
-    /// - Pass `search="pattern"` to restrict output to matched items.
-    /// - Pass `direct_match_only=true` to show only exact matches.
-    /// - Pass `frontmatter=false` to omit the leading comment block.
-    async fn ruskel(&self, _ctx: &ServerCtx, params: RuskelSkeletonTool) -> Result<CallToolResult> {
-        let params = params.resolve(self.defaults);
-        let search_domains = match resolve_search_domains(params.search_spec.as_deref()) {
-            Ok(domains) => domains,
-            Err(error) => {
-                return Ok(CallToolResult::new()
-                    .with_text_content(error)
-                    .mark_as_error());
-            }
-        };
-
-        if env::var_os("RUSKEL_MCP_TEST_MODE").is_some() {
-            return Ok(run_test_mode(&params));
+    /// A copy of the handlers whose crate loads report to `hook`.
+    fn with_progress(&self, hook: Arc<dyn ProgressHook>) -> Self {
+        Self {
+            ruskel: self.ruskel.clone().with_progress(hook),
+            defaults: self.defaults,
+            roots: self.roots.clone(),
         }
+    }
 
+    /// Run a `ruskel` tool call on the calling thread, dispatching on the requested mode.
+    fn run_skeleton_tool(
+        &self,
+        resolved: &ResolvedRuskelSkeletonTool,
+        search_domains: SearchDomain,
+        item_kinds: HashSet<SearchItemKind>,
+        page: Option<PageRequest>,
+    ) -> CallToolResult {
+        let params = &resolved.params;
         let ruskel = self
             .ruskel
             .clone()
-            .with_frontmatter(params.frontmatter)
-            .with_bin_target(params.bin.clone());
-
-        if let Some(query) = params
-            .search
-            .as_ref()
-            .map(|q| q.trim())
-            .filter(|q| !q.is_empty())
+            .with_frontmatter(resolved.frontmatter)
+            .with_bin_target(params.bin.clone())
+            .with_source_locations(params.source_locations)
+            .with_item_source(params.with_source);
+
+        let methods = params
+            .methods
+            .as_deref()
+            .map(str::trim)
+            .filter(|path| !path.is_empty());
+        if page.is_some()
+            && (params.closure || methods.is_some() || !matches!(params.impl_query(), Ok(None)))
         {
-            return Ok(self.run_search_mode(&ruskel, &params, query, search_domains));
+            return error_result(
+                "max_chars, max_items and cursor apply only to skeletons and searches",
+            );
+        }
+
+        if params.closure {
+            if params.methods.is_some()
+                || !matches!(params.impl_query(), Ok(None))
+                || !matches!(params.search_query(), Ok(None))
+            {
+                return CallToolResult::new()
+                    .with_text_content(
+                        "closure cannot be combined with search, implementors, traits_of or methods",
+                    )
+                    .mark_as_error();
+            }
+            return self.run_closure_mode(&ruskel, resolved);
+        }
+
+        if let Some(type_path) = methods {
+            if !matches!(params.impl_query(), Ok(None))
+                || !matches!(params.search_query(), Ok(None))
+            {
+                return CallToolResult::new()
+                    .with_text_content(
+                        "methods cannot be combined with search, implementors or traits_of",
+                    )
+                    .mark_as_error();
+            }
+            return self.run_methods_mode(&ruskel, resolved, type_path);
+        }
+
+        match (params.impl_query(), params.search_query()) {
+            (Ok(Some(query)), Ok(None)) => return self.run_impls_mode(&ruskel, resolved, query),
+            (Ok(Some(_)), Ok(Some(_))) => {
+                return CallToolResult::new()
+                    .with_text_content("implementors and traits_of cannot be combined with search")
+                    .mark_as_error();
+            }
+            (Err(error), _) => {
+                return CallToolResult::new()
+                    .with_text_content(error)
+                    .mark_as_error();
+            }
+            _ => {}
+        }
+
+        match params.search_query() {
+            Ok(Some((query, mode))) => {
+                let mut options = SearchOptions::configured(
+                    query,
+                    search_domains,
+                    params.search_case_sensitive,
+                    resolved.private,
+                    !params.direct_match_only,
+                );
+                options.mode = mode;
+                options.fuzzy = params.fuzzy;
+                options.ignore_references = params.ignore_references;
+                options.kinds = item_kinds;
+                return self.run_search_mode(&ruskel, params, &options, page);
+            }
+            Ok(None) => {}
+            Err(error) => {
+                return CallToolResult::new()
+                    .with_text_content(error)
+                    .mark_as_error();
+            }
         }
 
-        Ok(self.run_render_mode(&ruskel, &params))
+        if !item_kinds.is_empty() {
+            return CallToolResult::new()
+                .with_text_content("kind can only be used together with search")
+                .mark_as_error();
+        }
+
+        self.run_render_mode(&ruskel, resolved, page)
     }
 
     /// Build the MCP response for search invocations, including match summaries.
+    ///
+    /// Paged searches return one page of matches followed by a summary of the matches left.
     fn run_search_mode(
         &self,
         ruskel: &Ruskel,
-        params: &ResolvedRuskelSkeletonTool,
-        query: &str,
-        domains: SearchDomain,
+        params: &RuskelSkeletonTool,
+        options: &SearchOptions,
+        page: Option<PageRequest>,
     ) -> CallToolResult {
-        let options = SearchOptions::configured(
-            query,
-            domains,
-            params.search_case_sensitive,
-            params.private,
-            !params.direct_match_only,
-        );
+        let query = options.query.as_str();
+        let result = |results: &[SearchResult], total: usize, rendered: String| {
+            let text = search_summary(query, results, total, &rendered);
+            let content = RuskelToolResult {
+                query: Some(query.to_string()),
+                matches: Some(results.iter().map(ItemEntry::matched).collect()),
+                ..RuskelToolResult::rendered(ruskel, params, rendered)
+            };
+            (text, content)
+        };
+        let searched = match page {
+            Some(page) => ruskel
+                .search_page(
+                    &params.target,
+                    params.no_default_features,
+                    params.all_features,
+                    params.features.clone(),
+                    options,
+                    &page,
+                )
+                .map(|page| {
+                    let summary = page_summary(&page, "matches");
+                    let position = PageEntry::of(&page);
+                    let (text, content) = result(&page.items, page.total(), page.rendered);
+                    let content = RuskelToolResult {
+                        page: Some(position),
+                        ..content
+                    };
+                    structured_result(text, &content).with_text_content(summary)
+                }),
+            None => ruskel
+                .search(
+                    &params.target,
+                    params.no_default_features,
+                    params.all_features,
+                    params.features.clone(),
+                    options,
+                )
+                .map(|response| {
+                    let (text, content) =
+                        result(&response.results, response.results.len(), response.rendered);
+                    structured_result(text, &content)
+                }),
+        };
+        match searched {
+            Ok(result) => result,
+            Err(e) => {
+                error!("Failed to generate search results: {}", e);
+                CallToolResult::new()
+                    .with_text_content(format!(
+                        "Failed to search '{}' with query '{}': {}",
+                        params.target, query, e
+                    ))
+                    .mark_as_error()
+            }
+        }
+    }
 
-        match ruskel.search(
+    /// Build the MCP response for trait implementation queries: a listing followed by the
+    /// skeleton of the local impls.
+    fn run_impls_mode(
+        &self,
+        ruskel: &Ruskel,
+        resolved: &ResolvedRuskelSkeletonTool,
+        query: ImplQuery<'_>,
+    ) -> CallToolResult {
+        let params = &resolved.params;
+        match ruskel.trait_impls(
             &params.target,
             params.no_default_features,
             params.all_features,
             params.features.clone(),
-            &options,
+            resolved.private,
+            query,
         ) {
             Ok(response) => {
-                if response.results.is_empty() {
-                    return CallToolResult::new()
-                        .with_text_content(format!("No matches found for \"{}\".", query));
-                }
-
-                let mut summary = String::new();
-                summary.push_str(&format!(
-                    "Found {} matches for \"{}\":\n",
-                    response.results.len(),
-                    query
-                ));
-                for result in &response.results {
-                    let labels = describe_domains(result.matched);
-                    if labels.is_empty() {
-                        summary.push_str(&format!(" - {}\n", result.path_string));
-                    } else {
-                        summary.push_str(&format!(
-                            " - {} [{}]\n",
-                            result.path_string,
-                            labels.join(", ")
-                        ));
+                let summary = if response.impls.is_empty() {
+                    "No implementations found.".to_string()
+                } else {
+                    let mut summary = format!("Found {} implementations:\n", response.impls.len());
+                    for found in &response.impls {
+                        summary.push_str(&impl_summary_line(found));
                     }
-                }
-                summary.push('\n');
-                summary.push_str(&response.rendered);
+                    if !response.rendered.is_empty() {
+                        summary.push('\n');
+                        summary.push_str(&response.rendered);
+                    }
+                    summary
+                };
+                let content = RuskelToolResult {
+                    impls: Some(response.impls.iter().map(ImplEntry::of).collect()),
+                    ..RuskelToolResult::rendered(ruskel, params, response.rendered)
+                };
+                structured_result(summary, &content)
+            }
+            Err(e) => {
+                error!("Failed to find trait implementations: {}", e);
+                CallToolResult::new()
+                    .with_text_content(format!(
+                        "Failed to find trait implementations in '{}': {}",
+                        params.target, e
+                    ))
+                    .mark_as_error()
+            }
+        }
+    }
 
-                CallToolResult::new().with_text_content(summary)
+    /// Build the MCP response for method resolution requests.
+    fn run_methods_mode(
+        &self,
+        ruskel: &Ruskel,
+        resolved: &ResolvedRuskelSkeletonTool,
+        type_path: &str,
+    ) -> CallToolResult {
+        let params = &resolved.params;
+        match ruskel.methods(
+            &params.target,
+            params.no_default_features,
+            params.all_features,
+            params.features.clone(),
+            resolved.private,
+            type_path,
+        ) {
+            Ok(response) => {
+                let text = if response.methods.is_empty() {
+                    format!("No methods found for {}.", response.type_path)
+                } else {
+                    response.rendered.clone()
+                };
+                structured_result(
+                    text,
+                    &RuskelToolResult::rendered(ruskel, params, response.rendered),
+                )
             }
             Err(e) => {
-                error!("Failed to generate search results: {}", e);
+                error!("Failed to collect methods: {}", e);
                 CallToolResult::new()
                     .with_text_content(format!(
-                        "Failed to search '{}' with query '{}': {}",
-                        params.target, query, e
+                        "Failed to collect methods of '{}' in '{}': {}",
+                        type_path, params.target, e
                     ))
                     .mark_as_error()
             }
         }
     }
 
-    /// Build the MCP response for render-only requests.
-    fn run_render_mode(
+    /// Build the MCP response for type closure requests.
+    fn run_closure_mode(
         &self,
         ruskel: &Ruskel,
-        params: &ResolvedRuskelSkeletonTool,
+        resolved: &ResolvedRuskelSkeletonTool,
     ) -> CallToolResult {
-        match ruskel.render(
+        let params = &resolved.params;
+        match ruskel.render_closure(
             &params.target,
             params.no_default_features,
             params.all_features,
             params.features.clone(),
-            params.private,
+            resolved.private,
+            params.closure_depth,
         ) {
-            Ok(output) => CallToolResult::new().with_text_content(output),
+            Ok(output) => structured_result(
+                output.clone(),
+                &RuskelToolResult::rendered(ruskel, params, output),
+            ),
+            Err(e) => {
+                error!("Failed to generate type closure: {}", e);
+                CallToolResult::new()
+                    .with_text_content(format!(
+                        "Failed to generate type closure for '{}': {}",
+                        params.target, e
+                    ))
+                    .mark_as_error()
+            }
+        }
+    }
+
+    /// Build the MCP response for render-only requests.
+    ///
+    /// Paged requests return one page of the skeleton followed by a summary of the items left.
+    fn run_render_mode(
+        &self,
+        ruskel: &Ruskel,
+        resolved: &ResolvedRuskelSkeletonTool,
+        page: Option<PageRequest>,
+    ) -> CallToolResult {
+        let params = &resolved.params;
+        let rendered = match page {
+            Some(page) => ruskel
+                .render_page(
+                    &params.target,
+                    params.no_default_features,
+                    params.all_features,
+                    params.features.clone(),
+                    resolved.private,
+                    &page,
+                )
+                .map(|page| {
+                    let summary = page_summary(&page, "items");
+                    let content = RuskelToolResult {
+                        page: Some(PageEntry::of(&page)),
+                        ..RuskelToolResult::rendered(ruskel, params, page.rendered.clone())
+                    };
+                    structured_result(page.rendered, &content).with_text_content(summary)
+                }),
+            None => ruskel
+                .render(
+                    &params.target,
+                    params.no_default_features,
+                    params.all_features,
+                    params.features.clone(),
+                    resolved.private,
+                )
+                .map(|output| {
+                    structured_result(
+                        output.clone(),
+                        &RuskelToolResult::rendered(ruskel, params, output),
+                    )
+                }),
+        };
+        match rendered {
+            Ok(result) => result,
             Err(e) => {
                 error!("Failed to generate skeleton: {}", e);
                 CallToolResult::new()
@@ -293,6 +2242,143 @@ impl RuskelServer {
     }
 }
 
+/// Summarize search matches, one line per match, followed by the rendered skeleton.
+///
+/// `total` counts the matches of the whole search when `results` is one page of them.
+fn search_summary(query: &str, results: &[SearchResult], total: usize, rendered: &str) -> String {
+    if total == 0 {
+        return format!("No matches found for \"{}\".", query);
+    }
+
+    let mut summary = String::new();
+    if total == results.len() {
+        summary.push_str(&format!("Found {total} matches for \"{query}\":\n"));
+    } else {
+        summary.push_str(&format!(
+            "Found {total} matches for \"{query}\", showing {}:\n",
+            results.len()
+        ));
+    }
+    for result in results {
+        summary.push_str(&format!(" - {}", result.path_string));
+        let labels = describe_domains(result.matched);
+        if !labels.is_empty() {
+            summary.push_str(&format!(" [{}]", labels.join(", ")));
+        }
+        summary.push_str(&format!(" score={}", result.score));
+        if let Some(location) = &result.location {
+            summary.push_str(&format!(" ({location})"));
+        }
+        summary.push('\n');
+    }
+    summary.push('\n');
+    summary.push_str(rendered);
+    summary
+}
+
+/// The crate a `ruskel` tool call loaded, if it can be determined.
+///
+/// Called after the call succeeded, so the crate is usually already cached.
+fn loaded_crate(ruskel: &Ruskel, params: &RuskelSkeletonTool) -> Option<CrateEntry> {
+    ruskel
+        .crate_info(
+            &params.target,
+            params.no_default_features,
+            params.all_features,
+            params.features.clone(),
+        )
+        .ok()
+        .map(CrateEntry::from)
+}
+
+/// The first paragraph of a doc comment, joined onto one line, or `None` when it is blank.
+fn docs_summary(docs: &str) -> Option<String> {
+    let summary = docs
+        .trim()
+        .lines()
+        .map(str::trim)
+        .take_while(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!summary.is_empty()).then_some(summary)
+}
+
+/// Describe a trait implementation on one line, e.g. ` - Display for Widget [impl]`.
+fn impl_summary_line(found: &TraitImpl) -> String {
+    let mut line = format!(
+        " - {} for {} [{}]",
+        found.trait_path,
+        found.type_path,
+        found.origin.label()
+    );
+    if let Some(location) = &found.location {
+        line.push_str(&format!(" ({location})"));
+    }
+    line.push('\n');
+    line
+}
+
+/// Count the items directly inside each module of a listing.
+///
+/// Items nested in types, such as fields and methods, are not counted.
+fn summarize_modules(items: &[ListItem]) -> Vec<ModuleSummary> {
+    let mut modules: Vec<ModuleSummary> = items
+        .iter()
+        .filter(|item| matches!(item.kind, SearchItemKind::Crate | SearchItemKind::Module))
+        .map(|item| ModuleSummary {
+            path: item.path.clone(),
+            items: BTreeMap::new(),
+        })
+        .collect();
+    for item in items {
+        let Some((parent, _)) = item.path.rsplit_once("::") else {
+            continue;
+        };
+        if let Some(module) = modules.iter_mut().find(|module| module.path == parent) {
+            *module.items.entry(item.kind.label()).or_default() += 1;
+        }
+    }
+    modules
+}
+
+/// One line of the readable `deps` result describing `package`.
+fn package_line(package: &PackageEntry) -> String {
+    let origin = if package.member {
+        "member"
+    } else {
+        package.source
+    };
+    let docs = if package.docs_cached {
+        ", docs cached"
+    } else {
+        ""
+    };
+    let mut line = format!("{} {} ({origin}{docs})", package.name, package.version);
+    if !package.features.is_empty() {
+        line.push_str(&format!(" features: {}", package.features.join(", ")));
+    }
+    line.push_str(&format!(" target: {}\n", package.target));
+    line
+}
+
+/// Build a successful tool result carrying readable text and the same data as structured
+/// content.
+fn structured_result(text: String, content: &impl Serialize) -> CallToolResult {
+    match serde_json::to_value(content) {
+        Ok(value) => CallToolResult::new()
+            .with_text_content(text)
+            .with_structured_content(value),
+        Err(e) => error_result(format!("Failed to encode tool result: {e}")),
+    }
+}
+
+/// Build a tool result reporting `message` as an error.
+fn error_result(message: impl Into<String>) -> CallToolResult {
+    CallToolResult::new()
+        .with_text_content(message)
+        .mark_as_error()
+}
+
 /// Resolve search domains from optional MCP parameters, rejecting invalid tokens.
 fn resolve_search_domains(search_spec: Option<&[String]>) -> StdResult<SearchDomain, String> {
     let Some(search_spec) = search_spec else {
@@ -313,42 +2399,53 @@ fn resolve_search_domains(search_spec: Option<&[String]>) -> StdResult<SearchDom
     }
 }
 
-/// Lightweight stubbed response used when the MCP server is started in test mode.
-///
-/// This bypasses expensive rustdoc generation, allowing integration tests to run quickly
-/// while still exercising the MCP protocol surface.
-fn run_test_mode(params: &ResolvedRuskelSkeletonTool) -> CallToolResult {
-    let mut summary = String::new();
-    summary.push_str("ruskel test-mode output\n");
-    summary.push_str(&format!("target: {}\n", params.target));
-    summary.push_str(&format!("private: {}\n", params.private));
-    summary.push_str(&format!("frontmatter: {}\n", params.frontmatter));
-
-    if let Some(search) = &params.search {
-        summary.push_str(&format!("search: {}\n", search));
+/// Resolve item kinds from optional MCP parameters, rejecting invalid tokens.
+fn resolve_item_kinds(kind: Option<&[String]>) -> StdResult<HashSet<SearchItemKind>, String> {
+    let mut kinds = HashSet::new();
+    for token in kind.unwrap_or_default() {
+        kinds.extend(parse_kind_token(token)?.iter().copied());
     }
+    Ok(kinds)
+}
 
-    if let Some(spec) = &params.search_spec
-        && !spec.is_empty()
-    {
-        summary.push_str(&format!("search_spec: {}\n", spec.join(",")));
-    }
+/// Run a blocking ruskel call on tokio's blocking thread pool so it does not stall the runtime.
+async fn run_blocking<T: Send + 'static>(
+    call: impl FnOnce() -> RuskelResult<T> + Send + 'static,
+) -> RuskelResult<T> {
+    task::spawn_blocking(call)
+        .await
+        .unwrap_or_else(|e| Err(RuskelError::Generate(format!("ruskel task failed: {e}"))))
+}
 
-    CallToolResult::new().with_text_content(summary)
+/// How the MCP server talks to its clients.
+#[derive(Debug, Clone, Default)]
+pub enum McpTransport {
+    /// A single client on stdin and stdout.
+    #[default]
+    Stdio,
+    /// Newline-delimited JSON-RPC over TCP, bound to `host:port`.
+    Tcp(String),
+    /// Streamable HTTP bound to `host:port`, serving many clients from one process.
+    Http {
+        /// Address to bind.
+        addr: String,
+        /// Access control for the endpoint.
+        options: HttpOptions,
+    },
 }
 
-/// Serve the ruskel MCP API over TCP or stdio depending on configuration.
+/// Serve the ruskel MCP API over the selected transport.
 ///
-/// When `addr` is provided a TCP listener is started; otherwise the server exposes
-/// stdio pipes suitable for process integration.
+/// Stdio exposes pipes suitable for process integration; the network transports log to stdout
+/// and run until interrupted.
 pub async fn run_mcp_server(
     ruskel: Ruskel,
-    addr: Option<String>,
+    transport: McpTransport,
     log_level: Option<LevelFilter>,
     defaults: RuskelServerDefaults,
 ) -> Result<()> {
-    // Initialize tracing for TCP mode only
-    if addr.is_some() {
+    // Initialize tracing for network transports only
+    if !matches!(transport, McpTransport::Stdio) {
         let level = log_level.unwrap_or(LevelFilter::INFO);
         let filter = format!("ruskel_mcp={level},tmcp={level}");
 
@@ -359,24 +2456,52 @@ pub async fn run_mcp_server(
             .init();
     }
 
-    let server = Server::new(move || RuskelServer::with_defaults(ruskel.clone(), defaults));
+    let ruskel = ruskel.with_crate_cache(CRATE_CACHE_CAPACITY);
 
-    match addr {
-        Some(addr) => {
+    match transport {
+        McpTransport::Stdio => {
+            let server = Server::new(move || RuskelServer::with_defaults(ruskel.clone(), defaults));
+            server.serve_stdio().await
+        }
+        McpTransport::Tcp(addr) => {
+            let server = Server::new(move || RuskelServer::with_defaults(ruskel.clone(), defaults));
             tracing::info!("Starting MCP server on {}", addr);
             let handle = server.serve_tcp(addr).await?;
             ctrl_c().await?;
             handle.stop().await
         }
-        None => server.serve_stdio().await,
+        McpTransport::Http { addr, options } => {
+            let listener = TcpListener::bind(&addr).await?;
+            tracing::info!(
+                "Starting MCP server on http://{}{}",
+                listener.local_addr()?,
+                MCP_PATH
+            );
+            let shutdown = async {
+                if let Err(e) = ctrl_c().await {
+                    error!("Failed to wait for interrupt: {}", e);
+                }
+            };
+            serve_http(ruskel, defaults, options, listener, shutdown).await?;
+            Ok(())
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use libruskel::SearchDomain;
+    use libruskel::{SearchDomain, SearchItemKind};
+
+    use super::{docs_summary, resolve_item_kinds, resolve_search_domains};
 
-    use super::resolve_search_domains;
+    #[test]
+    fn docs_summary_joins_the_first_paragraph() {
+        assert_eq!(
+            docs_summary("\nParses a widget\n  from text.\n\nDetails follow."),
+            Some("Parses a widget from text.".to_string())
+        );
+        assert_eq!(docs_summary("  \n"), None);
+    }
 
     #[test]
     fn resolve_search_domains_defaults_when_missing() {
@@ -396,4 +2521,16 @@ mod tests {
             "invalid search domain 'bogus'. Expected one of: name, doc, path, signature."
         );
     }
+
+    #[test]
+    fn resolve_item_kinds_expands_broad_tokens() {
+        let kinds = resolve_item_kinds(Some(&[String::from("trait"), String::from("method")]))
+            .expect("valid kinds");
+
+        assert_eq!(kinds.len(), 3);
+        assert!(kinds.contains(&SearchItemKind::Trait));
+        assert!(kinds.contains(&SearchItemKind::Method));
+        assert!(kinds.contains(&SearchItemKind::TraitMethod));
+        assert!(resolve_item_kinds(Some(&[String::from("bogus")])).is_err());
+    }
 }
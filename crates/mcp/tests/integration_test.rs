@@ -1,19 +1,31 @@
 //! Integration tests for the MCP server
 //!
-//! These tests verify the MCP server protocol implementation using the tmcp client.
-
-use std::{env, io, sync::OnceLock, time::Duration};
+//! These tests verify the MCP server protocol implementation using the tmcp client, rendering
+//! the fixture crates in `fixtures/` from their checked-in rustdoc JSON.
+
+use std::{
+    future::pending,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
-use libruskel::Ruskel;
-use ruskel_mcp::{RuskelServer, RuskelServerDefaults};
-use tmcp::{Arguments, Client, Result, Server, schema::InitializeResult};
+use async_trait::async_trait;
+use libruskel::{FixtureSource, Ruskel};
+use ruskel_mcp::{HttpOptions, MCP_PATH, RuskelServer, RuskelServerDefaults, serve_http};
+use tmcp::{
+    Arguments, Client, ClientCtx, ClientHandler, Result, Server,
+    schema::{ClientCapabilities, InitializeResult, ListRootsResult, Root},
+};
 use tokio::{
-    io::{duplex, split},
+    io::{DuplexStream, ReadHalf, WriteHalf, duplex, split},
+    net::TcpListener,
     task::JoinHandle,
     time::timeout,
 };
+use url::Url;
 
-static TEST_MODE_ENV: OnceLock<()> = OnceLock::new();
 type ServerTask = JoinHandle<()>;
 
 /// Helper to create a test MCP client connected to an in-process server.
@@ -25,11 +37,86 @@ async fn create_test_client() -> Result<(Client, ServerTask)> {
 async fn create_test_client_with_defaults(
     defaults: RuskelServerDefaults,
 ) -> Result<(Client, ServerTask)> {
-    TEST_MODE_ENV.get_or_init(|| unsafe {
-        env::set_var("RUSKEL_MCP_TEST_MODE", "1");
-    });
+    let (client_reader, client_writer, server_task) = spawn_test_server(defaults);
+    let mut client = Client::new("test-client", "1.0.0");
+    client
+        .connect_stream_raw(client_reader, client_writer)
+        .await?;
 
-    let ruskel = Ruskel::new().with_silent(true);
+    Ok((client, server_task))
+}
+
+/// Client handler answering `roots/list` with a fixed set of roots.
+struct RootsClient(Vec<Root>);
+
+#[async_trait]
+impl ClientHandler for RootsClient {
+    async fn list_roots(&self, _context: &ClientCtx) -> Result<ListRootsResult> {
+        Ok(ListRootsResult {
+            roots: self.0.clone(),
+            _meta: None,
+        })
+    }
+}
+
+/// Helper to create a test MCP client that declares `roots`.
+async fn create_test_client_with_roots(
+    roots: Vec<Root>,
+) -> Result<(Client<RootsClient>, ServerTask)> {
+    let (client_reader, client_writer, server_task) =
+        spawn_test_server(RuskelServerDefaults::default());
+    let mut client = Client::new("test-client", "1.0.0")
+        .with_capabilities(ClientCapabilities::default().with_roots_capability(true))
+        .with_handler(RootsClient(roots));
+    client
+        .connect_stream_raw(client_reader, client_writer)
+        .await?;
+
+    Ok((client, server_task))
+}
+
+/// Directory holding the fixture crates, with their rustdoc JSON in `json/`.
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../fixtures")
+        .canonicalize()
+        .expect("fixtures directory")
+}
+
+/// Target naming `path` within the `widgets` fixture crate, or the whole crate when empty.
+fn widgets(path: &str) -> String {
+    let dir = fixtures_dir().join("widgets");
+    if path.is_empty() {
+        dir.display().to_string()
+    } else {
+        format!("{}::{path}", dir.display())
+    }
+}
+
+/// Client root pointing at the `widgets` fixture crate.
+fn widgets_root() -> Root {
+    Root {
+        uri: Url::from_file_path(fixtures_dir().join("widgets"))
+            .expect("file URI")
+            .to_string(),
+        name: Some("widgets".to_string()),
+        _meta: None,
+    }
+}
+
+/// A renderer reading the fixture JSON instead of building documentation.
+fn fixture_ruskel() -> Ruskel {
+    Ruskel::new()
+        .with_silent(true)
+        .with_offline(true)
+        .with_source(Arc::new(FixtureSource::new(fixtures_dir().join("json"))))
+}
+
+/// Start an in-process server, returning the client's ends of its stream.
+fn spawn_test_server(
+    defaults: RuskelServerDefaults,
+) -> (ReadHalf<DuplexStream>, WriteHalf<DuplexStream>, ServerTask) {
+    let ruskel = fixture_ruskel();
     let server = Server::new(move || RuskelServer::with_defaults(ruskel.clone(), defaults));
 
     let (server_side, client_side) = duplex(64 * 1024);
@@ -42,16 +129,36 @@ async fn create_test_client_with_defaults(
         }
     });
 
-    let mut client = Client::new("test-client", "1.0.0");
-    client
-        .connect_stream_raw(client_reader, client_writer)
-        .await?;
+    (client_reader, client_writer, server_task)
+}
 
-    Ok((client, server_task))
+/// Start a server on a local HTTP port, returning its endpoint URL.
+async fn spawn_http_server(options: HttpOptions) -> io::Result<(String, ServerTask)> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let endpoint = format!("http://{}{}", listener.local_addr()?, MCP_PATH);
+    let ruskel = fixture_ruskel();
+    let server_task = tokio::spawn(async move {
+        let shutdown = pending();
+        if let Err(err) = serve_http(
+            ruskel,
+            RuskelServerDefaults::default(),
+            options,
+            listener,
+            shutdown,
+        )
+        .await
+        {
+            eprintln!("test MCP HTTP server stopped: {err}");
+        }
+    });
+
+    Ok((endpoint, server_task))
 }
 
 /// Initialize the client connection
-async fn initialize_client(client: &mut Client) -> Result<InitializeResult> {
+async fn initialize_client<C: ClientHandler + 'static>(
+    client: &mut Client<C>,
+) -> Result<InitializeResult> {
     client.init().await
 }
 
@@ -68,7 +175,7 @@ async fn terminate_child(child: &mut ServerTask) -> io::Result<()> {
 #[cfg(test)]
 mod tests {
     use serde_json::json;
-    use tmcp::schema::{ContentBlock, LATEST_PROTOCOL_VERSION};
+    use tmcp::schema::{CallToolResult, ContentBlock, LATEST_PROTOCOL_VERSION, ResourceContents};
 
     use super::*;
 
@@ -86,6 +193,8 @@ mod tests {
         // Verify response structure
         assert_eq!(result.protocol_version, LATEST_PROTOCOL_VERSION);
         assert_eq!(result.server_info.name, "ruskel_server");
+        assert!(result.capabilities.tools.is_some());
+        assert!(result.capabilities.resources.is_some());
 
         // Clean up
         terminate_child(&mut child)
@@ -109,10 +218,25 @@ mod tests {
             .expect("Failed to list tools");
 
         // Verify response
-        assert_eq!(result.tools.len(), 1);
-        let tool = &result.tools[0];
-        assert_eq!(tool.name, "ruskel");
-        assert!(tool.description.is_some());
+        let mut names: Vec<&str> = result.tools.iter().map(|tool| tool.name.as_str()).collect();
+        names.sort_unstable();
+        assert_eq!(
+            names,
+            [
+                "crate_overview",
+                "deps",
+                "get_item",
+                "invalidate",
+                "list_items",
+                "methods",
+                "render_module",
+                "ruskel",
+                "search",
+                "trait_impls"
+            ]
+        );
+        assert!(result.tools.iter().all(|tool| tool.description.is_some()));
+        assert!(result.tools.iter().all(|tool| tool.output_schema.is_some()));
 
         // Clean up
         terminate_child(&mut child)
@@ -130,9 +254,8 @@ mod tests {
             .await
             .expect("Failed to initialize");
 
-        // Call tool with a crate that should exist
         let arguments = json!({
-            "target": "serde",
+            "target": widgets(""),
             "private": false
         });
 
@@ -143,7 +266,13 @@ mod tests {
             .expect("Failed to call tool");
 
         // Verify response
-        assert!(!result.content.is_empty());
+        assert_ne!(result.is_error, Some(true));
+        let text = result.text().expect("text content");
+        assert!(text.contains("pub struct Widget {"), "{text}");
+        assert!(!text.contains("mod layout"), "{text}");
+        let structured = result.structured_content.expect("structured content");
+        assert_eq!(structured["crate"]["name"], "widgets");
+        assert_eq!(structured["crate"]["version"], "0.3.1");
 
         // Clean up
         terminate_child(&mut child)
@@ -166,7 +295,7 @@ mod tests {
             .expect("Failed to initialize");
 
         let arguments = json!({
-            "target": "serde"
+            "target": widgets("")
         });
 
         let args = Arguments::from_struct(arguments).expect("invalid arguments struct");
@@ -184,8 +313,11 @@ mod tests {
             })
             .expect("tool response should contain text");
 
-        assert!(text.contains("private: true"));
-        assert!(text.contains("frontmatter: false"));
+        assert!(
+            text.contains("fn columns(count: usize, width: usize)"),
+            "{text}"
+        );
+        assert!(!text.contains("// Ruskel skeleton"), "{text}");
 
         terminate_child(&mut child)
             .await
@@ -271,7 +403,7 @@ mod tests {
             .expect("Failed to initialize");
 
         // Test multiple sequential requests
-        let test_targets = ["serde", "tokio", "async-trait"];
+        let test_targets = [widgets(""), widgets("shapes"), widgets("Widget")];
 
         for target in &test_targets {
             // List tools request
@@ -291,9 +423,9 @@ mod tests {
                 .await
                 .unwrap_or_else(|_| panic!("Timeout for target {target}"));
 
-            if let Ok(call_result) = result {
-                assert!(!call_result.content.is_empty());
-            }
+            let call_result = result.expect("Failed to call tool");
+            assert_ne!(call_result.is_error, Some(true), "{target} failed");
+            assert!(!call_result.content.is_empty());
         }
 
         // Clean up
@@ -360,18 +492,17 @@ mod tests {
 
         // 5. Valid request after another error
         let final_args = json!({
-            "target": "serde",
+            "target": widgets(""),
             "private": false
         });
 
         let args = Arguments::from_struct(final_args).expect("invalid arguments struct");
         let result = timeout(Duration::from_secs(30), client.call_tool("ruskel", args))
             .await
-            .expect("Timeout during final request");
-
-        if let Ok(call_result) = result {
-            assert!(!call_result.content.is_empty());
-        }
+            .expect("Timeout during final request")
+            .expect("Failed to call tool");
+        assert_ne!(result.is_error, Some(true));
+        assert!(!result.content.is_empty());
 
         // Clean up
         terminate_child(&mut child)
@@ -390,8 +521,8 @@ mod tests {
             .expect("Failed to initialize");
 
         let arguments = json!({
-            "target": "serde",
-            "search": "serde",
+            "target": widgets(""),
+            "search": "widget",
             "search_spec": ["bogus"]
         });
 
@@ -414,4 +545,611 @@ mod tests {
             .await
             .expect("Failed to stop MCP server");
     }
+
+    #[tokio::test]
+    async fn test_mcp_server_validates_page_cursors() {
+        let (mut client, mut child) = create_test_client()
+            .await
+            .expect("Failed to create test client");
+
+        let _init_result = initialize_client(&mut client)
+            .await
+            .expect("Failed to initialize");
+
+        let text_of = |result: &CallToolResult| {
+            result
+                .content
+                .iter()
+                .find_map(|content| match content {
+                    ContentBlock::Text(text) => Some(text.text.clone()),
+                    _ => None,
+                })
+                .expect("tool response should contain text")
+        };
+
+        let arguments = json!({
+            "target": widgets(""),
+            "query": "widget",
+            "max_items": 2
+        });
+        let args = Arguments::from_struct(arguments).expect("invalid arguments struct");
+        let result = client
+            .call_tool("search", args)
+            .await
+            .expect("Failed to call tool");
+        assert_ne!(result.is_error, Some(true), "{}", text_of(&result));
+        let page = &result.structured_content.expect("structured content")["page"];
+        assert_eq!(page["start"], 0);
+        assert_eq!(page["items"], 2);
+        let cursor = page["next_cursor"]
+            .as_str()
+            .expect("next cursor")
+            .to_string();
+
+        let arguments = json!({
+            "target": widgets(""),
+            "query": "widget",
+            "max_items": 2,
+            "cursor": cursor
+        });
+        let args = Arguments::from_struct(arguments).expect("invalid arguments struct");
+        let result = client
+            .call_tool("search", args)
+            .await
+            .expect("Failed to call tool");
+        assert_ne!(result.is_error, Some(true), "{}", text_of(&result));
+        let page = &result.structured_content.expect("structured content")["page"];
+        assert_eq!(page["start"], 2);
+
+        let arguments = json!({
+            "target": widgets(""),
+            "max_items": 1
+        });
+        let args = Arguments::from_struct(arguments).expect("invalid arguments struct");
+        let result = client
+            .call_tool("ruskel", args)
+            .await
+            .expect("Failed to call tool");
+        assert_ne!(result.is_error, Some(true), "{}", text_of(&result));
+        let page = &result.structured_content.expect("structured content")["page"];
+        assert_eq!(page["items"], 1);
+        assert!(page["next_cursor"].is_string());
+
+        let arguments = json!({
+            "target": widgets("shapes::area"),
+            "closure": true,
+            "max_items": 1
+        });
+        let args = Arguments::from_struct(arguments).expect("invalid arguments struct");
+        let result = client
+            .call_tool("ruskel", args)
+            .await
+            .expect("Failed to call tool");
+        assert_eq!(result.is_error, Some(true));
+        assert!(text_of(&result).contains("apply only to skeletons and searches"));
+
+        let arguments = json!({
+            "target": widgets(""),
+            "cursor": "40"
+        });
+        let args = Arguments::from_struct(arguments).expect("invalid arguments struct");
+        let result = client
+            .call_tool("render_module", args)
+            .await
+            .expect("Failed to call tool");
+        assert_eq!(result.is_error, Some(true));
+        assert!(text_of(&result).contains("invalid cursor '40'"));
+
+        terminate_child(&mut child)
+            .await
+            .expect("Failed to stop MCP server");
+    }
+
+    #[tokio::test]
+    async fn test_mcp_server_dedicated_tools() {
+        let (mut client, mut child) = create_test_client()
+            .await
+            .expect("Failed to create test client");
+
+        let _init_result = initialize_client(&mut client)
+            .await
+            .expect("Failed to initialize");
+
+        let calls = [
+            (
+                "list_items",
+                json!({ "target": widgets(""), "kind": ["trait"], "private": true }),
+                "widgets::Render",
+            ),
+            (
+                "search",
+                json!({ "target": widgets(""), "query": "render" }),
+                "widgets::render_all",
+            ),
+            (
+                "search",
+                json!({ "target": widgets(""), "query": "Shape", "usage": "consumers" }),
+                "widgets::shapes::area",
+            ),
+            (
+                "get_item",
+                json!({ "path": widgets("WidgetBuilder::build") }),
+                "pub fn build(self) -> Widget",
+            ),
+            (
+                "get_item",
+                json!({ "path": widgets("shapes::area"), "closure": true }),
+                "pub enum Shape {",
+            ),
+            (
+                "trait_impls",
+                json!({ "target": widgets(""), "implementors": "Render" }),
+                "widgets::Widget",
+            ),
+            (
+                "methods",
+                json!({ "target": widgets(""), "type_path": "Widget" }),
+                "pub fn size(&self) -> u32",
+            ),
+            (
+                "render_module",
+                json!({ "target": widgets("shapes"), "all_features": true }),
+                "pub enum Shape {",
+            ),
+            (
+                "crate_overview",
+                json!({ "target": widgets("") }),
+                "widgets::shapes",
+            ),
+            ("invalidate", json!({ "target": widgets("") }), "widgets"),
+        ];
+        for (tool, arguments, expected) in calls {
+            let args = Arguments::from_struct(arguments).expect("invalid arguments struct");
+            let result = timeout(Duration::from_secs(30), client.call_tool(tool, args))
+                .await
+                .expect("Timeout during tool call")
+                .expect("Failed to call tool");
+
+            assert_ne!(result.is_error, Some(true), "{tool} failed");
+            let text = result.text().expect("text content");
+            assert!(text.contains(expected), "{tool}: {text}");
+            assert!(
+                result.structured_content.is_some(),
+                "{tool} lacks structure"
+            );
+        }
+
+        terminate_child(&mut child)
+            .await
+            .expect("Failed to stop MCP server");
+    }
+
+    #[tokio::test]
+    async fn test_mcp_server_legacy_tool_modes() {
+        let (mut client, mut child) = create_test_client()
+            .await
+            .expect("Failed to create test client");
+
+        let _init_result = initialize_client(&mut client)
+            .await
+            .expect("Failed to initialize");
+
+        let calls = [
+            (
+                json!({ "target": widgets(""), "search": "^render", "search_regex": true }),
+                "widgets::render_all",
+            ),
+            (
+                json!({ "target": widgets(""), "consumers": "Shape" }),
+                "widgets::shapes::area",
+            ),
+            (
+                json!({ "target": widgets(""), "implementors": "Render" }),
+                "widgets::Widget",
+            ),
+            (
+                json!({ "target": widgets(""), "methods": "Widget" }),
+                "pub fn size(&self) -> u32",
+            ),
+            (
+                json!({ "target": widgets("shapes::area"), "closure": true }),
+                "pub enum Shape {",
+            ),
+        ];
+        for (arguments, expected) in calls {
+            let args = Arguments::from_struct(arguments).expect("invalid arguments struct");
+            let result = timeout(Duration::from_secs(30), client.call_tool("ruskel", args))
+                .await
+                .expect("Timeout during tool call")
+                .expect("Failed to call tool");
+
+            let text = result.text().expect("text content");
+            assert_ne!(result.is_error, Some(true), "{text}");
+            assert!(text.contains(expected), "{text}");
+        }
+
+        terminate_child(&mut child)
+            .await
+            .expect("Failed to stop MCP server");
+    }
+
+    #[tokio::test]
+    async fn test_mcp_server_deps_tool_lists_project_crates() {
+        let (mut client, mut child) = create_test_client_with_roots(vec![widgets_root()])
+            .await
+            .expect("Failed to create test client");
+
+        let _init_result = initialize_client(&mut client)
+            .await
+            .expect("Failed to initialize");
+
+        let args = Arguments::from_struct(json!({})).expect("invalid arguments struct");
+        let result = timeout(Duration::from_secs(30), client.call_tool("deps", args))
+            .await
+            .expect("Timeout during tool call")
+            .expect("Failed to call tool");
+
+        assert_ne!(result.is_error, Some(true), "{:?}", result.text());
+        let text = result.text().expect("text content");
+        assert!(text.starts_with("widgets 0.3.1 (member"), "{text}");
+        assert!(text.contains("target: widgets"), "{text}");
+
+        let packages = &result.structured_content.expect("structured content")["packages"];
+        assert_eq!(packages.as_array().map(Vec::len), Some(1));
+        let widgets = &packages[0];
+        assert_eq!(widgets["name"], "widgets");
+        assert_eq!(widgets["version"], "0.3.1");
+        assert_eq!(widgets["member"], true);
+        assert_eq!(widgets["source"], "path");
+        assert_eq!(widgets["target"], "widgets");
+        assert!(widgets["docs_cached"].is_boolean());
+
+        terminate_child(&mut child)
+            .await
+            .expect("Failed to stop MCP server");
+    }
+
+    #[tokio::test]
+    async fn test_mcp_server_search_tool_rejects_empty_query() {
+        let (mut client, mut child) = create_test_client()
+            .await
+            .expect("Failed to create test client");
+
+        let _init_result = initialize_client(&mut client)
+            .await
+            .expect("Failed to initialize");
+
+        let args = Arguments::from_struct(json!({ "target": widgets(""), "query": "  " }))
+            .expect("invalid arguments struct");
+        let result = client
+            .call_tool("search", args)
+            .await
+            .expect("Failed to call tool");
+
+        assert_eq!(result.is_error, Some(true));
+        assert_eq!(result.text(), Some("search requires a non-empty query"));
+
+        terminate_child(&mut child)
+            .await
+            .expect("Failed to stop MCP server");
+    }
+
+    #[tokio::test]
+    async fn test_mcp_server_resources() {
+        let (mut client, mut child) = create_test_client_with_roots(vec![widgets_root()])
+            .await
+            .expect("Failed to create test client");
+
+        let _init_result = initialize_client(&mut client)
+            .await
+            .expect("Failed to initialize");
+
+        let templates = client
+            .list_resource_templates(None)
+            .await
+            .expect("Failed to list resource templates");
+        let uris: Vec<&str> = templates
+            .resource_templates
+            .iter()
+            .map(|template| template.uri_template.as_str())
+            .collect();
+        assert_eq!(
+            uris,
+            [
+                "ruskel://{crate}/{path}",
+                "ruskel://{crate}@{version}/{path}"
+            ]
+        );
+
+        let resources = client
+            .list_resources(None)
+            .await
+            .expect("Failed to list resources");
+        let uris: Vec<&str> = resources
+            .resources
+            .iter()
+            .map(|resource| resource.uri.as_str())
+            .collect();
+        assert_eq!(uris, ["ruskel://widgets"]);
+
+        let result = client
+            .resources_read("ruskel://widgets/shapes/Shape")
+            .await
+            .expect("Failed to read resource");
+        let [ResourceContents::Text(contents)] = result.contents.as_slice() else {
+            panic!("expected a single text resource: {:?}", result.contents);
+        };
+        assert_eq!(contents.mime_type.as_deref(), Some("text/x-rust"));
+        assert!(
+            contents.text.contains("pub enum Shape {"),
+            "{}",
+            contents.text
+        );
+        assert!(
+            !contents.text.contains("pub struct Widget"),
+            "{}",
+            contents.text
+        );
+
+        assert!(client.resources_read("ruskel://../secrets").await.is_err());
+
+        terminate_child(&mut child)
+            .await
+            .expect("Failed to stop MCP server");
+    }
+
+    #[tokio::test]
+    async fn test_mcp_server_resolves_targets_against_client_roots() {
+        let empty = tempfile::tempdir().expect("tempdir");
+        let empty_uri = Url::from_file_path(empty.path())
+            .expect("file URI")
+            .to_string();
+        let (mut client, mut child) = create_test_client_with_roots(vec![
+            widgets_root(),
+            Root {
+                uri: empty_uri.clone(),
+                name: Some("empty".to_string()),
+                _meta: None,
+            },
+        ])
+        .await
+        .expect("Failed to create test client");
+
+        let _init_result = initialize_client(&mut client)
+            .await
+            .expect("Failed to initialize");
+
+        let widgets_uri = widgets_root().uri;
+        let calls = [
+            (
+                "render_module",
+                json!({ "target": "." }),
+                "pub struct Widget {",
+            ),
+            (
+                "list_items",
+                json!({ "target": "widgets::shapes", "root": "widgets" }),
+                "widgets::shapes::area",
+            ),
+            (
+                "render_module",
+                json!({ "target": "./", "root": widgets_uri }),
+                "pub mod widgets {",
+            ),
+            ("ruskel", json!({ "target": "./" }), "pub mod widgets {"),
+            (
+                "ruskel",
+                json!({ "target": "./", "root": widgets_uri }),
+                "pub mod widgets {",
+            ),
+        ];
+        for (tool, arguments, expected) in calls {
+            let args = Arguments::from_struct(arguments).expect("invalid arguments struct");
+            let result = timeout(Duration::from_secs(30), client.call_tool(tool, args))
+                .await
+                .expect("Timeout during tool call")
+                .expect("Failed to call tool");
+            let text = result.text().expect("text content");
+            assert_ne!(result.is_error, Some(true), "{tool} failed: {text}");
+            assert!(text.contains(expected), "{tool}: {text}");
+        }
+
+        let args = Arguments::from_struct(json!({ "target": ".", "root": empty_uri }))
+            .expect("invalid arguments struct");
+        let result = timeout(
+            Duration::from_secs(30),
+            client.call_tool("render_module", args),
+        )
+        .await
+        .expect("Timeout during tool call")
+        .expect("Failed to call tool");
+        assert_eq!(result.is_error, Some(true));
+
+        let args = Arguments::from_struct(json!({ "target": ".", "root": "docs" }))
+            .expect("invalid arguments struct");
+        let result = timeout(
+            Duration::from_secs(30),
+            client.call_tool("render_module", args),
+        )
+        .await
+        .expect("Timeout during tool call")
+        .expect("Failed to call tool");
+        assert_eq!(result.is_error, Some(true));
+        assert_eq!(
+            result.text().map(str::to_string),
+            Some(format!(
+                "unknown root 'docs'. Client roots: widgets ({}), empty ({})",
+                widgets(""),
+                empty.path().display()
+            ))
+        );
+
+        terminate_child(&mut child)
+            .await
+            .expect("Failed to stop MCP server");
+    }
+
+    #[tokio::test]
+    async fn test_mcp_http_refuses_open_address_without_token() {
+        let listener = TcpListener::bind("0.0.0.0:0")
+            .await
+            .expect("Failed to bind listener");
+        let result = serve_http(
+            fixture_ruskel(),
+            RuskelServerDefaults::default(),
+            HttpOptions::default(),
+            listener,
+            pending(),
+        )
+        .await;
+
+        let error = result.expect_err("open address without a token should be refused");
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[tokio::test]
+    async fn test_mcp_http_serves_sessions() {
+        let (endpoint, mut server) = spawn_http_server(HttpOptions::default())
+            .await
+            .expect("Failed to start HTTP server");
+
+        for _ in 0..2 {
+            let mut client = Client::new("test-client", "1.0.0");
+            let init = timeout(Duration::from_secs(10), client.connect_http(&endpoint))
+                .await
+                .expect("Timeout connecting")
+                .expect("Failed to connect");
+            assert_eq!(init.server_info.name, "ruskel_server");
+
+            let tools = timeout(Duration::from_secs(10), client.list_tools(None))
+                .await
+                .expect("Timeout listing tools")
+                .expect("Failed to list tools");
+            assert!(tools.tools.iter().any(|tool| tool.name == "ruskel"));
+
+            let args = Arguments::from_struct(json!({ "target": widgets("") }))
+                .expect("invalid arguments struct");
+            let result = timeout(
+                Duration::from_secs(30),
+                client.call_tool("render_module", args),
+            )
+            .await
+            .expect("Timeout during tool call")
+            .expect("Failed to call tool");
+            assert_ne!(result.is_error, Some(true));
+            let text = result.text().expect("text content");
+            assert!(text.contains("pub struct Widget {"), "{text}");
+        }
+
+        terminate_child(&mut server)
+            .await
+            .expect("Failed to stop MCP server");
+    }
+
+    #[tokio::test]
+    async fn test_mcp_http_checks_token_origin_and_session() {
+        let (endpoint, mut server) = spawn_http_server(HttpOptions {
+            token: Some("secret".to_string()),
+            allowed_origins: vec!["http://localhost:3000".to_string()],
+        })
+        .await
+        .expect("Failed to start HTTP server");
+        let http = reqwest::Client::new();
+        let post = |body: serde_json::Value| {
+            http.post(&endpoint)
+                .header("Accept", "application/json, text/event-stream")
+                .json(&body)
+        };
+        let initialize = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": LATEST_PROTOCOL_VERSION,
+                "capabilities": {},
+                "clientInfo": { "name": "test-client", "version": "1.0.0" }
+            }
+        });
+        let response = post(initialize.clone()).send().await.expect("request");
+        assert_eq!(response.status().as_u16(), 401);
+        let response = post(initialize.clone())
+            .bearer_auth("wrong")
+            .send()
+            .await
+            .expect("request");
+        assert_eq!(response.status().as_u16(), 401);
+        let response = post(initialize.clone())
+            .bearer_auth("secret")
+            .header("Origin", "http://attacker.example")
+            .send()
+            .await
+            .expect("request");
+        assert_eq!(response.status().as_u16(), 403);
+
+        let response = post(initialize)
+            .bearer_auth("secret")
+            .header("Origin", "http://localhost:3000")
+            .send()
+            .await
+            .expect("request");
+        assert_eq!(response.status().as_u16(), 200);
+        let session = response
+            .headers()
+            .get("mcp-session-id")
+            .and_then(|value| value.to_str().ok())
+            .expect("session id")
+            .to_string();
+        let body = response.text().await.expect("event stream");
+        assert!(body.starts_with("data: "), "{body}");
+        assert!(body.contains("ruskel_server"), "{body}");
+
+        let list_tools = json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" });
+        let response = post(list_tools.clone())
+            .bearer_auth("secret")
+            .send()
+            .await
+            .expect("request");
+        assert_eq!(response.status().as_u16(), 400);
+        let response = post(list_tools.clone())
+            .bearer_auth("secret")
+            .header("Mcp-Session-Id", "no-such-session")
+            .send()
+            .await
+            .expect("request");
+        assert_eq!(response.status().as_u16(), 404);
+
+        let response = http
+            .post(&endpoint)
+            .header("Accept", "application/json")
+            .bearer_auth("secret")
+            .header("Mcp-Session-Id", &session)
+            .json(&list_tools)
+            .send()
+            .await
+            .expect("request");
+        assert_eq!(response.status().as_u16(), 200);
+        let body: serde_json::Value = response.json().await.expect("JSON response");
+        assert_eq!(body["id"], 2);
+        assert!(body["result"]["tools"].is_array());
+
+        let response = http
+            .delete(&endpoint)
+            .bearer_auth("secret")
+            .header("Mcp-Session-Id", &session)
+            .send()
+            .await
+            .expect("request");
+        assert_eq!(response.status().as_u16(), 204);
+        let response = post(list_tools)
+            .bearer_auth("secret")
+            .header("Mcp-Session-Id", &session)
+            .send()
+            .await
+            .expect("request");
+        assert_eq!(response.status().as_u16(), 404);
+
+        terminate_child(&mut server)
+            .await
+            .expect("Failed to stop MCP server");
+    }
 }
@@ -0,0 +1,152 @@
+//! Optional user configuration file for persistent CLI defaults.
+//!
+//! The file is TOML, read from `$RUSKEL_CONFIG`, `$XDG_CONFIG_HOME/ruskel/config.toml` or
+//! `~/.config/ruskel/config.toml`, in that order. Flags and environment variables take precedence
+//! over values set here.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use clap::{ColorChoice, ValueEnum};
+use libruskel::highlight::ColorDepth;
+use serde::Deserialize;
+
+/// Environment variable naming an explicit configuration file.
+const CONFIG_ENV: &str = "RUSKEL_CONFIG";
+
+/// Settings read from the configuration file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// When to colorize output: auto, always or never.
+    pub color: Option<String>,
+    /// Built-in theme name or path to a `.tmTheme` file.
+    pub theme: Option<String>,
+    /// Terminal color depth: auto, truecolor, 256 or 16.
+    pub color_depth: Option<String>,
+}
+
+impl Config {
+    /// Load the configuration file, returning defaults when none exists.
+    pub fn load() -> Result<Self, String> {
+        match config_path() {
+            Some(path) if path.exists() => Self::from_path(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Parse the configuration file at `path`.
+    fn from_path(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+        Self::parse(&contents).map_err(|err| format!("invalid config {}: {err}", path.display()))
+    }
+
+    /// Parse configuration from TOML text, validating every value.
+    fn parse(contents: &str) -> Result<Self, String> {
+        let config: Self = toml::from_str(contents).map_err(|err| err.to_string())?;
+        config.color_choice()?;
+        config.color_depth()?;
+        Ok(config)
+    }
+
+    /// The configured color choice, if any.
+    pub fn color_choice(&self) -> Result<Option<ColorChoice>, String> {
+        self.color
+            .as_deref()
+            .map(|color| <ColorChoice as ValueEnum>::from_str(color, true))
+            .transpose()
+    }
+
+    /// The configured color depth, if any.
+    pub fn color_depth(&self) -> Result<Option<ColorDepthSetting>, String> {
+        self.color_depth.as_deref().map(str::parse).transpose()
+    }
+}
+
+/// Requested color depth, either detected from the terminal or fixed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorDepthSetting {
+    /// Detect the depth from `COLORTERM` and `TERM`.
+    Auto,
+    /// Always use the given depth.
+    Fixed(ColorDepth),
+}
+
+impl ColorDepthSetting {
+    /// Resolve the setting into a concrete color depth.
+    pub fn resolve(self) -> ColorDepth {
+        match self {
+            Self::Auto => ColorDepth::detect(),
+            Self::Fixed(depth) => depth,
+        }
+    }
+}
+
+impl FromStr for ColorDepthSetting {
+    type Err = String;
+
+    fn from_str(token: &str) -> Result<Self, Self::Err> {
+        if token.eq_ignore_ascii_case("auto") {
+            return Ok(Self::Auto);
+        }
+        token.parse().map(Self::Fixed).map_err(|_| {
+            format!("invalid color depth '{token}'. Expected one of: auto, truecolor, 256, 16.")
+        })
+    }
+}
+
+/// Locate the configuration file, if a candidate location can be determined.
+fn config_path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os(CONFIG_ENV).filter(|path| !path.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::home_dir().map(|home| home.join(".config")))?;
+    Some(base.join("ruskel").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_all_settings() {
+        let config = Config::parse(
+            r#"
+                color = "always"
+                theme = "InspiredGitHub"
+                color_depth = "256"
+            "#,
+        )
+        .expect("config should parse");
+
+        assert_eq!(config.color_choice(), Ok(Some(ColorChoice::Always)));
+        assert_eq!(config.theme.as_deref(), Some("InspiredGitHub"));
+        assert_eq!(
+            config.color_depth(),
+            Ok(Some(ColorDepthSetting::Fixed(ColorDepth::Ansi256)))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_invalid_values() {
+        assert!(Config::parse("colour = \"always\"").is_err());
+        assert!(Config::parse("color = \"sometimes\"").is_err());
+        assert!(Config::parse("color_depth = \"8\"").is_err());
+    }
+
+    #[test]
+    fn empty_config_sets_nothing() {
+        let config = Config::parse("").expect("empty config should parse");
+
+        assert_eq!(config.color_choice(), Ok(None));
+        assert_eq!(config.color_depth(), Ok(None));
+        assert!(config.theme.is_none());
+    }
+}
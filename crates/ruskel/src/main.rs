@@ -1,32 +1,53 @@
 //! Command-line interface for the `ruskel` API skeleton generator.
 
 use std::{
+    collections::HashSet,
     env,
     error::Error,
     io::{self, IsTerminal, Write},
-    process::{self, Command, Stdio},
-    thread,
+    iter,
+    ops::Range,
+    path::{Path, PathBuf},
+    process::{self, Child, ChildStdin, Command, Stdio},
+    sync::Arc,
 };
 
-use clap::{ColorChoice, Parser};
+use clap::{ColorChoice, Parser, Subcommand};
+use config::{ColorDepthSetting, Config};
 use libruskel::{
-    Ruskel, SearchDomain, SearchOptions, highlight, parse_domain_token,
+    FixtureSource, FormatBackend, ImplQuery, ListOptions, PackageInfo, Ruskel, SearchDomain,
+    SearchItemKind, SearchMode, SearchOptions, TypeUsage,
+    highlight::{DEFAULT_THEME, HighlightFormat, Highlighter},
+    parse_domain_token, parse_kind_token,
     toolchain::ensure_nightly_with_docs,
 };
-use ruskel_mcp::RuskelServerDefaults;
+use ruskel_mcp::{HttpOptions, McpTransport, RuskelServerDefaults};
 use shell_words::split;
 use tokio::runtime::Runtime;
 use tracing_subscriber::filter::LevelFilter;
 
+/// Optional configuration file support.
+mod config;
+
 /// Message printed when a search flag is present but contains only whitespace.
 const EMPTY_SEARCH_MESSAGE: &str = "Search query is empty; nothing to do.";
+/// ANSI escape starting a highlighted search match in `--list` output.
+const MATCH_HIGHLIGHT_START: &str = "\x1b[1;31m";
+/// ANSI escape ending a highlighted search match.
+const MATCH_HIGHLIGHT_END: &str = "\x1b[0m";
+/// Environment variable read for the HTTP bearer token when `--http-token` is not given.
+const MCP_TOKEN_ENV: &str = "RUSKEL_MCP_TOKEN";
 /// Error returned when `--mcp` is combined with flags that belong on individual requests.
-const MCP_REQUEST_SCOPED_FLAGS_ERROR: &str = "--mcp can only be used with --auto-impls, --private, --no-frontmatter, --offline, --verbose, --addr, and --log";
+const MCP_REQUEST_SCOPED_FLAGS_ERROR: &str = "--mcp can only be used with --auto-impls, --private, --no-frontmatter, --formatter, --best-effort-format, --offline, --verbose, --json-dir, --addr, --http, --http-token, --allow-origin, and --log";
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 /// Parsed command-line options for the ruskel CLI.
 struct Cli {
+    /// Inspect the project instead of rendering a target
+    #[command(subcommand)]
+    command: Option<ProjectCommand>,
+
     /// Target to generate - a directory, file path, or a module name
     #[arg(default_value = "./")]
     target: String,
@@ -61,6 +82,101 @@ struct Cli {
     #[arg(long, default_value_t = false)]
     search_case_sensitive: bool,
 
+    /// Interpret the search query as a regular expression (also enabled by a `regex:` prefix).
+    #[arg(long, default_value_t = false)]
+    search_regex: bool,
+
+    /// Also match item names within a small edit distance of the search query.
+    #[arg(long, default_value_t = false)]
+    fuzzy: bool,
+
+    /// Interpret the search query as a structured query, e.g. `kind:trait name:Async -deprecated`.
+    /// Values take a `regex:` prefix to match as regular expressions.
+    #[arg(long, default_value_t = false, conflicts_with = "search_regex")]
+    search_structured: bool,
+
+    /// Interpret the search query as a type signature, e.g. `&str -> Result<_, _>`, and match it
+    /// against function inputs and outputs.
+    #[arg(long, default_value_t = false, conflicts_with_all = ["search_regex", "search_structured"])]
+    search_types: bool,
+
+    /// Let references match their referents in type signature searches.
+    #[arg(long, default_value_t = false, requires = "search_types")]
+    ignore_refs: bool,
+
+    /// List or render the functions and methods that return the named type, e.g. `Config` or
+    /// `http::Request`.
+    #[arg(long, value_name = "TYPE", conflicts_with_all = ["search", "consumers"])]
+    producers: Option<String>,
+
+    /// List or render the functions and methods that take the named type as a parameter.
+    #[arg(long, value_name = "TYPE", conflicts_with = "search")]
+    consumers: Option<String>,
+
+    /// List or render every item whose signature, fields, variants or bounds mention the named
+    /// type.
+    #[arg(
+        long,
+        value_name = "TYPE",
+        conflicts_with_all = ["search", "producers", "consumers"]
+    )]
+    references: Option<String>,
+
+    /// List or render every type implementing the named trait, including blanket and auto trait
+    /// impls.
+    #[arg(
+        long,
+        value_name = "TRAIT",
+        conflicts_with_all = ["search", "producers", "consumers", "references", "traits_of"]
+    )]
+    implementors: Option<String>,
+
+    /// List or render every trait implemented by the named type, including blanket and auto trait
+    /// impls.
+    #[arg(
+        long,
+        value_name = "TYPE",
+        conflicts_with_all = ["search", "producers", "consumers", "references"]
+    )]
+    traits_of: Option<String>,
+
+    /// List or render every method callable on the named type, including trait methods and
+    /// methods reached through `Deref`.
+    #[arg(
+        long,
+        value_name = "TYPE",
+        conflicts_with_all = [
+            "search", "producers", "consumers", "references", "implementors", "traits_of"
+        ]
+    )]
+    methods: Option<String>,
+
+    /// Render a path target together with every type of the same crate it mentions,
+    /// transitively.
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with_all = [
+            "search", "producers", "consumers", "references", "implementors", "traits_of",
+            "methods", "list", "raw"
+        ]
+    )]
+    closure: bool,
+
+    /// Limit `--closure` to types within this many hops of the target.
+    #[arg(long, value_name = "DEPTH", requires = "closure")]
+    closure_depth: Option<usize>,
+
+    /// Comma-separated list of item kinds (e.g. trait, fn, struct, method) to restrict listings
+    /// and searches to.
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_name = "KIND[,KIND...]",
+        value_parser = parse_kind_token
+    )]
+    kind: Vec<&'static [SearchItemKind]>,
+
     /// Suppress automatic expansion of matched containers when searching.
     #[arg(long, default_value_t = false)]
     direct_match_only: bool,
@@ -77,6 +193,22 @@ struct Cli {
     #[arg(long, default_value_t = false)]
     no_frontmatter: bool,
 
+    /// Annotate rendered items and listings with their source file and line
+    #[arg(long, default_value_t = false)]
+    source_locations: bool,
+
+    /// Embed the original source body of matched or targeted functions
+    #[arg(long, default_value_t = false)]
+    with_source: bool,
+
+    /// Formatter applied to the rendered skeleton (rustfmt, prettyplease, none)
+    #[arg(long, value_name = "BACKEND", default_value_t = FormatBackend::RustFmt)]
+    formatter: FormatBackend,
+
+    /// Emit the unformatted skeleton with a warning instead of failing when formatting fails
+    #[arg(long, default_value_t = false)]
+    best_effort_format: bool,
+
     /// Disable default features
     #[arg(long, default_value_t = false)]
     no_default_features: bool,
@@ -89,22 +221,39 @@ struct Cli {
     #[arg(long, value_delimiter = ',')]
     features: Vec<String>,
 
-    /// Colorize output
-    #[arg(long, default_value_t = ColorChoice::Auto, env = "RUSKEL_COLOR")]
-    color: ColorChoice,
+    /// Colorize output [default: auto]
+    #[arg(long, env = "RUSKEL_COLOR")]
+    color: Option<ColorChoice>,
+
+    /// Syntax highlighting theme: a built-in theme name or a path to a .tmTheme file
+    /// [default: "Solarized (dark)"]
+    #[arg(long, value_name = "THEME", env = "RUSKEL_THEME")]
+    theme: Option<String>,
+
+    /// Terminal color depth used for highlighting (auto, truecolor, 256, 16) [default: auto]
+    #[arg(long, value_name = "DEPTH", env = "RUSKEL_COLOR_DEPTH")]
+    color_depth: Option<ColorDepthSetting>,
+
+    /// Emit syntax-highlighted HTML instead of terminal escapes
+    #[arg(long, default_value_t = false, conflicts_with = "raw")]
+    html: bool,
 
     /// Disable paging
     #[arg(long, default_value_t = false)]
     no_page: bool,
 
     /// Enable offline mode, ensuring Cargo will not use the network
-    #[arg(long, default_value_t = false)]
+    #[arg(long, default_value_t = false, global = true)]
     offline: bool,
 
     /// Enable verbose mode, showing cargo output while rendering docs
     #[arg(long, default_value_t = false)]
     verbose: bool,
 
+    /// Read pre-generated rustdoc JSON from DIR/<crate>.json instead of building it
+    #[arg(long, value_name = "DIR")]
+    json_dir: Option<PathBuf>,
+
     /// Run as an MCP server on stdout
     #[arg(long, default_value_t = false)]
     mcp: bool,
@@ -113,11 +262,34 @@ struct Cli {
     #[arg(long, requires = "mcp")]
     addr: Option<String>,
 
-    /// Log level for tracing output (only used with --mcp --addr)
-    #[arg(long, requires_all = ["mcp", "addr"])]
+    /// Host:port to serve MCP over streamable HTTP on (requires --mcp)
+    #[arg(long, value_name = "ADDR", requires = "mcp", conflicts_with = "addr")]
+    http: Option<String>,
+
+    /// Bearer token HTTP clients must present; required off loopback (requires --http)
+    #[arg(long, value_name = "TOKEN", requires = "http")]
+    http_token: Option<String>,
+
+    /// Browser origin allowed to call the HTTP server; repeatable (only used with --mcp --http)
+    #[arg(long = "allow-origin", value_name = "ORIGIN", requires = "http")]
+    allow_origins: Vec<String>,
+
+    /// Log level for tracing output (only used with --mcp --addr or --http)
+    #[arg(long, requires = "mcp")]
     log: Option<LevelFilter>,
 }
 
+/// Subcommands that report on a project rather than render a target.
+#[derive(Subcommand)]
+enum ProjectCommand {
+    /// List workspace members and their direct dependencies, with the target that loads each
+    Deps {
+        /// Project directory
+        #[arg(default_value = "./")]
+        path: PathBuf,
+    },
+}
+
 impl Cli {
     /// Resolve the active search domains specified by the CLI flags.
     fn search_domains(&self) -> SearchDomain {
@@ -133,34 +305,140 @@ impl Cli {
         }
     }
 
+    /// Resolve the item kinds selected by `--kind`; empty selects every kind.
+    fn item_kinds(&self) -> HashSet<SearchItemKind> {
+        self.kind
+            .iter()
+            .flat_map(|kinds| kinds.iter().copied())
+            .collect()
+    }
+
+    /// The query driving search mode: the `--search` text, or the type named by `--producers`,
+    /// `--consumers` or `--references`.
+    fn search_query(&self) -> Option<&str> {
+        self.search
+            .as_deref()
+            .or(self.producers.as_deref())
+            .or(self.consumers.as_deref())
+            .or(self.references.as_deref())
+    }
+
+    /// The trait implementation query selected by `--implementors` or `--traits-of`.
+    fn impl_query(&self) -> Option<ImplQuery<'_>> {
+        self.implementors
+            .as_deref()
+            .map(ImplQuery::Implementors)
+            .or(self.traits_of.as_deref().map(ImplQuery::TraitsOf))
+    }
+
+    /// The search mode selected by the usage and search flags.
+    fn search_mode(&self) -> SearchMode {
+        if self.producers.is_some() {
+            SearchMode::Usage(TypeUsage::Producers)
+        } else if self.consumers.is_some() {
+            SearchMode::Usage(TypeUsage::Consumers)
+        } else if self.references.is_some() {
+            SearchMode::Usage(TypeUsage::References)
+        } else if self.search_types {
+            SearchMode::Types
+        } else if self.search_structured {
+            SearchMode::Structured
+        } else if self.search_regex {
+            SearchMode::Regex
+        } else {
+            SearchMode::Literal
+        }
+    }
+
     /// Build search options for a concrete query using the CLI's current flags.
     fn build_search_options(&self, query: &str) -> SearchOptions {
-        SearchOptions::configured(
+        let mut options = SearchOptions::configured(
             query,
             self.search_domains(),
             self.search_case_sensitive,
             self.private,
             !self.direct_match_only,
-        )
+        );
+        options.mode = self.search_mode();
+        options.fuzzy = self.fuzzy;
+        options.ignore_references = self.ignore_refs;
+        options.kinds = self.item_kinds();
+        options
     }
 
     /// Check whether the current CLI invocation uses request-scoped flags.
     fn uses_request_scoped_flags(&self) -> bool {
-        self.target != "./"
+        self.command.is_some()
+            || self.target != "./"
             || self.bin.is_some()
             || self.raw
             || self.list
-            || self.search.is_some()
+            || self.search_query().is_some()
+            || self.impl_query().is_some()
+            || self.methods.is_some()
+            || self.closure
+            || self.closure_depth.is_some()
             || self.search_domains() != SearchDomain::default()
             || self.search_case_sensitive
+            || self.search_regex
+            || self.fuzzy
+            || self.search_structured
+            || self.search_types
+            || self.ignore_refs
+            || !self.kind.is_empty()
             || self.direct_match_only
             || self.no_default_features
             || self.all_features
             || !self.features.is_empty()
-            || !matches!(self.color, ColorChoice::Auto)
+            || !matches!(self.color, None | Some(ColorChoice::Auto))
+            || self.html
+            || self.theme.is_some()
+            || self.color_depth.is_some()
+            || self.source_locations
+            || self.with_source
             || self.no_page
     }
 
+    /// Resolve the highlighter applied to rendered code, or `None` for plain output.
+    ///
+    /// Flags and environment variables take precedence over the configuration file.
+    fn highlighter(&self, config: &Config) -> Result<Option<Highlighter>, Box<dyn Error>> {
+        let theme = self
+            .theme
+            .as_deref()
+            .or(config.theme.as_deref())
+            .unwrap_or(DEFAULT_THEME);
+        if self.html {
+            return Ok(Some(Highlighter::new(theme, HighlightFormat::Html)?));
+        }
+
+        if !self.color_enabled(config)? {
+            return Ok(None);
+        }
+
+        let depth = match self.color_depth {
+            Some(depth) => depth,
+            None => config.color_depth()?.unwrap_or(ColorDepthSetting::Auto),
+        };
+        let format = HighlightFormat::Ansi(depth.resolve());
+        Ok(Some(Highlighter::new(theme, format)?))
+    }
+
+    /// Should terminal output be colorized?
+    ///
+    /// Flags and environment variables take precedence over the configuration file.
+    fn color_enabled(&self, config: &Config) -> Result<bool, Box<dyn Error>> {
+        let color = match self.color {
+            Some(color) => color,
+            None => config.color_choice()?.unwrap_or(ColorChoice::Auto),
+        };
+        Ok(match color {
+            ColorChoice::Never => false,
+            ColorChoice::Always => true,
+            ColorChoice::Auto => io::stdout().is_terminal(),
+        })
+    }
+
     /// Derive the MCP server defaults from the allowed server-scoped flags.
     fn mcp_defaults(&self) -> Result<RuskelServerDefaults, Box<dyn Error>> {
         if self.uses_request_scoped_flags() {
@@ -172,6 +450,27 @@ impl Cli {
             frontmatter: !self.no_frontmatter,
         })
     }
+
+    /// Select the MCP transport from `--addr` and `--http`.
+    fn mcp_transport(&self) -> McpTransport {
+        if let Some(addr) = &self.http {
+            McpTransport::Http {
+                addr: addr.clone(),
+                options: HttpOptions {
+                    token: self
+                        .http_token
+                        .clone()
+                        .or_else(|| env::var(MCP_TOKEN_ENV).ok())
+                        .filter(|token| !token.is_empty()),
+                    allowed_origins: self.allow_origins.clone(),
+                },
+            }
+        } else if let Some(addr) = &self.addr {
+            McpTransport::Tcp(addr.clone())
+        } else {
+            McpTransport::Stdio
+        }
+    }
 }
 
 /// Ensure the nightly toolchain and rust-docs JSON component are present.
@@ -213,32 +512,89 @@ fn search_query_state(query: Option<&str>) -> SearchQuery<'_> {
 
 /// Construct a configured `Ruskel` instance from CLI arguments.
 fn ruskel_from_cli(cli: &Cli) -> Ruskel {
-    Ruskel::new()
+    let ruskel = Ruskel::new()
         .with_offline(cli.offline)
         .with_auto_impls(cli.auto_impls)
         .with_frontmatter(!cli.no_frontmatter)
         .with_silent(!cli.verbose)
         .with_bin_target(cli.bin.clone())
+        .with_format_backend(cli.formatter)
+        .with_best_effort_format(cli.best_effort_format)
+        .with_source_locations(cli.source_locations)
+        .with_item_source(cli.with_source);
+    match &cli.json_dir {
+        Some(dir) => ruskel.with_source(Arc::new(FixtureSource::new(dir))),
+        None => ruskel,
+    }
 }
 
 /// Write generated output either through a pager or directly to stdout.
-fn emit_output(cli: &Cli, output: String) -> Result<(), Box<dyn Error>> {
-    if io::stdout().is_terminal() && !cli.no_page {
-        page_output(output)?;
+fn emit_output(
+    cli: &Cli,
+    output: &str,
+    highlighter: Option<&Highlighter>,
+) -> Result<(), Box<dyn Error>> {
+    stream_output(cli, highlighter, |out| {
+        out.write_all(output.as_bytes())?;
+        Ok(())
+    })
+}
+
+/// Stream output produced by `produce` through a pager or directly to stdout, highlighting it
+/// line by line when a highlighter is supplied.
+///
+/// The pager is only started once the first byte is written, so errors raised before any output
+/// is produced are reported without flashing an empty pager. A closed pipe (for example, the user
+/// quitting the pager early) is not treated as an error.
+fn stream_output(
+    cli: &Cli,
+    highlighter: Option<&Highlighter>,
+    produce: impl FnOnce(&mut dyn Write) -> Result<(), Box<dyn Error>>,
+) -> Result<(), Box<dyn Error>> {
+    let result = if io::stdout().is_terminal() && !cli.no_page {
+        let (pager_command, pager_args) = pager_command_from_env();
+        if is_command_available(&pager_command) {
+            let mut pager = LazyPager::new(pager_command, pager_args);
+            let result = write_stream(&mut pager, highlighter, produce);
+            pager.finish()?;
+            result
+        } else {
+            write_stream(&mut io::stdout().lock(), highlighter, produce)
+        }
     } else {
-        print!("{output}");
-    }
+        write_stream(&mut io::stdout().lock(), highlighter, produce)
+    };
 
-    Ok(())
+    match result {
+        Err(error) if is_broken_pipe(error.as_ref()) => Ok(()),
+        other => other,
+    }
 }
 
-/// Apply syntax highlighting when requested.
-fn highlight_output(output: String, should_highlight: bool) -> Result<String, Box<dyn Error>> {
-    if should_highlight {
-        Ok(highlight::highlight_code(&output)?)
+/// Run `produce` against `out`, wrapping it in the highlighter when one is supplied.
+fn write_stream(
+    out: &mut dyn Write,
+    highlighter: Option<&Highlighter>,
+    produce: impl FnOnce(&mut dyn Write) -> Result<(), Box<dyn Error>>,
+) -> Result<(), Box<dyn Error>> {
+    if let Some(highlighter) = highlighter {
+        let mut writer = highlighter.writer(out)?;
+        produce(&mut writer)?;
+        writer.finish()?;
     } else {
-        Ok(output)
+        produce(out)?;
+        out.flush()?;
     }
+    Ok(())
+}
+
+/// Check whether an error was caused by writing to a closed pipe.
+fn is_broken_pipe(error: &(dyn Error + 'static)) -> bool {
+    iter::successors(Some(error), |&error| error.source()).any(|error| {
+        error
+            .downcast_ref::<io::Error>()
+            .is_some_and(|error| error.kind() == io::ErrorKind::BrokenPipe)
+    })
 }
 
 /// Launch the MCP server variant of ruskel using the provided CLI configuration.
@@ -249,7 +605,7 @@ fn run_mcp(cli: &Cli) -> Result<(), Box<dyn Error>> {
     let runtime = Runtime::new()?;
     runtime.block_on(ruskel_mcp::run_mcp_server(
         ruskel,
-        cli.addr.clone(),
+        cli.mcp_transport(),
         cli.log,
         defaults,
     ))?;
@@ -259,20 +615,26 @@ fn run_mcp(cli: &Cli) -> Result<(), Box<dyn Error>> {
 
 /// Render a skeleton locally and stream it to stdout or a pager.
 fn run_cmdline(cli: &Cli) -> Result<(), Box<dyn Error>> {
-    let should_highlight = match cli.color {
-        ColorChoice::Never => false,
-        ColorChoice::Always => true,
-        ColorChoice::Auto => io::stdout().is_terminal(),
-    };
+    let config = Config::load()?;
+    let highlighter = cli.highlighter(&config)?;
 
     let rs = ruskel_from_cli(cli);
 
+    if let Some(query) = cli.impl_query() {
+        return run_impls(cli, &rs, query, highlighter.as_ref());
+    }
+
+    if let Some(type_path) = &cli.methods {
+        return run_methods(cli, &rs, type_path, highlighter.as_ref());
+    }
+
     if cli.list {
-        return run_list(cli, &rs);
+        let highlight_matches = !cli.html && cli.color_enabled(&config)?;
+        return run_list(cli, &rs, highlight_matches);
     }
 
-    match search_query_state(cli.search.as_deref()) {
-        SearchQuery::Present(query) => return run_search(cli, &rs, query, should_highlight),
+    match search_query_state(cli.search_query()) {
+        SearchQuery::Present(query) => return run_search(cli, &rs, query, highlighter.as_ref()),
         SearchQuery::Empty => {
             println!("{EMPTY_SEARCH_MESSAGE}");
             return Ok(());
@@ -280,35 +642,105 @@ fn run_cmdline(cli: &Cli) -> Result<(), Box<dyn Error>> {
         SearchQuery::Missing => {}
     }
 
-    let output = if cli.raw {
-        rs.raw_json(
+    if !cli.kind.is_empty() {
+        return Err("--kind requires --list or --search".into());
+    }
+
+    if cli.closure {
+        let output = rs.render_closure(
             &cli.target,
             cli.no_default_features,
             cli.all_features,
             cli.features.clone(),
             cli.private,
-        )?
-    } else {
-        rs.render(
+            cli.closure_depth,
+        )?;
+        return emit_output(cli, &output, highlighter.as_ref());
+    }
+
+    if cli.raw {
+        let output = rs.raw_json(
             &cli.target,
             cli.no_default_features,
             cli.all_features,
             cli.features.clone(),
             cli.private,
-        )?
-    };
+        )?;
+        return emit_output(cli, &output, None);
+    }
+
+    stream_output(cli, highlighter.as_ref(), |out| {
+        rs.render_to(
+            &cli.target,
+            cli.no_default_features,
+            cli.all_features,
+            cli.features.clone(),
+            cli.private,
+            out,
+        )?;
+        Ok(())
+    })
+}
+
+/// List the crates of the project containing `path` as an aligned table.
+fn run_deps(cli: &Cli, path: &Path) -> Result<(), Box<dyn Error>> {
+    let packages = ruskel_from_cli(cli)
+        .with_root(Some(path.to_path_buf()))
+        .project_packages()?;
+    if packages.is_empty() {
+        println!("No Cargo project found.");
+        return Ok(());
+    }
 
-    let output = highlight_output(output, should_highlight && !cli.raw)?;
-    emit_output(cli, output)
+    let rows: Vec<[String; 6]> = packages.iter().map(package_row).collect();
+    let header = ["NAME", "VERSION", "SOURCE", "DOCS", "TARGET", "FEATURES"].map(String::from);
+    let mut widths = [0; 6];
+    for row in iter::once(&header).chain(&rows) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let mut buffer = String::new();
+    for row in iter::once(&header).chain(&rows) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        buffer.push_str(line.join("  ").trim_end());
+        buffer.push('\n');
+    }
+
+    emit_output(cli, &buffer, None)
+}
+
+/// The cells of `package` in the `deps` table.
+fn package_row(package: &PackageInfo) -> [String; 6] {
+    [
+        package.name.clone(),
+        package.version.clone(),
+        if package.member {
+            "member".to_string()
+        } else {
+            package.source.label().to_string()
+        },
+        if package.docs_cached { "cached" } else { "-" }.to_string(),
+        package.target.clone(),
+        package.features.join(","),
+    ]
 }
 
 /// Execute the list flow and print a structured item summary.
-fn run_list(cli: &Cli, rs: &Ruskel) -> Result<(), Box<dyn Error>> {
+///
+/// When `highlight_matches` is set, the parts of each path matched by the search query are
+/// emphasized with ANSI escapes.
+fn run_list(cli: &Cli, rs: &Ruskel, highlight_matches: bool) -> Result<(), Box<dyn Error>> {
     if cli.raw {
         return Err("--raw cannot be combined with --list".into());
     }
 
-    let (search_options, query_label) = match search_query_state(cli.search.as_deref()) {
+    let (search_options, query_label) = match search_query_state(cli.search_query()) {
         SearchQuery::Missing => (None, None),
         SearchQuery::Empty => {
             println!("{EMPTY_SEARCH_MESSAGE}");
@@ -317,13 +749,17 @@ fn run_list(cli: &Cli, rs: &Ruskel) -> Result<(), Box<dyn Error>> {
         SearchQuery::Present(query) => (Some(cli.build_search_options(query)), Some(query)),
     };
 
+    let options = ListOptions {
+        include_private: cli.private,
+        kinds: cli.item_kinds(),
+        search: search_options,
+    };
     let listings = rs.list(
         &cli.target,
         cli.no_default_features,
         cli.all_features,
         cli.features.clone(),
-        cli.private,
-        search_options.as_ref(),
+        &options,
     )?;
 
     if listings.is_empty() {
@@ -341,21 +777,69 @@ fn run_list(cli: &Cli, rs: &Ruskel) -> Result<(), Box<dyn Error>> {
         .max()
         .unwrap_or(0);
 
+    let score_width = listings
+        .iter()
+        .filter_map(|entry| entry.score)
+        .map(|score| score.to_string().len())
+        .max()
+        .unwrap_or(0);
+
+    let path_width = if cli.source_locations {
+        listings
+            .iter()
+            .map(|entry| entry.path.len())
+            .max()
+            .unwrap_or(0)
+    } else {
+        0
+    };
+
     let mut buffer = String::new();
     for entry in listings {
         let label = entry.kind.label();
+        let path = if highlight_matches {
+            highlight_ranges(&entry.path, &entry.highlights)
+        } else {
+            entry.path.clone()
+        };
+        let mut line = match entry.score {
+            Some(score) => format!("{score:>score_width$} "),
+            None => String::new(),
+        };
         if label_width > 0 {
-            buffer.push_str(&format!(
-                "{label:<width$} {}\n",
-                entry.path,
-                width = label_width
-            ));
+            line.push_str(&format!("{label:<width$} {path}", width = label_width));
         } else {
-            buffer.push_str(&format!("{label} {}\n", entry.path));
+            line.push_str(&format!("{label} {path}"));
         }
+        if cli.source_locations
+            && let Some(location) = &entry.location
+        {
+            let padding = path_width - entry.path.len();
+            line.push_str(&format!("{:padding$}  {location}", ""));
+        }
+        buffer.push_str(&line);
+        buffer.push('\n');
     }
 
-    emit_output(cli, buffer)
+    emit_output(cli, &buffer, None)
+}
+
+/// Wrap the given byte ranges of `text` in bold red ANSI escapes, as `grep --color` does.
+fn highlight_ranges(text: &str, ranges: &[Range<usize>]) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for range in ranges {
+        if range.start < cursor || range.end > text.len() {
+            continue;
+        }
+        out.push_str(&text[cursor..range.start]);
+        out.push_str(MATCH_HIGHLIGHT_START);
+        out.push_str(&text[range.clone()]);
+        out.push_str(MATCH_HIGHLIGHT_END);
+        cursor = range.end;
+    }
+    out.push_str(&text[cursor..]);
+    out
 }
 
 /// Execute the search flow and print the filtered skeleton to stdout.
@@ -363,7 +847,7 @@ fn run_search(
     cli: &Cli,
     rs: &Ruskel,
     query: &str,
-    should_highlight: bool,
+    highlighter: Option<&Highlighter>,
 ) -> Result<(), Box<dyn Error>> {
     if cli.raw {
         return Err("--raw cannot be combined with --search".into());
@@ -384,8 +868,117 @@ fn run_search(
         return Ok(());
     }
 
-    let output = highlight_output(response.rendered, should_highlight)?;
-    emit_output(cli, output)
+    emit_output(cli, &response.rendered, highlighter)
+}
+
+/// Execute the trait implementation flow, printing either a listing or the focused skeleton.
+fn run_impls(
+    cli: &Cli,
+    rs: &Ruskel,
+    query: ImplQuery<'_>,
+    highlighter: Option<&Highlighter>,
+) -> Result<(), Box<dyn Error>> {
+    if cli.raw {
+        return Err("--raw cannot be combined with --implementors or --traits-of".into());
+    }
+
+    let response = rs.trait_impls(
+        &cli.target,
+        cli.no_default_features,
+        cli.all_features,
+        cli.features.clone(),
+        cli.private,
+        query,
+    )?;
+
+    if response.impls.is_empty() {
+        println!("No implementations found.");
+        return Ok(());
+    }
+
+    if !cli.list {
+        if response.rendered.is_empty() {
+            println!("No local implementations to render; use --list to see them all.");
+            return Ok(());
+        }
+        return emit_output(cli, &response.rendered, highlighter);
+    }
+
+    let label_width = response
+        .impls
+        .iter()
+        .map(|found| found.origin.label().len())
+        .max()
+        .unwrap_or(0);
+    let mut buffer = String::new();
+    for found in &response.impls {
+        let description = format!("{} for {}", found.trait_path, found.type_path);
+        buffer.push_str(&format!(
+            "{:<label_width$} {description}",
+            found.origin.label()
+        ));
+        if cli.source_locations
+            && let Some(location) = &found.location
+        {
+            buffer.push_str(&format!("  {location}"));
+        }
+        buffer.push('\n');
+    }
+
+    emit_output(cli, &buffer, None)
+}
+
+/// Execute the method resolution flow, printing either a listing or the annotated impl block.
+fn run_methods(
+    cli: &Cli,
+    rs: &Ruskel,
+    type_path: &str,
+    highlighter: Option<&Highlighter>,
+) -> Result<(), Box<dyn Error>> {
+    if cli.raw {
+        return Err("--raw cannot be combined with --methods".into());
+    }
+
+    let response = rs.methods(
+        &cli.target,
+        cli.no_default_features,
+        cli.all_features,
+        cli.features.clone(),
+        cli.private,
+        type_path,
+    )?;
+
+    if response.methods.is_empty() {
+        println!("No methods found for {}.", response.type_path);
+        return Ok(());
+    }
+
+    if !cli.list {
+        return emit_output(cli, &response.rendered, highlighter);
+    }
+
+    let name_width = response
+        .methods
+        .iter()
+        .map(|method| method.name.len())
+        .max()
+        .unwrap_or(0);
+    let mut buffer = String::new();
+    for method in &response.methods {
+        buffer.push_str(&format!(
+            "{:<name_width$}  {}",
+            method.name,
+            method.origin()
+        ));
+        if cli.source_locations
+            && let Some(location) = &method.location
+        {
+            buffer.push_str(&format!("  {location}"));
+        }
+        buffer.push('\n');
+    }
+
+    emit_output(cli, &buffer, None)
 }
 
 fn main() {
@@ -393,8 +986,13 @@ fn main() {
 
     let result = if cli.mcp {
         run_mcp(&cli)
+    } else if let Some(ProjectCommand::Deps { path }) = &cli.command {
+        run_deps(&cli, path)
     } else {
-        if let Err(e) = check_nightly_toolchain() {
+        // Pre-generated JSON needs no toolchain.
+        if cli.json_dir.is_none()
+            && let Err(e) = check_nightly_toolchain()
+        {
             eprintln!("{e}");
             process::exit(1);
         }
@@ -445,41 +1043,76 @@ fn pager_command_from_env() -> (String, Vec<String>) {
     }
 }
 
-/// Display the generated content through a pager when available.
-fn page_output(content: String) -> Result<(), Box<dyn Error>> {
-    let (pager_command, pager_args) = pager_command_from_env();
+/// A writer that feeds a pager, spawning it on the first write.
+struct LazyPager {
+    /// Pager executable.
+    command: String,
+    /// Arguments passed to the pager.
+    args: Vec<String>,
+    /// Running pager process and its stdin, once started.
+    child: Option<(Child, ChildStdin)>,
+}
 
-    if !is_command_available(&pager_command) {
-        println!("{content}");
-        return Ok(());
+impl LazyPager {
+    /// Prepare a pager that will be launched with `command` and `args` on first write.
+    fn new(command: String, args: Vec<String>) -> Self {
+        Self {
+            command,
+            args,
+            child: None,
+        }
     }
 
-    let mut command = Command::new(&pager_command);
-    command.args(&pager_args);
-    command.stdin(Stdio::piped());
-
-    let mut child = command.spawn()?;
-
-    let mut stdin = child
-        .stdin
-        .take()
-        .ok_or_else(|| io::Error::other("Failed to open stdin for pager"))?;
+    /// Return the pager's stdin, starting the pager if necessary.
+    fn stdin(&mut self) -> io::Result<&mut ChildStdin> {
+        if self.child.is_none() {
+            let mut child = Command::new(&self.command)
+                .args(&self.args)
+                .stdin(Stdio::piped())
+                .spawn()?;
+            let stdin = child
+                .stdin
+                .take()
+                .ok_or_else(|| io::Error::other("Failed to open stdin for pager"))?;
+            self.child = Some((child, stdin));
+        }
+        match &mut self.child {
+            Some((_, stdin)) => Ok(stdin),
+            None => unreachable!("pager was started above"),
+        }
+    }
 
-    thread::spawn(move || {
-        stdin.write_all(content.as_bytes()).ok();
+    /// Close the pager's input and wait for the user to exit it.
+    fn finish(self) -> Result<(), Box<dyn Error>> {
+        let Some((mut child, stdin)) = self.child else {
+            return Ok(());
+        };
         drop(stdin);
-    });
 
-    match child.wait() {
-        Ok(status) => {
-            if !status.success() {
-                eprintln!("Pager exited with non-zero status: {status}");
+        match child.wait() {
+            Ok(status) => {
+                if !status.success() {
+                    eprintln!("Pager exited with non-zero status: {status}");
+                }
+                Ok(())
             }
-            Ok(())
+            Err(error) => Err(Box::new(io::Error::other(format!(
+                "Failed to wait for pager: {error}"
+            )))),
+        }
+    }
+}
+
+impl Write for LazyPager {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.stdin()?.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.child {
+            Some((_, stdin)) => stdin.flush(),
+            None => Ok(()),
         }
-        Err(error) => Err(Box::new(io::Error::other(format!(
-            "Failed to wait for pager: {error}"
-        )))),
     }
 }
 
@@ -519,6 +1152,40 @@ mod tests {
         assert_eq!(error.to_string(), MCP_REQUEST_SCOPED_FLAGS_ERROR);
     }
 
+    #[test]
+    fn mcp_transport_follows_address_flags() {
+        assert!(matches!(
+            parse_cli(&["ruskel", "--mcp"]).mcp_transport(),
+            McpTransport::Stdio
+        ));
+        assert!(matches!(
+            parse_cli(&["ruskel", "--mcp", "--addr", "127.0.0.1:8000"]).mcp_transport(),
+            McpTransport::Tcp(addr) if addr == "127.0.0.1:8000"
+        ));
+
+        let cli = parse_cli(&[
+            "ruskel",
+            "--mcp",
+            "--http",
+            "0.0.0.0:8000",
+            "--http-token",
+            "secret",
+            "--allow-origin",
+            "http://localhost:3000",
+        ]);
+        cli.mcp_defaults().expect("HTTP flags are server-scoped");
+        let McpTransport::Http { addr, options } = cli.mcp_transport() else {
+            panic!("expected the HTTP transport");
+        };
+        assert_eq!(addr, "0.0.0.0:8000");
+        assert_eq!(options.token.as_deref(), Some("secret"));
+        assert_eq!(options.allowed_origins, ["http://localhost:3000"]);
+
+        assert!(
+            Cli::try_parse_from(["ruskel", "--mcp", "--http", "a:1", "--addr", "b:2"]).is_err()
+        );
+    }
+
     #[test]
     fn search_domains_fold_selected_flags() {
         let cli = parse_cli(&["ruskel", "--search-spec", "name,path"]);
@@ -533,4 +1200,54 @@ mod tests {
         let cli = parse_cli(&["ruskel", "--mcp", "--search-case-sensitive"]);
         assert!(cli.uses_request_scoped_flags());
     }
+
+    #[test]
+    fn request_scoped_flag_detection_tracks_highlighting_options() {
+        let cli = parse_cli(&["ruskel", "--mcp", "--theme", "InspiredGitHub"]);
+        assert!(cli.uses_request_scoped_flags());
+
+        let cli = parse_cli(&["ruskel", "--mcp", "--color-depth", "16"]);
+        assert!(cli.uses_request_scoped_flags());
+    }
+
+    #[test]
+    fn deps_parses_as_a_subcommand() {
+        let cli = parse_cli(&["ruskel", "deps", "--offline"]);
+        assert_eq!(cli.target, "./");
+        assert!(cli.offline);
+        assert!(matches!(
+            &cli.command,
+            Some(ProjectCommand::Deps { path }) if path == Path::new("./")
+        ));
+
+        let cli = parse_cli(&["ruskel", "--mcp", "deps"]);
+        assert!(cli.uses_request_scoped_flags());
+    }
+
+    #[test]
+    fn highlighter_flags_override_config_file() {
+        let config = Config {
+            color: Some("always".into()),
+            theme: Some("no-such-theme".into()),
+            color_depth: None,
+        };
+
+        let cli = parse_cli(&["ruskel", "--color", "never"]);
+        assert!(cli.highlighter(&config).expect("never disables").is_none());
+
+        let cli = parse_cli(&["ruskel", "--theme", "InspiredGitHub", "--color-depth", "16"]);
+        assert!(cli.highlighter(&config).expect("theme flag wins").is_some());
+
+        let cli = parse_cli(&["ruskel"]);
+        assert!(cli.highlighter(&config).is_err());
+    }
+
+    #[test]
+    fn highlight_ranges_wraps_each_match() {
+        assert_eq!(
+            highlight_ranges("crate::try_open", &[7..11, 12..14]),
+            "crate::\x1b[1;31mtry_\x1b[0mo\x1b[1;31mpe\x1b[0mn"
+        );
+        assert_eq!(highlight_ranges("crate::open", &[]), "crate::open");
+    }
 }
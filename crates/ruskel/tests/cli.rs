@@ -1,11 +1,33 @@
-//! CLI integration tests for ruskel's top-level flag validation.
+//! CLI integration tests for ruskel's top-level flag validation, and for rendering the fixture
+//! crates in `fixtures/` from their checked-in rustdoc JSON.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 use assert_cmd::Command;
 use predicates::str::contains;
 
+/// Directory holding the fixture crates, with their rustdoc JSON in `json/`.
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../../fixtures")
+}
+
+/// A `ruskel` command reading the fixture JSON, with paging, colour and configuration disabled.
+fn fixture_command() -> Command {
+    let mut command = Command::cargo_bin("ruskel").expect("binary should build");
+    command
+        .env("RUSKEL_CONFIG", "/nonexistent/ruskel.toml")
+        .arg("--json-dir")
+        .arg(fixtures_dir().join("json"))
+        .args(["--no-page", "--color", "never", "--offline"]);
+    command
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{Command, contains};
+    use super::{Command, contains, fixture_command, fixtures_dir, fs};
 
     #[test]
     fn mcp_rejects_search_query_flags() {
@@ -16,7 +38,7 @@ mod tests {
             .assert()
             .failure()
             .stderr(contains(
-                "--mcp can only be used with --auto-impls, --private, --no-frontmatter, --offline, --verbose, --addr, and --log",
+                "--mcp can only be used with --auto-impls, --private, --no-frontmatter, --formatter, --best-effort-format, --offline, --verbose, --json-dir, --addr, --http, --http-token, --allow-origin, and --log",
             ));
     }
 
@@ -29,7 +51,137 @@ mod tests {
             .assert()
             .failure()
             .stderr(contains(
-                "--mcp can only be used with --auto-impls, --private, --no-frontmatter, --offline, --verbose, --addr, and --log",
+                "--mcp can only be used with --auto-impls, --private, --no-frontmatter, --formatter, --best-effort-format, --offline, --verbose, --json-dir, --addr, --http, --http-token, --allow-origin, and --log",
             ));
     }
+
+    #[test]
+    fn invalid_config_file_is_reported() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "colour = \"always\"\n").expect("write config");
+
+        let mut command = Command::cargo_bin("ruskel").expect("binary should build");
+        command.env("RUSKEL_CONFIG", &path).arg("--no-page");
+
+        command
+            .assert()
+            .failure()
+            .stderr(contains("invalid config"));
+    }
+
+    #[test]
+    fn unknown_theme_is_rejected() {
+        let mut command = Command::cargo_bin("ruskel").expect("binary should build");
+        command
+            .env("RUSKEL_CONFIG", "/nonexistent/ruskel.toml")
+            .args(["--html", "--theme", "no-such-theme", "--no-page"]);
+
+        command
+            .assert()
+            .failure()
+            .stderr(contains("unknown theme 'no-such-theme'"));
+    }
+
+    #[test]
+    fn unknown_item_kind_is_rejected() {
+        let mut command = Command::cargo_bin("ruskel").expect("binary should build");
+        command.args(["--list", "--kind", "trait,widget"]);
+
+        command
+            .assert()
+            .failure()
+            .stderr(contains("invalid item kind 'widget'"));
+    }
+
+    #[test]
+    fn type_search_conflicts_with_regex() {
+        let mut command = Command::cargo_bin("ruskel").expect("binary should build");
+        command.args(["--search", "u8 -> u8", "--search-types", "--search-regex"]);
+
+        command
+            .assert()
+            .failure()
+            .stderr(contains("cannot be used with"));
+    }
+
+    #[test]
+    fn implementors_conflict_with_search() {
+        let mut command = Command::cargo_bin("ruskel").expect("binary should build");
+        command.args(["--implementors", "Display", "--search", "widget"]);
+
+        command
+            .assert()
+            .failure()
+            .stderr(contains("cannot be used with"));
+    }
+
+    #[test]
+    fn http_token_requires_http() {
+        let mut command = Command::cargo_bin("ruskel").expect("binary should build");
+        command.args(["--mcp", "--http-token", "secret"]);
+
+        command.assert().failure().stderr(contains("--http <ADDR>"));
+    }
+
+    #[test]
+    fn mcp_token_variable_is_ignored_without_http() {
+        let mut command = fixture_command();
+        command
+            .env("RUSKEL_MCP_TOKEN", "secret")
+            .arg(fixtures_dir().join("widgets"));
+
+        command
+            .assert()
+            .success()
+            .stdout(contains("pub struct WidgetBuilder {"));
+    }
+
+    #[test]
+    fn fixture_json_renders_skeletons() {
+        let mut command = fixture_command();
+        command.arg(fixtures_dir().join("widgets"));
+
+        command
+            .assert()
+            .success()
+            .stdout(contains("pub struct WidgetBuilder {"))
+            .stdout(contains("pub fn area(shape: &Shape) -> f64 {}"));
+    }
+
+    #[test]
+    fn fixture_json_lists_and_searches_items() {
+        let widgets = fixtures_dir().join("widgets");
+
+        let mut command = fixture_command();
+        command.arg(&widgets).args(["--list", "--kind", "trait"]);
+        command.assert().success().stdout("trait widgets::Render\n");
+
+        let mut command = fixture_command();
+        command.current_dir(&widgets).args([
+            "widgets",
+            "--search",
+            "area",
+            "--search-spec",
+            "name",
+        ]);
+        command
+            .assert()
+            .success()
+            .stdout(contains("//   - widgets::shapes::area [name]"))
+            .stdout(contains("pub fn area(shape: &Shape) -> f64 {}"));
+    }
+
+    #[test]
+    fn deps_lists_project_crates_with_targets() {
+        let mut command = fixture_command();
+        command.arg("deps").arg(fixtures_dir().join("widgets"));
+
+        command
+            .assert()
+            .success()
+            .stdout(contains("NAME     VERSION  SOURCE  DOCS"))
+            .stdout(contains("widgets  0.3.1    member"))
+            .stdout(contains("  widgets\n"));
+    }
 }
@@ -0,0 +1,167 @@
+//! A small widget toolkit used as a test fixture for ruskel.
+//!
+//! The checked-in rustdoc JSON for this crate lives in `fixtures/json/widgets.json`; regenerate
+//! it with `cargo xtask gen-fixtures` after editing this file.
+
+use std::fmt;
+
+/// Geometric shapes and measurements.
+pub mod shapes {
+    /// A shape a widget can take.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Shape {
+        /// A circle with the given radius.
+        Circle(f64),
+        /// A square with sides of the given length.
+        Square {
+            /// Length of each side.
+            side: f64,
+        },
+    }
+
+    /// The area covered by `shape`.
+    pub fn area(shape: &Shape) -> f64 {
+        match shape {
+            Shape::Circle(radius) => std::f64::consts::PI * radius * radius,
+            Shape::Square { side } => side * side,
+        }
+    }
+}
+
+/// Settings shared by all widgets.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    /// Whether widgets draw a border.
+    pub border: bool,
+    /// Padding around widget contents, in cells.
+    pub padding: u16,
+}
+
+/// A named, sized widget.
+#[derive(Debug, Clone)]
+pub struct Widget {
+    /// Display name of the widget.
+    pub name: String,
+    /// Size in cells.
+    size: u32,
+    /// Shape the widget is drawn as.
+    shape: shapes::Shape,
+}
+
+impl Widget {
+    /// Create a square widget of size one.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            size: 1,
+            shape: shapes::Shape::Square { side: 1.0 },
+        }
+    }
+
+    /// Size of the widget in cells.
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// The widget's shape.
+    pub fn shape(&self) -> &shapes::Shape {
+        &self.shape
+    }
+
+    /// Grow the widget by `amount` cells.
+    fn grow(&mut self, amount: u32) {
+        self.size += amount;
+    }
+}
+
+/// Things that can be drawn as text.
+pub trait Render {
+    /// Draw `self` as text.
+    fn render(&self) -> String;
+
+    /// Draw `self` within `width` columns.
+    fn render_width(&self, width: usize) -> String {
+        let mut text = self.render();
+        text.truncate(width);
+        text
+    }
+}
+
+impl Render for Widget {
+    fn render(&self) -> String {
+        format!("[{}]", self.name)
+    }
+}
+
+impl fmt::Display for Widget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render())
+    }
+}
+
+/// Builds widgets step by step.
+#[derive(Debug, Default)]
+pub struct WidgetBuilder {
+    /// Name of the widget being built.
+    name: String,
+    /// Size of the widget being built.
+    size: u32,
+    /// Shape of the widget being built, if chosen.
+    shape: Option<shapes::Shape>,
+}
+
+impl WidgetBuilder {
+    /// Start building a widget called `name`.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            size: 1,
+            shape: None,
+        }
+    }
+
+    /// Set the widget's size.
+    pub fn size(mut self, size: u32) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Set the widget's shape.
+    pub fn shape(mut self, shape: shapes::Shape) -> Self {
+        self.shape = Some(shape);
+        self
+    }
+
+    /// Finish building the widget.
+    pub fn build(self) -> Widget {
+        let mut widget = Widget::new(self.name);
+        if let Some(shape) = self.shape {
+            widget.shape = shape;
+        }
+        widget.grow(self.size - 1);
+        widget
+    }
+}
+
+/// Render every widget in `widgets`, one per line, using `config`.
+pub fn render_all(widgets: &[Widget], config: &Config) -> String {
+    let padding = " ".repeat(usize::from(config.padding));
+    widgets
+        .iter()
+        .map(|widget| format!("{padding}{}", widget.render()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Helpers that are not part of the public API.
+mod layout {
+    /// Columns needed to lay out `count` widgets of width `width`.
+    pub(crate) fn columns(count: usize, width: usize) -> usize {
+        count * width
+    }
+}
+
+/// Total columns needed to lay `widgets` out side by side.
+pub fn total_width(widgets: &[Widget]) -> usize {
+    layout::columns(widgets.len(), 8)
+}
@@ -3,7 +3,7 @@
 use std::{collections::HashMap, error::Error, fs, path::PathBuf};
 
 use clap::{Parser, Subcommand};
-use libruskel::toolchain::nightly_sysroot;
+use libruskel::{Ruskel, toolchain::nightly_sysroot};
 use rustdoc_types::{Crate, ItemEnum, Visibility};
 
 #[derive(Parser)]
@@ -25,6 +25,8 @@ enum Commands {
         #[arg(short, long)]
         write: bool,
     },
+    /// Regenerate the rustdoc JSON of the fixture crates under `fixtures/`
+    GenFixtures,
 }
 
 /// Run the CLI and dispatch to the selected subcommand.
@@ -33,6 +35,7 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     match cli.command {
         Commands::GenStdMapping { write } => generate_std_mapping(write),
+        Commands::GenFixtures => generate_fixtures(),
     }
 }
 
@@ -315,3 +318,32 @@ fn generate_std_mapping(write: bool) -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+/// Document each fixture crate, private items included, into `fixtures/json/<crate>.json`.
+fn generate_fixtures() -> Result<(), Box<dyn Error>> {
+    let fixtures = PathBuf::from("fixtures");
+    let mut crate_dirs: Vec<PathBuf> = fs::read_dir(&fixtures)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    crate_dirs.retain(|dir| dir.join("Cargo.toml").exists());
+    crate_dirs.sort();
+
+    let ruskel = Ruskel::new().with_silent(true);
+    for dir in crate_dirs {
+        let target = format!("./{}", dir.display());
+        let crate_data = ruskel.inspect(&target, false, false, Vec::new(), true)?;
+        let name = crate_data
+            .index
+            .get(&crate_data.root)
+            .and_then(|root| root.name.clone())
+            .ok_or_else(|| format!("{target} has no crate root"))?;
+
+        // Going through `Value` sorts the item index, so regenerating only changes what changed.
+        let json = serde_json::to_string(&serde_json::to_value(&crate_data)?)?;
+        let json_path = fixtures.join("json").join(format!("{name}.json"));
+        fs::write(&json_path, json)?;
+        eprintln!("Updated {}", json_path.display());
+    }
+
+    Ok(())
+}